@@ -7,9 +7,27 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 //
 
+use std::env;
+
 fn main() {
     let ac = autocfg::new();
     ac.emit_has_type("u128");
+
+    // core::simd is only available on nightly compilers.
+    // The simd feature falls back to the scalar implementation otherwise.
+    autocfg::emit_possibility("has_portable_simd");
+    if env::var_os("CARGO_FEATURE_SIMD").is_some()
+        && ac.probe_raw("#![feature(portable_simd)]\n\
+                         pub fn probe(y: core::simd::Simd<u32, 4>) -> core::simd::Simd<u32, 4> { y << 13 }\n").is_ok()
+    {
+        autocfg::emit("has_portable_simd");
+    }
+
+    // ARMv6-M (Cortex-M0 and Cortex-M0+) Thumb-1 assembly implementation.
+    println!("cargo:rustc-check-cfg=cfg(armv6m)");
+    if env::var("TARGET").unwrap_or_default().starts_with("thumbv6m-") {
+        println!("cargo:rustc-cfg=armv6m");
+    }
     autocfg::rerun_path("build.rs");
 }
 
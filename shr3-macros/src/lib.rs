@@ -0,0 +1,351 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Procedural macros for the `shr3` crate.
+//!
+//! Do not use this crate directly.
+//! Enable the `macros` feature of the `shr3` crate instead.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Literal, Span, TokenStream as TokenStream2};
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    parse_quote,
+    Data,
+    DeriveInput,
+    Expr,
+    Fields,
+    Ident,
+    LitInt,
+    Token,
+    WherePredicate,
+};
+
+/// Element types supported by `shr3_table!`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ElemType {
+    U8, U16, U32, U64, U128,
+    I8, I16, I32, I64, I128,
+}
+
+impl ElemType {
+    fn from_ident(ident: &Ident) -> syn::Result<ElemType> {
+        Ok(match ident.to_string().as_str() {
+            "u8" => ElemType::U8,
+            "u16" => ElemType::U16,
+            "u32" => ElemType::U32,
+            "u64" => ElemType::U64,
+            "u128" => ElemType::U128,
+            "i8" => ElemType::I8,
+            "i16" => ElemType::I16,
+            "i32" => ElemType::I32,
+            "i64" => ElemType::I64,
+            "i128" => ElemType::I128,
+            _ => {
+                return Err(syn::Error::new(ident.span(),
+                    "shr3_table!: Unsupported element type. \
+                     Expected one of u8, u16, u32, u64, u128, i8, i16, i32, i64 or i128."));
+            }
+        })
+    }
+
+    /// Number of bits in the type.
+    fn numbits(&self) -> u8 {
+        match self {
+            ElemType::U8 | ElemType::I8 => 8,
+            ElemType::U16 | ElemType::I16 => 16,
+            ElemType::U32 | ElemType::I32 => 32,
+            ElemType::U64 | ElemType::I64 => 64,
+            ElemType::U128 | ElemType::I128 => 128,
+        }
+    }
+
+    /// The unsigned type with the same number of bits.
+    fn unsigned(&self) -> Ident {
+        let name = match self {
+            ElemType::U8 | ElemType::I8 => "u8",
+            ElemType::U16 | ElemType::I16 => "u16",
+            ElemType::U32 | ElemType::I32 => "u32",
+            ElemType::U64 | ElemType::I64 => "u64",
+            ElemType::U128 | ElemType::I128 => "u128",
+        };
+        Ident::new(name, Span::call_site())
+    }
+}
+
+/// Parsed arguments of `shr3_table!`.
+struct TableArgs {
+    ty: Ident,
+    len: usize,
+    seed: u32,
+}
+
+impl Parse for TableArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ty: Ident = input.parse()?;
+        input.parse::<Token![;]>()?;
+        let len: LitInt = input.parse()?;
+        let len = len.base10_parse()?;
+        let mut seed = 1;
+        if input.parse::<Option<Token![;]>>()?.is_some() && !input.is_empty() {
+            let key: Ident = input.parse()?;
+            if key != "seed" {
+                return Err(syn::Error::new(key.span(), "shr3_table!: Expected `seed = ...`."));
+            }
+            input.parse::<Token![=]>()?;
+            let value: LitInt = input.parse()?;
+            seed = value.base10_parse()?;
+            input.parse::<Option<Token![;]>>()?;
+        }
+        Ok(TableArgs {
+            ty,
+            len,
+            seed,
+        })
+    }
+}
+
+/// Generate the array expression for `shr3_table!`.
+///
+/// The array is a constant that is evaluated at compile time with `Shr3::get_bits_const()`.
+fn expand_table(args: &TableArgs) -> syn::Result<TokenStream2> {
+    let elem = ElemType::from_ident(&args.ty)?;
+    let ty = &args.ty;
+    let len = Literal::usize_unsuffixed(args.len);
+    let seed = Literal::u32_suffixed(args.seed);
+    let value = if elem.numbits() <= 32 {
+        let numbits = Literal::u8_suffixed(elem.numbits());
+        quote!(shr3.get_bits_const(#numbits) as #ty)
+    } else {
+        // Concatenate 32 bit words. The first word is the most significant.
+        let unsigned = elem.unsigned();
+        let words = Literal::u8_suffixed(elem.numbits() / 32);
+        quote! {{
+            let mut value: #unsigned = 0;
+            let mut j = 0;
+            while j < #words {
+                value = (value << 32) | shr3.get_bits_const(32) as #unsigned;
+                j += 1;
+            }
+            value as #ty
+        }}
+    };
+    Ok(quote! {{
+        const TABLE: [#ty; #len] = {
+            let mut shr3 = ::shr3::Shr3::new_state(#seed);
+            let mut table: [#ty; #len] = [0; #len];
+            let mut i = 0;
+            while i < #len {
+                table[i] = #value;
+                i += 1;
+            }
+            table
+        };
+        TABLE
+    }})
+}
+
+/// Generate an array of random values at compile time.
+///
+/// `shr3_table!(TYPE; LEN; seed = SEED)` expands to a constant array of the type `[TYPE; LEN]`.
+/// It is evaluated at compile time with `Shr3::get_bits_const()`.
+/// The elements are generated exactly like `Shr3Ops::<TYPE>::get()` of `Shr3::new_state(SEED)`
+/// in ascending order.
+/// `seed = SEED` is optional. The default seed is 1, like in `Shr3::new()`.
+///
+/// `TYPE` is one of `u8`, `u16`, `u32`, `u64`, `u128`, `i8`, `i16`, `i32`, `i64` or `i128`.
+/// `LEN` and `SEED` must be integer literals.
+///
+/// ```
+///     use shr3::prelude::*;
+///     use shr3::shr3_table;
+///
+///     const TABLE: [u8; 256] = shr3_table!(u8; 256; seed = 42);
+///
+///     let mut shr3 = Shr3::new_state(42);
+///     let mut buf = [0_u8; 256];
+///     shr3.fill_slice(&mut buf);
+///     assert_eq!(TABLE, buf);
+/// ```
+#[proc_macro]
+pub fn shr3_table(input: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(input as TableArgs);
+    expand_table(&args)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// Generate the constructor arguments of all `fields` for `derive(Random)`.
+///
+/// The trait bounds required by the fields are appended to `bounds`.
+fn expand_fields(fields: &Fields, bounds: &mut Vec<WherePredicate>) -> syn::Result<TokenStream2> {
+    let mut values = Vec::new();
+    for field in fields {
+        let ty = &field.ty;
+        let mut range: Option<Expr> = None;
+        for attr in &field.attrs {
+            if attr.path().is_ident("random") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("range") {
+                        range = Some(meta.value()?.parse()?);
+                        Ok(())
+                    } else {
+                        Err(meta.error("derive(Random): Expected `range = ...`."))
+                    }
+                })?;
+            }
+        }
+        if let Some(range) = range {
+            bounds.push(parse_quote!(__R: ::shr3::Shr3Ops<#ty>));
+            values.push(quote!(::shr3::Shr3Ops::<#ty>::get_range(rng, #range)));
+        } else {
+            bounds.push(parse_quote!(#ty: ::shr3::Random<__R>));
+            values.push(quote!(<#ty as ::shr3::Random<__R>>::random(rng)));
+        }
+    }
+    // The fields are evaluated in the order of the constructor expression.
+    Ok(match fields {
+        Fields::Named(_) => {
+            let names = fields.iter().map(|f| &f.ident);
+            quote!({ #(#names: #values),* })
+        }
+        Fields::Unnamed(_) => quote!(( #(#values),* )),
+        Fields::Unit => quote!(),
+    })
+}
+
+/// Generate the `Random` implementation for `derive(Random)`.
+fn expand_random(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let mut bounds = Vec::new();
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let fields = expand_fields(&data.fields, &mut bounds)?;
+            quote!(Self #fields)
+        }
+        Data::Enum(data) => {
+            let count = data.variants.len();
+            if count == 0 || count > u32::MAX as usize {
+                return Err(syn::Error::new(input.ident.span(),
+                    "derive(Random): The enum must have at least one variant."));
+            }
+            let mut arms = Vec::new();
+            for (i, variant) in data.variants.iter().enumerate() {
+                let ident = &variant.ident;
+                let fields = expand_fields(&variant.fields, &mut bounds)?;
+                // The last variant catches all remaining values.
+                let pat = if i == count - 1 { quote!(_) } else { Literal::u32_suffixed(i as u32).into_token_stream() };
+                arms.push(quote!(#pat => Self::#ident #fields));
+            }
+            bounds.push(parse_quote!(__R: ::shr3::Shr3Ops<u32>));
+            let max = Literal::u32_suffixed((count - 1) as u32);
+            quote! {
+                match ::shr3::Shr3Ops::<u32>::get_max(rng, #max) {
+                    #(#arms),*
+                }
+            }
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new(data.union_token.span,
+                "derive(Random): Unions are not supported."));
+        }
+    };
+
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    generics.params.push(parse_quote!(__R));
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut where_clause = where_clause.cloned().unwrap_or_else(|| parse_quote!(where));
+    where_clause.predicates.extend(bounds);
+
+    Ok(quote! {
+        impl #impl_generics ::shr3::Random<__R> for #name #ty_generics #where_clause {
+            fn random(rng: &mut __R) -> Self {
+                #body
+            }
+        }
+    })
+}
+
+/// Derive the `Random` trait for structs and enums.
+///
+/// The fields are generated from top to bottom with `Random::random()`.
+/// A field with the attribute `#[random(range = ...)]` is generated with `Shr3Ops::get_range()` instead.
+///
+/// The variant of an enum is chosen uniformly with `Shr3Ops::<u32>::get_max()`.
+/// The fields of the chosen variant are generated afterwards.
+///
+/// ```
+///     use shr3::prelude::*;
+///     use shr3::Random;
+///
+///     #[derive(Random, Debug)]
+///     enum Command {
+///         Reset,
+///         Read { addr: u16, #[random(range = 1..=16)] len: u8 },
+///         Write(u16, [u8; 4]),
+///     }
+///
+///     #[derive(Random, Debug)]
+///     struct Packet {
+///         seq: u8,
+///         cmd: Command,
+///         crc_ok: bool,
+///     }
+///
+///     let mut shr3 = Shr3::new_state(42);
+///     for _ in 0..100 {
+///         let packet: Packet = shr3.get_random();
+///         if let Command::Read { len, .. } = packet.cmd {
+///             assert!((1..=16).contains(&len));
+///         }
+///     }
+/// ```
+#[proc_macro_derive(Random, attributes(random))]
+pub fn derive_random(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_random(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table() {
+        let args: TableArgs = syn::parse_str("u16; 3; seed = 42").unwrap();
+        assert_eq!((args.len, args.seed), (3, 42));
+        let args: TableArgs = syn::parse_str("i8; 2").unwrap();
+        assert_eq!((args.len, args.seed), (2, 1));
+        assert!(expand_table(&args).is_ok());
+        let args: TableArgs = syn::parse_str("f32; 2").unwrap();
+        assert!(expand_table(&args).is_err());
+        assert!(syn::parse_str::<TableArgs>("u8; 2; salt = 3").is_err());
+    }
+
+    #[test]
+    fn test_derive() {
+        let input: DeriveInput = syn::parse_str("struct A<T> { #[random(range = 1..5)] a: u8, b: T }").unwrap();
+        assert!(expand_random(&input).is_ok());
+        let input: DeriveInput = syn::parse_str("enum B { X, Y(u8), Z { z: i32 } }").unwrap();
+        assert!(expand_random(&input).is_ok());
+        let input: DeriveInput = syn::parse_str("enum C {}").unwrap();
+        assert!(expand_random(&input).is_err());
+        let input: DeriveInput = syn::parse_str("union D { a: u8 }").unwrap();
+        assert!(expand_random(&input).is_err());
+        let input: DeriveInput = syn::parse_str("struct E { #[random(min = 1)] a: u8 }").unwrap();
+        assert!(expand_random(&input).is_err());
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -7,8 +7,104 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 //
 
+//! Architecture specific implementations of the shuffle functions.
+//!
+//! `shr3()` and `shr3_16()` dispatch to the implementation for the target architecture.
+//! This module makes the individual implementations accessible,
+//! e.g. for benchmarking them against each other or for verifying them against the generic implementation.
+//!
+//! The `generic` module is always available.
+//! The assembly modules are only available on their target architecture
+//! and if the `force-generic` feature is disabled.
+//!
+//! ```
+//!     use shr3::arch;
+//!
+//!     println!("SHR3 implementation: {}", arch::current().name());
+//!     assert_eq!(arch::generic::shr3(42), shr3::shr3(42));
+//! ```
+
 pub mod generic;
-#[cfg(target_arch="avr")]
+#[cfg(all(target_arch="avr", not(feature="force-generic")))]
 pub mod avr;
+#[cfg(all(armv6m, not(feature="force-generic")))]
+pub mod armv6m;
+#[cfg(all(target_arch="riscv32", not(feature="force-generic")))]
+pub mod riscv32;
+#[cfg(all(target_arch="xtensa", not(feature="force-generic")))]
+pub mod xtensa;
+
+/// Implementation of the shuffle function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Arch {
+    /// Portable Rust implementation in `arch::generic`.
+    Generic,
+    /// AVR assembly implementation in `arch::avr` for cores with `mul`.
+    Avr,
+    /// AVR assembly implementation in `arch::avr` for cores without `mul`.
+    AvrNoMul,
+    /// Size optimized AVR assembly implementation in `arch::avr` (`tiny-code` feature).
+    AvrTinyCode,
+    /// ARMv6-M Thumb-1 assembly implementation in `arch::armv6m`.
+    ArmV6m,
+    /// RISC-V assembly implementation in `arch::riscv32`.
+    RiscV32,
+    /// Xtensa assembly implementation in `arch::xtensa`.
+    Xtensa,
+}
+
+impl Arch {
+    /// Get a short human readable name of the implementation.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Arch::Generic => "generic",
+            Arch::Avr => "avr",
+            Arch::AvrNoMul => "avr-nomul",
+            Arch::AvrTinyCode => "avr-tiny-code",
+            Arch::ArmV6m => "armv6m",
+            Arch::RiscV32 => "riscv32",
+            Arch::Xtensa => "xtensa",
+        }
+    }
+}
+
+/// Get the implementation that `shr3()` uses on this target.
+pub const fn current() -> Arch {
+    if cfg!(feature="force-generic") {
+        Arch::Generic
+    } else if cfg!(target_arch="avr") {
+        if cfg!(feature="tiny-code") {
+            Arch::AvrTinyCode
+        } else if cfg!(target_feature="mul") {
+            Arch::Avr
+        } else {
+            Arch::AvrNoMul
+        }
+    } else if cfg!(armv6m) {
+        Arch::ArmV6m
+    } else if cfg!(target_arch="riscv32") {
+        Arch::RiscV32
+    } else if cfg!(target_arch="xtensa") {
+        Arch::Xtensa
+    } else {
+        Arch::Generic
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_current() {
+        assert_eq!(current(), Arch::Generic);
+        assert_eq!(current().name(), "generic");
+        for state in [1, 42, 0x1234_5678, 0xFFFF_FFFF] {
+            assert_eq!(generic::shr3(state), crate::shr3(state));
+        }
+        assert_eq!(generic::shr3_16(0x1234), crate::shr3_16(0x1234));
+    }
+}
 
 // vim: ts=4 sw=4 expandtab
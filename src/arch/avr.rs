@@ -7,15 +7,219 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 //
 
+//! AVR 8-bit assembly implementation.
+
 use core::arch::asm;
 
-#[cfg_attr(feature="__devmode__", inline(never))]
-#[cfg_attr(not(feature="__devmode__"), inline(always))]
+/// Copy the register pair `$src` to the register pair `$dst`.
+///
+/// Cycles: 1
+#[cfg(target_feature="movw")]
+macro_rules! asm_movw {
+    ($dst:literal, $src:literal) => {
+        concat!("movw   {", $dst, ":h}:{", $dst, ":l}, {", $src, ":h}:{", $src, ":l}\n")
+    }
+}
+
+/// Copy the register pair `$src` to the register pair `$dst`
+/// on AVR cores without the `movw` instruction (e.g. AT90S).
+///
+/// Cycles: 2
+#[cfg(not(target_feature="movw"))]
+macro_rules! asm_movw {
+    ($dst:literal, $src:literal) => {
+        concat!("mov    {", $dst, ":l}, {", $src, ":l}\n",
+                "mov    {", $dst, ":h}, {", $src, ":h}\n")
+    }
+}
+
+/// One SHR3 round on the registers `ab` (state bits 0 to 15) and `cd` (state bits 16 to 31)
+/// for AVR cores with the `mul` instruction.
+///
+/// Requires: `fac` = 32, temporary register pairs `tab` and `tcd`. Clobbers r0 and r1.
+/// Cycles: 15 + 5 + 20 = 40
+#[cfg(all(target_feature="mul", not(feature="tiny-code")))]
+macro_rules! asm_shr3_round {
+    () => {
+        concat!(
+            // y ^= y << 13
+            // Cycles: 1 + 1 + 2 + 1 + 2 + (2 * 1) + 2 + (4 * 1) = 15
+            "movw   {tab:h}:{tab:l}, {ab:h}:{ab:l}\n",  // mov ab to temp
+            "mov    {tcd:l}, {cd:l}\n",                 // mov c to temp
+            "mul    {tcd:l}, {fac}\n",                  // r0:r1 = c << 5
+            "mov    {tcd:l}, r0\n",                     // temp_c[7:5] = low 3 bits result
+            "mul    {tab:h}, {fac}\n",                  // r0:r1 = b << 5
+            "mov    {tab:h}, r0\n",                     // temp_b[7:5] = low 3 bits result
+            "or     {tcd:l}, r1\n",                     // temp_c[4:0] = high 5 bits result
+            "mul    {tab:l}, {fac}\n",                  // r0:r1 = a << 5
+            "or     {tab:h}, r1\n",                     // temp_b[4:0] = high 5 bits result
+            "eor    {ab:h}, r0\n",                      // b[7:5] ^= low 3 bits result
+            "eor    {cd:l}, {tab:h}\n",                 // c ^= temp_b << 8
+            "eor    {cd:h}, {tcd:l}\n",                 // d ^= temp_c << 8
+
+            // y ^= y >> 17
+            // Cycles: 5
+            "movw   {tcd:h}:{tcd:l}, {cd:h}:{cd:l}\n",  // mov cd to temp
+            "lsr    {tcd:h}\n",                         // temp_d >>= 1
+            "ror    {tcd:l}\n",                         // temp_c >>= 1
+            "eor    {ab:l}, {tcd:l}\n",                 // a ^= temp_c >> 16
+            "eor    {ab:h}, {tcd:h}\n",                 // b ^= temp_d >> 16
+
+            // y ^= y << 5
+            // Cycles: (2 * 1) + 2 + 1 + 2 + (2 * 1) + 2 + (2 * 1) + 2 + (5 * 1) = 20
+            "movw   {tab:h}:{tab:l}, {ab:h}:{ab:l}\n",  // mov ab to temp
+            "movw   {tcd:h}:{tcd:l}, {cd:h}:{cd:l}\n",  // mov cd to temp
+            "mul    {tcd:h}, {fac}\n",                  // r0:r1 = d << 5
+            "mov    {tcd:h}, r0\n",                     // temp_d[7:5] = low 3 bits result
+            "mul    {tcd:l}, {fac}\n",                  // r0:r1 = c << 5
+            "mov    {tcd:l}, r0\n",                     // temp_c[7:5] = low 3 bits result
+            "or     {tcd:h}, r1\n",                     // temp_d[4:0] = high 5 bits result
+            "mul    {tab:h}, {fac}\n",                  // r0:r1 = b << 5
+            "mov    {tab:h}, r0\n",                     // temp_b[7:5] = low 3 bits result
+            "or     {tcd:l}, r1\n",                     // temp_c[4:0] = high 5 bits result
+            "mul    {tab:l}, {fac}\n",                  // r0:r1 = a << 5
+            "or     {tab:h}, r1\n",                     // temp_b[4:0] = high 5 bits result
+            "eor    {ab:l}, r0\n",                      // a[7:5] ^= low 3 bits result
+            "eor    {ab:h}, {tab:h}\n",                 // b ^= temp_b
+            "eor    {cd:l}, {tcd:l}\n",                 // c ^= temp_c
+            "eor    {cd:h}, {tcd:h}\n",                 // d ^= temp_d
+        )
+    }
+}
+
+/// One SHR3 round on the registers `ab` (state bits 0 to 15) and `cd` (state bits 16 to 31)
+/// for AVR cores without the `mul` instruction.
+///
+/// The left shifts are done as byte moves plus right shifts by 3.
+///
+/// Requires: temporary register pairs `tab` and `tcd` and temporary register `tz`.
+/// Cycles: 18 + 5 + 22 = 45
+/// Without `movw` each register pair copy (`asm_movw!`) costs one more cycle.
+#[cfg(all(not(target_feature="mul"), not(feature="tiny-code")))]
+macro_rules! asm_shr3_round_nomul {
+    () => {
+        concat!(
+            // y ^= y << 13
+            // Cycles: 1 + 1 + 1 + (3 * 4) + 3 = 18
+            asm_movw!("tab", "ab"),                     // mov ab to temp
+            "mov    {tcd:l}, {cd:l}\n",                 // mov c to temp
+            "clr    {tz}\n",                            // temp_z = 0
+            "lsr    {tcd:l}\n",                         // temp_zabc >>= 1
+            "ror    {tab:h}\n",
+            "ror    {tab:l}\n",
+            "ror    {tz}\n",
+            "lsr    {tcd:l}\n",                         // temp_zabc >>= 1
+            "ror    {tab:h}\n",
+            "ror    {tab:l}\n",
+            "ror    {tz}\n",
+            "lsr    {tcd:l}\n",                         // temp_zabc >>= 1
+            "ror    {tab:h}\n",
+            "ror    {tab:l}\n",
+            "ror    {tz}\n",
+            "eor    {ab:h}, {tz}\n",                    // b ^= a << 5
+            "eor    {cd:l}, {tab:l}\n",                 // c ^= (a >> 3) | (b << 5)
+            "eor    {cd:h}, {tab:h}\n",                 // d ^= (b >> 3) | (c << 5)
+
+            // y ^= y >> 17
+            // Cycles: 5
+            asm_movw!("tcd", "cd"),                     // mov cd to temp
+            "lsr    {tcd:h}\n",                         // temp_d >>= 1
+            "ror    {tcd:l}\n",                         // temp_c >>= 1
+            "eor    {ab:l}, {tcd:l}\n",                 // a ^= temp_c >> 16
+            "eor    {ab:h}, {tcd:h}\n",                 // b ^= temp_d >> 16
+
+            // y ^= y << 5
+            // Cycles: 1 + 1 + 1 + (3 * 5) + 4 = 22
+            asm_movw!("tab", "ab"),                     // mov ab to temp
+            asm_movw!("tcd", "cd"),                     // mov cd to temp
+            "clr    {tz}\n",                            // temp_z = 0
+            "lsr    {tcd:h}\n",                         // temp_zabcd >>= 1
+            "ror    {tcd:l}\n",
+            "ror    {tab:h}\n",
+            "ror    {tab:l}\n",
+            "ror    {tz}\n",
+            "lsr    {tcd:h}\n",                         // temp_zabcd >>= 1
+            "ror    {tcd:l}\n",
+            "ror    {tab:h}\n",
+            "ror    {tab:l}\n",
+            "ror    {tz}\n",
+            "lsr    {tcd:h}\n",                         // temp_zabcd >>= 1
+            "ror    {tcd:l}\n",
+            "ror    {tab:h}\n",
+            "ror    {tab:l}\n",
+            "ror    {tz}\n",
+            "eor    {ab:l}, {tz}\n",                    // a ^= a << 5
+            "eor    {ab:h}, {tab:l}\n",                 // b ^= (a >> 3) | (b << 5)
+            "eor    {cd:l}, {tab:h}\n",                 // c ^= (b >> 3) | (c << 5)
+            "eor    {cd:h}, {tcd:l}\n",                 // d ^= (c >> 3) | (d << 5)
+        )
+    }
+}
+
+/// One SHR3 round on the registers `ab` (state bits 0 to 15) and `cd` (state bits 16 to 31)
+/// with the smallest code size.
+///
+/// The left shifts are done as loops of single bit shifts.
+///
+/// Requires: temporary register pairs `tab` and `tcd` and upper temporary register `cnt`.
+/// Cycles: 31 + 5 + 36 = 72
+/// Without `movw` each register pair copy (`asm_movw!`) costs one more cycle.
+#[cfg(feature="tiny-code")]
+macro_rules! asm_shr3_round_tiny {
+    () => {
+        concat!(
+            // y ^= y << 13
+            // Cycles: 1 + 1 + 1 + (5 * 5) - 1 + 3 = 31
+            asm_movw!("tab", "ab"),                     // mov ab to temp
+            "mov    {tcd:l}, {cd:l}\n",                 // mov c to temp
+            "ldi    {cnt}, 5\n",                        // shift count
+            "1:\n",
+            "lsl    {tab:l}\n",                         // temp_abc <<= 1
+            "rol    {tab:h}\n",
+            "rol    {tcd:l}\n",
+            "dec    {cnt}\n",                           // cnt -= 1
+            "brne   1b\n",                              // loop until cnt == 0
+            "eor    {ab:h}, {tab:l}\n",                 // b ^= temp_a
+            "eor    {cd:l}, {tab:h}\n",                 // c ^= temp_b
+            "eor    {cd:h}, {tcd:l}\n",                 // d ^= temp_c
+
+            // y ^= y >> 17
+            // Cycles: 5
+            asm_movw!("tcd", "cd"),                     // mov cd to temp
+            "lsr    {tcd:h}\n",                         // temp_d >>= 1
+            "ror    {tcd:l}\n",                         // temp_c >>= 1
+            "eor    {ab:l}, {tcd:l}\n",                 // a ^= temp_c >> 16
+            "eor    {ab:h}, {tcd:h}\n",                 // b ^= temp_d >> 16
+
+            // y ^= y << 5
+            // Cycles: 1 + 1 + 1 + (5 * 6) - 1 + 4 = 36
+            asm_movw!("tab", "ab"),                     // mov ab to temp
+            asm_movw!("tcd", "cd"),                     // mov cd to temp
+            "ldi    {cnt}, 5\n",                        // shift count
+            "1:\n",
+            "lsl    {tab:l}\n",                         // temp_abcd <<= 1
+            "rol    {tab:h}\n",
+            "rol    {tcd:l}\n",
+            "rol    {tcd:h}\n",
+            "dec    {cnt}\n",                           // cnt -= 1
+            "brne   1b\n",                              // loop until cnt == 0
+            "eor    {ab:l}, {tab:l}\n",                 // a ^= temp_a
+            "eor    {ab:h}, {tab:h}\n",                 // b ^= temp_b
+            "eor    {cd:l}, {tcd:l}\n",                 // c ^= temp_c
+            "eor    {cd:h}, {tcd:h}\n",                 // d ^= temp_d
+        )
+    }
+}
+
+/// One round of the SHR3 shuffle function.
+#[cfg_attr(any(feature="__devmode__", feature="tiny-code"), inline(never))]
+#[cfg_attr(not(any(feature="__devmode__", feature="tiny-code")), inline(always))]
 pub fn shr3(state: u32) -> u32 {
     // Split state to 16 bit pairs. The compiler optimizes this away.
     let mut ab = state as u16;
     let mut cd = (state >> 16) as u16;
 
+    #[cfg(all(target_feature="mul", not(feature="tiny-code")))]
     unsafe {
         // Cycles: 2 + 15 + 5 + 20 + 2 = 44
         asm!(
@@ -24,47 +228,103 @@ pub fn shr3(state: u32) -> u32 {
             "mov    {r0_save}, r0",                     // save r0
             "ldi    {fac}, 32",                         // mul factor
 
-            // y ^= y << 13
-            // Cycles: 1 + 1 + 2 + 1 + 2 + (2 * 1) + 2 + (4 * 1) = 15
-            "movw   {tab:l}:{tab:h}, {ab:l}:{ab:h}",    // mov ab to temp
-            "mov    {tcd:l}, {cd:l}",                   // mov c to temp
-            "mul    {tcd:l}, {fac}",                    // r0:r1 = c << 5
-            "mov    {tcd:l}, r0",                       // temp_c[7:5] = low 3 bits result
-            "mul    {tab:h}, {fac}",                    // r0:r1 = b << 5
-            "mov    {tab:h}, r0",                       // temp_b[7:5] = low 3 bits result
-            "or     {tcd:l}, r1",                       // temp_c[4:0] = high 5 bits result
-            "mul    {tab:l}, {fac}",                    // r0:r1 = a << 5
-            "or     {tab:h}, r1",                       // temp_b[4:0] = high 5 bits result
-            "eor    {ab:h}, r0",                        // b[7:5] ^= low 3 bits result
-            "eor    {cd:l}, {tab:h}",                   // c ^= temp_b << 8
-            "eor    {cd:h}, {tcd:l}",                   // d ^= temp_c << 8
+            asm_shr3_round!(),
 
-            // y ^= y >> 17
-            // Cycles: 5
-            "movw   {tcd:l}:{tcd:h}, {cd:l}:{cd:h}",    // mov cd to temp
-            "lsr    {tcd:h}",                           // temp_d >>= 1
-            "ror    {tcd:l}",                           // temp_c >>= 1
-            "eor    {ab:l}, {tcd:l}",                   // a ^= temp_c >> 16
-            "eor    {ab:h}, {tcd:h}",                   // b ^= temp_d >> 16
+            // Cleanup
+            // Cycles: 2
+            "clr    r1",                                // restore r1
+            "mov    r0, {r0_save}",                     // restore r0
 
-            // y ^= y << 5
-            // Cycles: (2 * 1) + 2 + 1 + 2 + (2 * 1) + 2 + (2 * 1) + 2 + (5 * 1) = 20
-            "movw   {tab:l}:{tab:h}, {ab:l}:{ab:h}",    // mov ab to temp
-            "movw   {tcd:l}:{tcd:h}, {cd:l}:{cd:h}",    // mov cd to temp
-            "mul    {tcd:h}, {fac}",                    // r0:r1 = d << 5
-            "mov    {tcd:h}, r0",                       // temp_d[7:5] = low 3 bits result
-            "mul    {tcd:l}, {fac}",                    // r0:r1 = c << 5
-            "mov    {tcd:l}, r0",                       // temp_c[7:5] = low 3 bits result
-            "or     {tcd:h}, r1",                       // temp_d[4:0] = high 5 bits result
-            "mul    {tab:h}, {fac}",                    // r0:r1 = b << 5
-            "mov    {tab:h}, r0",                       // temp_b[7:5] = low 3 bits result
-            "or     {tcd:l}, r1",                       // temp_c[4:0] = high 5 bits result
-            "mul    {tab:l}, {fac}",                    // r0:r1 = a << 5
-            "or     {tab:h}, r1",                       // temp_b[4:0] = high 5 bits result
-            "eor    {ab:l}, r0",                        // a[7:5] ^= low 3 bits result
-            "eor    {ab:h}, {tab:h}",                   // b ^= temp_b
-            "eor    {cd:l}, {tcd:l}",                   // c ^= temp_c
-            "eor    {cd:h}, {tcd:h}",                   // d ^= temp_d
+            ab = inout(reg_pair) ab,                    // input/output byte a + b
+            cd = inout(reg_pair) cd,                    // input/output byte c + d
+            tab = out(reg_pair) _,                      // temporary byte a + b
+            tcd = out(reg_pair) _,                      // temporary byte c + d
+            fac = out(reg_upper) _,                     // mul factor
+            r0_save = out(reg) _,                       // r0 restore
+
+            options(pure, nomem, nostack),              // We only access registers
+        );
+    }
+
+    #[cfg(all(not(target_feature="mul"), not(feature="tiny-code")))]
+    unsafe {
+        // Cycles: 45
+        asm!(
+            asm_shr3_round_nomul!(),
+
+            ab = inout(reg_pair) ab,                    // input/output byte a + b
+            cd = inout(reg_pair) cd,                    // input/output byte c + d
+            tab = out(reg_pair) _,                      // temporary byte a + b
+            tcd = out(reg_pair) _,                      // temporary byte c + d
+            tz = out(reg) _,                            // temporary shift byte
+
+            options(pure, nomem, nostack),              // We only access registers
+        );
+    }
+
+    #[cfg(feature="tiny-code")]
+    unsafe {
+        // Cycles: 72
+        asm!(
+            asm_shr3_round_tiny!(),
+
+            ab = inout(reg_pair) ab,                    // input/output byte a + b
+            cd = inout(reg_pair) cd,                    // input/output byte c + d
+            tab = out(reg_pair) _,                      // temporary byte a + b
+            tcd = out(reg_pair) _,                      // temporary byte c + d
+            cnt = out(reg_upper) _,                     // shift loop counter
+
+            options(pure, nomem, nostack),              // We only access registers
+        );
+    }
+
+    // Combine the 16 bit state pairs. The compiler optimizes this away.
+    (ab as u32) | ((cd as u32) << 16)
+}
+
+/// Do `bitcount` (1 to 8) SHR3 rounds and extract the LSB of each round.
+///
+/// Returns the new state and the extracted bits.
+/// The first extracted bit is the most significant of the `bitcount` returned bits.
+///
+/// Panics, if `bitcount` is not in the range 1 to 8.
+///
+/// Not available with the `tiny-code` feature. The bit extraction then calls `shr3()` per bit.
+#[cfg(not(feature="tiny-code"))]
+#[cfg_attr(feature="__devmode__", inline(never))]
+#[cfg_attr(not(feature="__devmode__"), inline(always))]
+pub fn shr3_bits8(state: u32, bitcount: u8) -> (u32, u8) {
+    assert!((1..=8).contains(&bitcount), "shr3_bits8: bitcount must be 1 to 8.");
+
+    // Split state to 16 bit pairs. The compiler optimizes this away.
+    let mut ab = state as u16;
+    let mut cd = (state >> 16) as u16;
+    let mut bits: u8 = 0;
+
+    #[cfg(all(target_feature="mul", not(feature="tiny-code")))]
+    unsafe {
+        // Cycles: 2 + (bitcount * (40 + 3 + 3)) - 1 + 2
+        asm!(
+            // Setup
+            // Cycles: 2
+            "mov    {r0_save}, r0",                     // save r0
+            "ldi    {fac}, 32",                         // mul factor
+
+            "2:",
+
+            // Cycles: 40
+            asm_shr3_round!(),
+
+            // bits = (bits << 1) | (y & 1)
+            // Cycles: 3
+            "bst    {ab:l}, 0",                         // T = a[0]
+            "lsl    {bits}",                            // bits <<= 1
+            "bld    {bits}, 0",                         // bits[0] = T
+
+            // Loop
+            // Cycles: 1 + 2 (1 on exit)
+            "dec    {cnt}",                             // cnt -= 1
+            "brne   2b",                                // loop until cnt == 0
 
             // Cleanup
             // Cycles: 2
@@ -73,6 +333,8 @@ pub fn shr3(state: u32) -> u32 {
 
             ab = inout(reg_pair) ab,                    // input/output byte a + b
             cd = inout(reg_pair) cd,                    // input/output byte c + d
+            bits = inout(reg) bits,                     // output bits
+            cnt = inout(reg) bitcount => _,             // loop counter
             tab = out(reg_pair) _,                      // temporary byte a + b
             tcd = out(reg_pair) _,                      // temporary byte c + d
             fac = out(reg_upper) _,                     // mul factor
@@ -82,8 +344,79 @@ pub fn shr3(state: u32) -> u32 {
         );
     }
 
+    #[cfg(all(not(target_feature="mul"), not(feature="tiny-code")))]
+    unsafe {
+        // Cycles: bitcount * (45 + 3 + 3) - 1
+        asm!(
+            "2:",
+
+            // Cycles: 45
+            asm_shr3_round_nomul!(),
+
+            // bits = (bits << 1) | (y & 1)
+            // Cycles: 3
+            "bst    {ab:l}, 0",                         // T = a[0]
+            "lsl    {bits}",                            // bits <<= 1
+            "bld    {bits}, 0",                         // bits[0] = T
+
+            // Loop
+            // Cycles: 1 + 2 (1 on exit)
+            "dec    {cnt}",                             // cnt -= 1
+            "brne   2b",                                // loop until cnt == 0
+
+            ab = inout(reg_pair) ab,                    // input/output byte a + b
+            cd = inout(reg_pair) cd,                    // input/output byte c + d
+            bits = inout(reg) bits,                     // output bits
+            cnt = inout(reg) bitcount => _,             // loop counter
+            tab = out(reg_pair) _,                      // temporary byte a + b
+            tcd = out(reg_pair) _,                      // temporary byte c + d
+            tz = out(reg) _,                            // temporary shift byte
+
+            options(pure, nomem, nostack),              // We only access registers
+        );
+    }
+
     // Combine the 16 bit state pairs. The compiler optimizes this away.
-    (ab as u32) | ((cd as u32) << 16)
+    ((ab as u32) | ((cd as u32) << 16), bits)
+}
+
+/// One round of the 16 bit xorshift shuffle function.
+#[cfg_attr(feature="__devmode__", inline(never))]
+#[cfg_attr(not(feature="__devmode__"), inline(always))]
+pub fn shr3_16(state: u16) -> u16 {
+    let mut ab = state;
+
+    unsafe {
+        // Cycles: 7 + 3 + 1 = 11
+        asm!(
+            // y ^= y << 7
+            // Cycles: 1 + (6 * 1) = 7
+            asm_movw!("tab", "ab"),                     // mov ab to temp
+            "lsr    {tab:h}",                           // temp_b >>= 1
+            "ror    {tab:l}",                           // temp_a >>= 1, carry = a[0]
+            "clr    {tab:h}",                           // temp_b = 0, carry unchanged
+            "ror    {tab:h}",                           // temp_b[7] = a[0]
+            "eor    {ab:l}, {tab:h}",                   // a ^= temp_b
+            "eor    {ab:h}, {tab:l}",                   // b ^= temp_a
+
+            // y ^= y >> 9
+            // Cycles: 3
+            "mov    {tab:l}, {ab:h}",                   // mov b to temp
+            "lsr    {tab:l}",                           // temp_a >>= 1
+            "eor    {ab:l}, {tab:l}",                   // a ^= temp_a
+
+            // y ^= y << 8
+            // Cycles: 1
+            "eor    {ab:h}, {ab:l}",                    // b ^= a
+
+            ab = inout(reg_pair) ab,                    // input/output byte a + b
+            tab = out(reg_pair) _,                      // temporary byte a + b
+
+            options(pure, nomem, nostack),              // We only access registers
+        );
+    }
+
+    ab
 }
 
 // vim: ts=4 sw=4 expandtab
@@ -7,10 +7,12 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 //
 
+//! Portable implementation for all architectures.
+
+/// One round of the SHR3 shuffle function.
 #[cfg_attr(feature="__devmode__", inline(never))]
 #[cfg_attr(not(feature="__devmode__"), inline(always))]
-#[cfg_attr(target_arch="avr", allow(dead_code))]
-pub fn shr3(mut state: u32) -> u32 {
+pub const fn shr3(mut state: u32) -> u32 {
     // Fixed variant with full cycle.
     state ^= state << 13;
     state ^= state >> 17;
@@ -18,4 +20,15 @@ pub fn shr3(mut state: u32) -> u32 {
     state
 }
 
+/// One round of the 16 bit xorshift shuffle function.
+#[cfg_attr(feature="__devmode__", inline(never))]
+#[cfg_attr(not(feature="__devmode__"), inline(always))]
+pub const fn shr3_16(mut state: u16) -> u16 {
+    // Full cycle triple (7, 9, 8).
+    state ^= state << 7;
+    state ^= state >> 9;
+    state ^= state << 8;
+    state
+}
+
 // vim: ts=4 sw=4 expandtab
@@ -0,0 +1,101 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! 32 bit RISC-V (RV32I and RV32E) assembly implementation.
+
+use core::arch::asm;
+
+/// One SHR3 round on the register `y`.
+///
+/// Only one temporary register is used, so that register pressure stays low on RV32E.
+///
+/// Requires: temporary register `t`.
+/// Cycles: 6 (single cycle shifts, e.g. CH32V003)
+macro_rules! asm_shr3_round {
+    () => {
+        concat!(
+            // y ^= y << 13
+            "slli   {t}, {y}, 13\n",                    // t = y << 13
+            "xor    {y}, {y}, {t}\n",                   // y ^= t
+
+            // y ^= y >> 17
+            "srli   {t}, {y}, 17\n",                    // t = y >> 17
+            "xor    {y}, {y}, {t}\n",                   // y ^= t
+
+            // y ^= y << 5
+            "slli   {t}, {y}, 5\n",                     // t = y << 5
+            "xor    {y}, {y}, {t}\n",                   // y ^= t
+        )
+    }
+}
+
+/// One round of the SHR3 shuffle function.
+#[cfg_attr(feature="__devmode__", inline(never))]
+#[cfg_attr(not(feature="__devmode__"), inline(always))]
+pub fn shr3(mut state: u32) -> u32 {
+    unsafe {
+        // Cycles: 6
+        asm!(
+            asm_shr3_round!(),
+
+            y = inout(reg) state,                       // input/output state
+            t = out(reg) _,                             // temporary
+
+            options(pure, nomem, nostack),              // We only access registers
+        );
+    }
+
+    state
+}
+
+/// Do `bitcount` (1 to 8) SHR3 rounds and extract the LSB of each round.
+///
+/// Returns the new state and the extracted bits.
+/// The first extracted bit is the most significant of the `bitcount` returned bits.
+///
+/// Panics, if `bitcount` is not in the range 1 to 8.
+#[cfg_attr(feature="__devmode__", inline(never))]
+#[cfg_attr(not(feature="__devmode__"), inline(always))]
+pub fn shr3_bits8(mut state: u32, bitcount: u8) -> (u32, u8) {
+    assert!((1..=8).contains(&bitcount), "shr3_bits8: bitcount must be 1 to 8.");
+
+    let mut bits: u32 = 0;
+
+    unsafe {
+        // Cycles: bitcount * (6 + 3 + 1 + 2) - 1
+        asm!(
+            "2:",
+
+            // Cycles: 6
+            asm_shr3_round!(),
+
+            // bits = (bits << 1) | (y & 1)
+            // Cycles: 3
+            "andi   {t}, {y}, 1",                       // t = y[0]
+            "slli   {bits}, {bits}, 1",                 // bits <<= 1
+            "or     {bits}, {bits}, {t}",               // bits[0] = t
+
+            // Loop
+            // Cycles: 1 + 2 (1 on exit)
+            "addi   {cnt}, {cnt}, -1",                  // cnt -= 1
+            "bnez   {cnt}, 2b",                         // loop until cnt == 0
+
+            y = inout(reg) state,                       // input/output state
+            bits = inout(reg) bits,                     // output bits
+            cnt = inout(reg) bitcount as u32 => _,      // loop counter
+            t = out(reg) _,                             // temporary
+
+            options(pure, nomem, nostack),              // We only access registers
+        );
+    }
+
+    (state, bits as u8)
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,107 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! AArch64 NEON fill kernels.
+//!
+//! If the `std` feature is enabled, then NEON is detected at runtime.
+//! Otherwise it must be enabled at compile time.
+
+use core::arch::aarch64::*;
+
+/// Fill `buf` with interleaved words of 4 lanes.
+///
+/// Returns the number of filled words. That is the length of `buf` rounded down to a multiple of 4.
+#[target_feature(enable="neon")]
+pub(crate) unsafe fn fill4_neon(state: &mut [u32; 4], buf: &mut [u32]) -> usize {
+    let len = buf.len();
+    let mut chunks = buf.chunks_exact_mut(4);
+    let mut y = vld1q_u32(state.as_ptr());
+    for chunk in &mut chunks {
+        y = veorq_u32(y, vshlq_n_u32::<13>(y));
+        y = veorq_u32(y, vshrq_n_u32::<17>(y));
+        y = veorq_u32(y, vshlq_n_u32::<5>(y));
+        vst1q_u32(chunk.as_mut_ptr(), y);
+    }
+    vst1q_u32(state.as_mut_ptr(), y);
+    len - chunks.into_remainder().len()
+}
+
+/// Fill `buf` with interleaved words of 8 lanes.
+///
+/// Returns the number of filled words. That is the length of `buf` rounded down to a multiple of 8.
+#[target_feature(enable="neon")]
+pub(crate) unsafe fn fill8_neon(state: &mut [u32; 8], buf: &mut [u32]) -> usize {
+    let len = buf.len();
+    let mut chunks = buf.chunks_exact_mut(8);
+    let mut y0 = vld1q_u32(state.as_ptr());
+    let mut y1 = vld1q_u32(state.as_ptr().add(4));
+    for chunk in &mut chunks {
+        y0 = veorq_u32(y0, vshlq_n_u32::<13>(y0));
+        y1 = veorq_u32(y1, vshlq_n_u32::<13>(y1));
+        y0 = veorq_u32(y0, vshrq_n_u32::<17>(y0));
+        y1 = veorq_u32(y1, vshrq_n_u32::<17>(y1));
+        y0 = veorq_u32(y0, vshlq_n_u32::<5>(y0));
+        y1 = veorq_u32(y1, vshlq_n_u32::<5>(y1));
+        vst1q_u32(chunk.as_mut_ptr(), y0);
+        vst1q_u32(chunk.as_mut_ptr().add(4), y1);
+    }
+    vst1q_u32(state.as_mut_ptr(), y0);
+    vst1q_u32(state.as_mut_ptr().add(4), y1);
+    len - chunks.into_remainder().len()
+}
+
+/// Check whether the CPU supports NEON.
+#[inline]
+fn has_neon() -> bool {
+    #[cfg(feature="std")]
+    let has = std::arch::is_aarch64_feature_detected!("neon");
+    #[cfg(not(feature="std"))]
+    let has = cfg!(target_feature="neon");
+    has
+}
+
+/// Fill kernel for 4 lanes.
+///
+/// Returns 0, if NEON is not available.
+#[inline]
+pub(crate) fn fill4(state: &mut [u32; 4], buf: &mut [u32]) -> usize {
+    if has_neon() {
+        // SAFETY: The CPU supports NEON.
+        unsafe { fill4_neon(state, buf) }
+    } else {
+        0
+    }
+}
+
+/// Fill kernel for 8 lanes.
+///
+/// Returns 0, if NEON is not available.
+#[inline]
+pub(crate) fn fill8(state: &mut [u32; 8], buf: &mut [u32]) -> usize {
+    if has_neon() {
+        // SAFETY: The CPU supports NEON.
+        unsafe { fill8_neon(state, buf) }
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::tests::check_kernel;
+
+    #[test]
+    fn test_kernels() {
+        assert_eq!(check_kernel(fill4, 62), 60);
+        assert_eq!(check_kernel(fill8, 63), 56);
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,82 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! WebAssembly SIMD128 fill kernels.
+
+use core::arch::wasm32::*;
+
+/// Fill `buf` with interleaved words of 4 lanes.
+///
+/// Returns the number of filled words. That is the length of `buf` rounded down to a multiple of 4.
+#[target_feature(enable="simd128")]
+pub(crate) unsafe fn fill4_simd128(state: &mut [u32; 4], buf: &mut [u32]) -> usize {
+    let len = buf.len();
+    let mut chunks = buf.chunks_exact_mut(4);
+    let mut y = v128_load(state.as_ptr() as *const v128);
+    for chunk in &mut chunks {
+        y = v128_xor(y, u32x4_shl(y, 13));
+        y = v128_xor(y, u32x4_shr(y, 17));
+        y = v128_xor(y, u32x4_shl(y, 5));
+        v128_store(chunk.as_mut_ptr() as *mut v128, y);
+    }
+    v128_store(state.as_mut_ptr() as *mut v128, y);
+    len - chunks.into_remainder().len()
+}
+
+/// Fill `buf` with interleaved words of 8 lanes.
+///
+/// Returns the number of filled words. That is the length of `buf` rounded down to a multiple of 8.
+#[target_feature(enable="simd128")]
+pub(crate) unsafe fn fill8_simd128(state: &mut [u32; 8], buf: &mut [u32]) -> usize {
+    let len = buf.len();
+    let mut chunks = buf.chunks_exact_mut(8);
+    let mut y0 = v128_load(state.as_ptr() as *const v128);
+    let mut y1 = v128_load(state.as_ptr().add(4) as *const v128);
+    for chunk in &mut chunks {
+        y0 = v128_xor(y0, u32x4_shl(y0, 13));
+        y1 = v128_xor(y1, u32x4_shl(y1, 13));
+        y0 = v128_xor(y0, u32x4_shr(y0, 17));
+        y1 = v128_xor(y1, u32x4_shr(y1, 17));
+        y0 = v128_xor(y0, u32x4_shl(y0, 5));
+        y1 = v128_xor(y1, u32x4_shl(y1, 5));
+        v128_store(chunk.as_mut_ptr() as *mut v128, y0);
+        v128_store(chunk.as_mut_ptr().add(4) as *mut v128, y1);
+    }
+    v128_store(state.as_mut_ptr() as *mut v128, y0);
+    v128_store(state.as_mut_ptr().add(4) as *mut v128, y1);
+    len - chunks.into_remainder().len()
+}
+
+/// Fill kernel for 4 lanes.
+#[inline]
+pub(crate) fn fill4(state: &mut [u32; 4], buf: &mut [u32]) -> usize {
+    // SAFETY: SIMD128 is enabled at compile time.
+    unsafe { fill4_simd128(state, buf) }
+}
+
+/// Fill kernel for 8 lanes.
+#[inline]
+pub(crate) fn fill8(state: &mut [u32; 8], buf: &mut [u32]) -> usize {
+    // SAFETY: SIMD128 is enabled at compile time.
+    unsafe { fill8_simd128(state, buf) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::tests::check_kernel;
+
+    #[test]
+    fn test_kernels() {
+        assert_eq!(check_kernel(fill4, 62), 60);
+        assert_eq!(check_kernel(fill8, 63), 56);
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,145 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! SSE2 and AVX2 fill kernels.
+//!
+//! If the `std` feature is enabled, then the kernels are selected by runtime CPU feature detection.
+//! Otherwise they are selected by the target features enabled at compile time.
+
+#[cfg(target_arch="x86")]
+use core::arch::x86::*;
+#[cfg(target_arch="x86_64")]
+use core::arch::x86_64::*;
+
+/// Fill `buf` with interleaved words of 4 lanes.
+///
+/// Returns the number of filled words. That is the length of `buf` rounded down to a multiple of 4.
+#[target_feature(enable="sse2")]
+pub(crate) unsafe fn fill4_sse2(state: &mut [u32; 4], buf: &mut [u32]) -> usize {
+    let len = buf.len();
+    let mut chunks = buf.chunks_exact_mut(4);
+    let mut y = _mm_loadu_si128(state.as_ptr() as *const __m128i);
+    for chunk in &mut chunks {
+        y = _mm_xor_si128(y, _mm_slli_epi32::<13>(y));
+        y = _mm_xor_si128(y, _mm_srli_epi32::<17>(y));
+        y = _mm_xor_si128(y, _mm_slli_epi32::<5>(y));
+        _mm_storeu_si128(chunk.as_mut_ptr() as *mut __m128i, y);
+    }
+    _mm_storeu_si128(state.as_mut_ptr() as *mut __m128i, y);
+    len - chunks.into_remainder().len()
+}
+
+/// Fill `buf` with interleaved words of 8 lanes.
+///
+/// Returns the number of filled words. That is the length of `buf` rounded down to a multiple of 8.
+#[target_feature(enable="sse2")]
+pub(crate) unsafe fn fill8_sse2(state: &mut [u32; 8], buf: &mut [u32]) -> usize {
+    let len = buf.len();
+    let mut chunks = buf.chunks_exact_mut(8);
+    let mut y0 = _mm_loadu_si128(state.as_ptr() as *const __m128i);
+    let mut y1 = _mm_loadu_si128(state.as_ptr().add(4) as *const __m128i);
+    for chunk in &mut chunks {
+        y0 = _mm_xor_si128(y0, _mm_slli_epi32::<13>(y0));
+        y1 = _mm_xor_si128(y1, _mm_slli_epi32::<13>(y1));
+        y0 = _mm_xor_si128(y0, _mm_srli_epi32::<17>(y0));
+        y1 = _mm_xor_si128(y1, _mm_srli_epi32::<17>(y1));
+        y0 = _mm_xor_si128(y0, _mm_slli_epi32::<5>(y0));
+        y1 = _mm_xor_si128(y1, _mm_slli_epi32::<5>(y1));
+        _mm_storeu_si128(chunk.as_mut_ptr() as *mut __m128i, y0);
+        _mm_storeu_si128(chunk.as_mut_ptr().add(4) as *mut __m128i, y1);
+    }
+    _mm_storeu_si128(state.as_mut_ptr() as *mut __m128i, y0);
+    _mm_storeu_si128(state.as_mut_ptr().add(4) as *mut __m128i, y1);
+    len - chunks.into_remainder().len()
+}
+
+/// Fill `buf` with interleaved words of 8 lanes.
+///
+/// Returns the number of filled words. That is the length of `buf` rounded down to a multiple of 8.
+#[target_feature(enable="avx2")]
+pub(crate) unsafe fn fill8_avx2(state: &mut [u32; 8], buf: &mut [u32]) -> usize {
+    let len = buf.len();
+    let mut chunks = buf.chunks_exact_mut(8);
+    let mut y = _mm256_loadu_si256(state.as_ptr() as *const __m256i);
+    for chunk in &mut chunks {
+        y = _mm256_xor_si256(y, _mm256_slli_epi32::<13>(y));
+        y = _mm256_xor_si256(y, _mm256_srli_epi32::<17>(y));
+        y = _mm256_xor_si256(y, _mm256_slli_epi32::<5>(y));
+        _mm256_storeu_si256(chunk.as_mut_ptr() as *mut __m256i, y);
+    }
+    _mm256_storeu_si256(state.as_mut_ptr() as *mut __m256i, y);
+    len - chunks.into_remainder().len()
+}
+
+/// Check whether the CPU supports SSE2.
+#[inline]
+fn has_sse2() -> bool {
+    #[cfg(feature="std")]
+    let has = std::is_x86_feature_detected!("sse2");
+    #[cfg(not(feature="std"))]
+    let has = cfg!(target_feature="sse2");
+    has
+}
+
+/// Check whether the CPU supports AVX2.
+#[inline]
+fn has_avx2() -> bool {
+    #[cfg(feature="std")]
+    let has = std::is_x86_feature_detected!("avx2");
+    #[cfg(not(feature="std"))]
+    let has = cfg!(target_feature="avx2");
+    has
+}
+
+/// Fill kernel for 4 lanes.
+///
+/// Returns 0, if SSE2 is not available.
+#[inline]
+pub(crate) fn fill4(state: &mut [u32; 4], buf: &mut [u32]) -> usize {
+    if has_sse2() {
+        // SAFETY: The CPU supports SSE2.
+        unsafe { fill4_sse2(state, buf) }
+    } else {
+        0
+    }
+}
+
+/// Fill kernel for 8 lanes.
+///
+/// AVX2 is used, if it is available. SSE2 otherwise.
+/// Returns 0, if neither is available.
+#[inline]
+pub(crate) fn fill8(state: &mut [u32; 8], buf: &mut [u32]) -> usize {
+    if has_avx2() {
+        // SAFETY: The CPU supports AVX2.
+        unsafe { fill8_avx2(state, buf) }
+    } else if has_sse2() {
+        // SAFETY: The CPU supports SSE2.
+        unsafe { fill8_sse2(state, buf) }
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::batch::tests::check_kernel;
+
+    #[test]
+    fn test_kernels() {
+        assert_eq!(check_kernel(fill4, 62), 60);
+        assert_eq!(check_kernel(|s, b| unsafe { fill8_sse2(s, b) }, 63), 56);
+        if has_avx2() {
+            assert_eq!(check_kernel(|s, b| unsafe { fill8_avx2(s, b) }, 64), 64);
+        }
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,186 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Block buffered SHR3 generator.
+
+use crate::shr3;
+use crate::wordbits::{WordBits, impl_shr3_ops_words};
+
+/// Number of 32 bit words generated per block.
+const BLOCK_WORDS: usize = 8;
+
+/// Generate one block of 32 bit words.
+///
+/// Each word consists of 32 bits of the SHR3 bit stream, MSB first.
+/// That is identical to `Shr3Ops::<u32>::get()`.
+fn generate(state: &mut u32, block: &mut [u32; BLOCK_WORDS]) {
+    let mut s = *state;
+    for word in block {
+        let mut w = 0;
+        for _ in 0..32 {
+            s = shr3(s);
+            w = (w << 1) | (s & 1);
+        }
+        *word = w;
+    }
+    *state = s;
+}
+
+/// Block buffered SHR3 generator.
+///
+/// The generator produces a block of 8 words (256 bits) of the SHR3 bit stream at a time
+/// in a tight loop.
+/// Extractions are served from the block buffer.
+/// That amortizes the per-call overhead of small extractions like `Shr3Ops::<u8>::get()`.
+///
+/// The extracted random stream is bit exact identical to `Shr3` with the same seed.
+/// But up to 255 bits are generated in advance.
+/// Therefore, the SHR3 state of the block generator is ahead of the extracted stream.
+///
+/// ```
+///     use shr3::prelude::*;
+///     use shr3::Shr3Block;
+///
+///     let mut a = Shr3Block::new_state(42);
+///     let mut b = Shr3::new_state(42);
+///     let x: u8 = a.get();
+///     let y: u8 = b.get();
+///     assert_eq!(x, y);
+///     let x: u16 = a.get_bits(11);
+///     let y: u16 = b.get_bits(11);
+///     assert_eq!(x, y);
+/// ```
+///
+/// This generator is *not* cryptographically secure!
+pub struct Shr3Block {
+    state: u32,
+    block: [u32; BLOCK_WORDS],
+    index: usize,
+    bits: WordBits,
+}
+
+impl Shr3Block {
+    /// Create a new instance with default initial `state = 1`.
+    #[inline]
+    pub const fn new() -> Shr3Block {
+        Self::new_state(1)
+    }
+
+    /// Create a new instance with user specified initial state.
+    ///
+    /// Special state 0: The SHR3 state must not be 0. If 0 is passed to this function,
+    ///                  then the state 0x7FFFFFFF is picked instead.
+    #[inline]
+    pub const fn new_state(state: u32) -> Shr3Block {
+        Shr3Block {
+            state: if state == 0 { 0x7FFFFFFF } else { state },
+            block: [0; BLOCK_WORDS],
+            index: BLOCK_WORDS,
+            bits: WordBits::new(),
+        }
+    }
+
+    /// Get the next 32 bit word of the block buffer.
+    ///
+    /// A new block is generated, if the buffer is exhausted.
+    ///
+    /// *Note*: Bits of the current word that have not been extracted by `Shr3Ops` yet
+    ///         are skipped.
+    #[inline]
+    pub fn next_u32(&mut self) -> u32 {
+        if self.index >= BLOCK_WORDS {
+            generate(&mut self.state, &mut self.block);
+            self.index = 0;
+        }
+        let word = self.block[self.index];
+        self.index += 1;
+        word
+    }
+
+    /// Fill the byte slice `buf` with random bytes.
+    ///
+    /// The extracted random stream is identical to calling
+    /// `Shr3Ops::<u8>::get()` for each byte of `buf` in ascending order.
+    pub fn fill_bytes(&mut self, buf: &mut [u8]) {
+        let mut pos = 0;
+        // Consume the partially extracted word bitwise.
+        while pos < buf.len() && !self.bits.is_empty() {
+            buf[pos] = crate::Shr3Ops::<u8>::get(self);
+            pos += 1;
+        }
+        let mut chunks = buf[pos..].chunks_exact_mut(4);
+        for chunk in &mut chunks {
+            chunk.copy_from_slice(&self.next_u32().to_be_bytes());
+        }
+        for b in chunks.into_remainder() {
+            *b = crate::Shr3Ops::<u8>::get(self);
+        }
+    }
+}
+
+impl Default for Shr3Block {
+    /// Create a new instance with default initial `state = 1`.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl_shr3_ops_words!(Shr3Block);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Shr3, Shr3Ops};
+
+    #[test]
+    fn test_block() {
+        let mut a = Shr3Block::new_state(42);
+        let mut b = Shr3::new_state(42);
+        for _ in 0..100 {
+            assert_eq!(a.next_u32(), Shr3Ops::<u32>::get(&mut b));
+        }
+
+        let mut a = Shr3Block::new_state(42);
+        let mut b = Shr3::new_state(42);
+        for i in 0..300_u32 {
+            let n = (i % 17) as u8;
+            let x: u16 = a.get_bits(n);
+            let y: u16 = b.get_bits(n);
+            assert_eq!(x, y);
+            let x: i64 = a.get();
+            let y: i64 = b.get();
+            assert_eq!(x, y);
+        }
+
+        let mut a = Shr3Block::new_state(42);
+        let mut b = Shr3::new_state(42);
+        let _: u8 = a.get_bits(3);
+        let _: u8 = b.get_bits(3);
+        let mut x = [0_u8; 37];
+        let mut y = [0_u8; 37];
+        a.fill_bytes(&mut x);
+        b.fill_bytes(&mut y);
+        assert_eq!(x, y);
+        let mut x = [0_u8; 40];
+        let mut y = [0_u8; 40];
+        a.fill_bytes(&mut x);
+        b.fill_bytes(&mut y);
+        assert_eq!(x, y);
+
+        let mut a = Shr3Block::new_state(0);
+        let mut b = Shr3::new_state(0);
+        assert_eq!(a.next_u32(), Shr3Ops::<u32>::get(&mut b));
+        let mut a = Shr3Block::default();
+        let mut b = Shr3::default();
+        assert_eq!(a.next_u32(), Shr3Ops::<u32>::get(&mut b));
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
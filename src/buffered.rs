@@ -0,0 +1,142 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! SHR3 wrapper that caches unconsumed random bits.
+
+use crate::Shr3;
+use crate::wordbits::{WordBits, impl_shr3_ops_words};
+
+/// SHR3 generator that caches the unconsumed bits of each round.
+///
+/// The plain `Shr3` does one SHR3 round per extracted bit and outputs the LSB of each state.
+/// That gives the best quality stream, but it costs 32 rounds per 32 bits.
+/// This wrapper trades quality for speed and does only one round per 32 extracted bits.
+/// Each round produces the whole 32 bit state as in `Shr3::get_word32()`.
+/// The bits are extracted from MSB to LSB.
+/// The bits that are not consumed by an extraction are kept for the following extractions.
+/// Therefore, alternating `get_bits(3)` and `get_bits(5)` calls consume one round per 4 calls
+/// instead of 16 rounds.
+///
+/// *Note*: Consecutive words of the word mode stream are consecutive SHR3 states.
+///         They are strongly correlated, because each word is a fixed linear function
+///         of the previous word.
+///         Use the plain `Shr3`, if the quality of the random stream matters.
+///
+/// Consumption pattern compared to the plain `Shr3`:
+///
+/// * The random stream is the word mode stream of `Shr3::get_word32()`.
+///   It is different from the bit stream of the wrapped `Shr3`.
+/// * The state of the wrapped generator is only advanced when the cache runs empty.
+/// * Up to 31 bits of the last round are cached in the wrapper.
+///   They are lost, if the wrapper is unwrapped with `Shr3Buffered::into_inner()`.
+///
+/// ```
+///     use shr3::prelude::*;
+///     use shr3::Shr3Buffered;
+///
+///     let mut shr3 = Shr3Buffered::new(Shr3::new_state(42));
+///     for _ in 0..4 {
+///         let _: u8 = shr3.get_bits(3);
+///         let _: u8 = shr3.get_bits(5);
+///     }
+///     // Only one SHR3 round for 32 bits.
+///     assert_eq!(shr3.inner().state(), shr3::shr3(42));
+///     assert_eq!(shr3.cached_bits(), 0);
+/// ```
+pub struct Shr3Buffered {
+    shr3: Shr3,
+    bits: WordBits,
+}
+
+impl Shr3Buffered {
+    /// Wrap `shr3` with an empty cache.
+    #[inline]
+    pub const fn new(shr3: Shr3) -> Self {
+        Self {
+            shr3,
+            bits: WordBits::new(),
+        }
+    }
+
+    /// Get the number of cached bits.
+    #[inline]
+    pub const fn cached_bits(&self) -> u8 {
+        self.bits.avail()
+    }
+
+    /// Drop all cached bits.
+    ///
+    /// The next extraction starts with a new round.
+    #[inline]
+    pub fn clear_cache(&mut self) {
+        self.bits = WordBits::new();
+    }
+
+    /// Do one SHR3 round and get the whole 32 bit state.
+    ///
+    /// This bypasses the cache. The cached bits are kept.
+    #[inline]
+    pub fn next_u32(&mut self) -> u32 {
+        self.shr3.get_word32()
+    }
+
+    /// Get a reference to the wrapped generator.
+    #[inline]
+    pub const fn inner(&self) -> &Shr3 {
+        &self.shr3
+    }
+
+    /// Unwrap the generator.
+    ///
+    /// The cached bits are lost.
+    #[inline]
+    pub fn into_inner(self) -> Shr3 {
+        self.shr3
+    }
+}
+
+impl_shr3_ops_words!(Shr3Buffered);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shr3Ops;
+
+    #[test]
+    fn test_buffered() {
+        let mut a = Shr3Buffered::new(Shr3::new_state(42));
+        let mut b = Shr3::new_state(42);
+        let w0 = b.get_word32();
+        let w1 = b.get_word32();
+        let x: u8 = a.get_bits(3);
+        assert_eq!(x as u32, w0 >> 29);
+        assert_eq!(a.cached_bits(), 29);
+        let x: u8 = a.get_bits(5);
+        assert_eq!(x as u32, (w0 >> 24) & 0x1F);
+        let x: u32 = a.get();
+        assert_eq!(x, (w0 << 8) | (w1 >> 24));
+        assert_eq!(a.cached_bits(), 24);
+        assert_eq!(a.inner().state(), w1);
+
+        a.clear_cache();
+        assert_eq!(a.cached_bits(), 0);
+        let x: u32 = a.get();
+        assert_eq!(x, b.get_word32());
+
+        let mut a = Shr3Buffered::new(Shr3::new_state(42));
+        for _ in 0..1000 {
+            let x: u8 = a.get_range(1..7);
+            assert!((1..7).contains(&x));
+        }
+        let state = a.inner().state();
+        assert_eq!(a.into_inner().state(), state);
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,58 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! ASCII character sets for `Shr3::fill_charset()`.
+
+/// Decimal digits `0-9`.
+pub const DIGITS: &[u8] = b"0123456789";
+
+/// Lower case hexadecimal digits `0-9a-f`.
+pub const HEX_LOWER: &[u8] = b"0123456789abcdef";
+
+/// Upper case hexadecimal digits `0-9A-F`.
+pub const HEX_UPPER: &[u8] = b"0123456789ABCDEF";
+
+/// Crockford's base32 alphabet `0-9A-Z` without `I`, `L`, `O` and `U`.
+pub const CROCKFORD_BASE32: &[u8] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Digits, upper case letters and lower case letters `0-9A-Za-z`.
+pub const ALPHANUMERIC: &[u8] = b"0123456789\
+                                  ABCDEFGHIJKLMNOPQRSTUVWXYZ\
+                                  abcdefghijklmnopqrstuvwxyz";
+
+/// All printable ASCII characters including space `0x20-0x7E`.
+pub const PRINTABLE: &[u8] = b" !\"#$%&'()*+,-./\
+                               0123456789:;<=>?\
+                               @ABCDEFGHIJKLMNO\
+                               PQRSTUVWXYZ[\\]^_\
+                               `abcdefghijklmno\
+                               pqrstuvwxyz{|}~";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_charsets() {
+        assert_eq!(DIGITS.len(), 10);
+        assert!(DIGITS.iter().all(u8::is_ascii_digit));
+        assert_eq!(HEX_LOWER.len(), 16);
+        assert!(HEX_LOWER.iter().all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase()));
+        assert_eq!(HEX_UPPER.len(), 16);
+        assert!(HEX_UPPER.iter().all(|c| c.is_ascii_hexdigit() && !c.is_ascii_lowercase()));
+        assert_eq!(CROCKFORD_BASE32.len(), 32);
+        assert!(CROCKFORD_BASE32.iter().all(|c| c.is_ascii_alphanumeric() && !b"ILOU".contains(c)));
+        assert_eq!(ALPHANUMERIC.len(), 62);
+        assert!(ALPHANUMERIC.iter().all(u8::is_ascii_alphanumeric));
+        assert_eq!(PRINTABLE.len(), 95);
+        assert!(PRINTABLE.iter().enumerate().all(|(i, c)| *c == 0x20 + i as u8));
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,95 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Helpers for the collections of the `heapless` crate.
+//!
+//! `heapless::Vec` and `heapless::String` dereference to slices and `str`.
+//! Therefore, `Shr3::choose()` and `Shr3::shuffle()` can be used on them directly.
+
+use crate::{BaseOps, Shr3, Shr3Ops};
+use core::num::Wrapping;
+use core::ops::{Add, Sub};
+use heapless::{String, Vec};
+
+impl Shr3 {
+    /// Append random values to `vec` until it is full.
+    ///
+    /// Each value is extracted like `Shr3Ops::get()`.
+    /// The elements already in `vec` are kept.
+    pub fn fill_vec<T, const N: usize>(&mut self, vec: &mut Vec<T, N>)
+        where Shr3: Shr3Ops<T>,
+              T: BaseOps + PartialOrd,
+              T::U: BaseOps,
+              Wrapping<T::U>: Sub<Output=Wrapping<T::U>> + Add<Output=Wrapping<T::U>> + PartialOrd,
+    {
+        while !vec.is_full() {
+            let _ = vec.push(self.get());
+        }
+    }
+
+    /// Append random characters from `charset` to `string` until it is full.
+    ///
+    /// Each character is chosen like `Shr3::choose()`.
+    /// The characters already in `string` are kept.
+    ///
+    /// See the `charset` module for predefined character sets.
+    ///
+    /// Panics, if `charset` is empty or if it contains non-ASCII characters.
+    pub fn fill_string<const N: usize>(&mut self, string: &mut String<N>, charset: &[u8]) {
+        assert!(!charset.is_empty(), "fill_string: The charset must not be empty.");
+        assert!(charset.is_ascii(), "fill_string: The charset must only contain ASCII characters.");
+        while string.len() < string.capacity() {
+            let c = *self.choose(charset).unwrap();
+            let _ = string.push(c as char);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vec() {
+        let mut a = Shr3::new_state(42);
+        let mut vec: Vec<u16, 6> = Vec::new();
+        let _ = vec.push(0x1234);
+        a.fill_vec(&mut vec);
+        assert_eq!(vec, [0x1234, 0x20D3, 0x2C5C, 0x2A17, 0xD3C5, 0xAF08]);
+        a.fill_vec(&mut vec);
+        assert_eq!(vec.len(), 6);
+
+        let x = *a.choose(&vec).unwrap();
+        assert!(vec.contains(&x));
+        a.shuffle(&mut vec);
+        vec.sort_unstable();
+        assert_eq!(vec, [0x1234, 0x20D3, 0x2A17, 0x2C5C, 0xAF08, 0xD3C5]);
+    }
+
+    #[test]
+    fn test_string() {
+        let mut a = Shr3::new_state(42);
+        let mut string: String<32> = String::new();
+        let _ = string.push_str("id-");
+        a.fill_string(&mut string, b"abc");
+        assert_eq!(string.len(), 32);
+        assert!(string.starts_with("id-"));
+        assert!(string[3..].bytes().all(|c| b"abc".contains(&c)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_string_non_ascii() {
+        let mut a = Shr3::new_state(42);
+        let mut string: String<4> = String::new();
+        a.fill_string(&mut string, &[0xC4]);
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
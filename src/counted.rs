@@ -0,0 +1,230 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! SHR3 wrapper that counts the extracted bits.
+
+use crate::gf2::{PERIOD, jump};
+use crate::{BaseOps, Shr3, Shr3Ops};
+use core::num::Wrapping;
+use core::ops::{Add, BitOrAssign, ShlAssign, Sub};
+
+/// SHR3 generator that counts the number of extracted bits.
+///
+/// One bit is extracted per SHR3 round.
+/// Therefore, the count is also the number of SHR3 rounds since construction.
+/// The random stream is identical to the stream of the wrapped `Shr3`.
+///
+/// The SHR3 stream repeats after `2**32 - 1` rounds.
+/// Use `is_exhausted()` or `remaining()` to detect
+/// when the stream has repeated or approaches its repetition.
+///
+/// *Note*: Only extractions through `Shr3Ops` are counted.
+///
+/// ```
+///     use shr3::prelude::*;
+///     use shr3::Shr3Counted;
+///
+///     let mut shr3 = Shr3Counted::new(Shr3::new_state(42));
+///     let _: u16 = shr3.get();
+///     let _: u8 = shr3.get_bits(3);
+///     assert_eq!(shr3.position(), 19);
+/// ```
+pub struct Shr3Counted {
+    shr3: Shr3,
+    position: u64,
+}
+
+impl Shr3Counted {
+    /// Period of the SHR3 stream in rounds.
+    pub const PERIOD: u64 = PERIOD;
+
+    /// Wrap `shr3` with a count of 0.
+    #[inline]
+    pub const fn new(shr3: Shr3) -> Self {
+        Self {
+            shr3,
+            position: 0,
+        }
+    }
+
+    /// Get the number of bits extracted since construction.
+    #[inline]
+    pub const fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Check whether the random stream has wrapped around.
+    ///
+    /// Returns `true`, if `Shr3Counted::PERIOD` or more bits have been extracted since construction.
+    /// All following extracted bits repeat the already extracted stream.
+    #[inline]
+    pub const fn is_exhausted(&self) -> bool {
+        self.position >= PERIOD
+    }
+
+    /// Get the number of bits that can be extracted before the random stream wraps around.
+    ///
+    /// Returns 0, if the stream is exhausted.
+    #[inline]
+    pub const fn remaining(&self) -> u64 {
+        PERIOD.saturating_sub(self.position)
+    }
+
+    /// Set the generator to the state that is reached after
+    /// `position` extracted bits (rounds) since construction.
+    ///
+    /// Seeking forward and backward is possible.
+    /// This does not iterate over the skipped rounds.
+    /// It uses GF(2) matrix exponentiation with `O(log position)` matrix squarings.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///     use shr3::Shr3Counted;
+    ///
+    ///     let mut a = Shr3Counted::new(Shr3::new_state(42));
+    ///     let mut b = Shr3Counted::new(Shr3::new_state(42));
+    ///     let _: u64 = a.get();
+    ///     b.seek(64);
+    ///     let x: u32 = a.get();
+    ///     let y: u32 = b.get();
+    ///     assert_eq!(x, y);
+    /// ```
+    pub fn seek(&mut self, position: u64) {
+        let delta = (position % PERIOD + PERIOD - self.position % PERIOD) % PERIOD;
+        self.shr3.set_state(jump(self.shr3.state(), delta));
+        self.position = position;
+    }
+
+    /// Skip `rounds` SHR3 rounds (extracted bits) of the random stream.
+    ///
+    /// The skipped rounds are counted.
+    /// See `Shr3::discard()`.
+    #[inline]
+    pub fn discard(&mut self, rounds: u64) {
+        self.shr3.discard(rounds);
+        self.position += rounds;
+    }
+
+    /// Get a reference to the wrapped generator.
+    #[inline]
+    pub const fn inner(&self) -> &Shr3 {
+        &self.shr3
+    }
+
+    /// Unwrap the generator.
+    #[inline]
+    pub fn into_inner(self) -> Shr3 {
+        self.shr3
+    }
+}
+
+impl<T> Shr3Ops<T> for Shr3Counted
+    where T: BaseOps + PartialOrd,
+          T::U: BaseOps,
+          Wrapping<T::U>: Sub<Output=Wrapping<T::U>> + Add<Output=Wrapping<T::U>> + PartialOrd + ShlAssign<usize> + BitOrAssign,
+{
+    #[inline]
+    fn get_bits(&mut self, bitcount: u8) -> T {
+        T::from_unsigned(Shr3Ops::<T>::get_bits_unsigned(self, bitcount))
+    }
+
+    #[inline]
+    fn get_bits_unsigned(&mut self, bitcount: u8) -> Wrapping<T::U> {
+        self.position += bitcount as u64;
+        Shr3Ops::<T>::get_bits_unsigned(&mut self.shr3, bitcount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counted() {
+        let mut a = Shr3Counted::new(Shr3::new_state(42));
+        let mut b = Shr3::new_state(42);
+        assert_eq!(a.position(), 0);
+        for _ in 0..10 {
+            assert_eq!(Shr3Ops::<u16>::get(&mut a), Shr3Ops::<u16>::get(&mut b));
+        }
+        assert_eq!(a.position(), 160);
+        let x: u64 = a.get();
+        assert_eq!(x, Shr3Ops::<u64>::get(&mut b));
+        assert_eq!(a.position(), 224);
+        let x: i8 = a.get_bits(3);
+        assert_eq!(x, Shr3Ops::<i8>::get_bits(&mut b, 3));
+        assert_eq!(a.position(), 227);
+        let x: [char; 3] = a.get_array();
+        assert_eq!(x, b.get_array());
+        assert_eq!(a.inner().state(), b.state());
+        assert!(a.position() >= 227 + 3 * 21);
+        assert_eq!(a.into_inner().state(), b.state());
+
+        // A rejected draw counts all of its bits.
+        let mut a = Shr3Counted::new(Shr3::new_state(42));
+        for _ in 0..100 {
+            let _: u8 = a.get_max(128);
+        }
+        assert!(a.position() > 800);
+        assert_eq!(a.position() % 8, 0);
+    }
+
+    #[test]
+    fn test_exhausted() {
+        let mut a = Shr3Counted::new(Shr3::new_state(42));
+        assert!(!a.is_exhausted());
+        assert_eq!(a.remaining(), Shr3Counted::PERIOD);
+        let _: u32 = a.get();
+        assert_eq!(a.remaining(), Shr3Counted::PERIOD - 32);
+        a.discard(Shr3Counted::PERIOD - 33);
+        assert!(!a.is_exhausted());
+        assert_eq!(a.remaining(), 1);
+        let _: u8 = a.get_bits(1);
+        assert!(a.is_exhausted());
+        assert_eq!(a.remaining(), 0);
+        assert_eq!(a.inner().state(), 42);
+        let _: u8 = a.get();
+        assert!(a.is_exhausted());
+        assert_eq!(a.remaining(), 0);
+    }
+
+    #[test]
+    fn test_seek() {
+        let mut a = Shr3Counted::new(Shr3::new_state(42));
+        let mut b = Shr3::new_state(42);
+        let _: u64 = b.get();
+        let exp: u32 = b.get();
+        a.seek(64);
+        assert_eq!(a.position(), 64);
+        let x: u32 = a.get();
+        assert_eq!(x, exp);
+        assert_eq!(a.position(), 96);
+
+        a.seek(0);
+        assert_eq!(a.inner().state(), 42);
+        let x: u16 = a.get();
+        assert_eq!(x, 0x20D3);
+
+        a.seek(Shr3Counted::PERIOD);
+        assert_eq!(a.inner().state(), 42);
+        a.seek(1_000_000_000_000);
+        let state = a.inner().state();
+        a.seek(5);
+        a.seek(1_000_000_000_000);
+        assert_eq!(a.inner().state(), state);
+
+        a.seek(0);
+        a.discard(64);
+        assert_eq!(a.position(), 64);
+        let x: u32 = a.get();
+        assert_eq!(x, exp);
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,32 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Random distributions on top of `Shr3Ops`.
+
+mod alias;
+mod bernoulli;
+mod exp;
+mod geometric;
+#[cfg(feature="libm")]
+mod normal;
+mod poisson;
+mod uniform;
+
+pub use alias::AliasTable;
+pub use bernoulli::Bernoulli;
+#[cfg(feature="libm")]
+pub use exp::Exp;
+pub use exp::ExpU32;
+pub use geometric::Geometric;
+#[cfg(feature="libm")]
+pub use normal::Normal;
+pub use poisson::Poisson;
+pub use uniform::Uniform;
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,166 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+use crate::Shr3Ops;
+
+/// Walker alias table for repeated weighted sampling of `N` indices.
+///
+/// The probability of each index is proportional to its weight.
+/// The table is built once with Vose's algorithm in exact integer arithmetic.
+/// The construction is a `const fn`, so the table can be built at compile time.
+///
+/// Each `sample()` costs two unbiased bounded draws,
+/// independent of the number of weights.
+///
+/// ```
+///     use shr3::prelude::*;
+///     use shr3::dist::AliasTable;
+///
+///     const TABLE: AliasTable<4> = AliasTable::new(&[1, 2, 0, 5]);
+///
+///     let mut shr3 = Shr3::new();
+///     let index = TABLE.sample(&mut shr3);
+///     assert_ne!(index, 2);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AliasTable<const N: usize> {
+    /// Probability threshold of each index in the range `0..=sum`.
+    prob: [u32; N],
+    /// Alias index of each index.
+    alias: [usize; N],
+    /// Sum of all weights.
+    sum: u32,
+}
+
+impl<const N: usize> AliasTable<N> {
+    /// Build an alias table from the `weights`.
+    ///
+    /// The sum of all weights must not be 0 and it must fit into `u32`.
+    pub const fn new(weights: &[u16; N]) -> AliasTable<N> {
+        let mut sum = 0_u64;
+        let mut i = 0;
+        while i < N {
+            sum += weights[i] as u64;
+            i += 1;
+        }
+        assert!(sum > 0 && sum <= u32::MAX as u64);
+
+        // Scale the weights, so that the average scaled weight is `sum`.
+        let mut scaled = [0_u64; N];
+        let mut small = [0_usize; N];
+        let mut large = [0_usize; N];
+        let mut nr_small = 0;
+        let mut nr_large = 0;
+        let mut i = 0;
+        while i < N {
+            scaled[i] = weights[i] as u64 * N as u64;
+            if scaled[i] < sum {
+                small[nr_small] = i;
+                nr_small += 1;
+            } else {
+                large[nr_large] = i;
+                nr_large += 1;
+            }
+            i += 1;
+        }
+
+        let mut prob = [sum as u32; N];
+        let mut alias = [0_usize; N];
+        let mut i = 0;
+        while i < N {
+            alias[i] = i;
+            i += 1;
+        }
+        while nr_small > 0 && nr_large > 0 {
+            nr_small -= 1;
+            let s = small[nr_small];
+            let l = large[nr_large - 1];
+            prob[s] = scaled[s] as u32;
+            alias[s] = l;
+            // Move the remainder of the small bucket's slot to the large index.
+            scaled[l] = scaled[l] + scaled[s] - sum;
+            if scaled[l] < sum {
+                nr_large -= 1;
+                small[nr_small] = l;
+                nr_small += 1;
+            }
+        }
+        // All remaining indices have a probability of exactly `sum`.
+
+        AliasTable {
+            prob,
+            alias,
+            sum: sum as u32,
+        }
+    }
+
+    /// Get a random index with a probability proportional to its weight.
+    pub fn sample<R>(&self, rng: &mut R) -> usize
+        where R: Shr3Ops<usize> + Shr3Ops<u32>,
+    {
+        let i: usize = rng.get_max(N - 1);
+        let x: u32 = rng.get_max(self.sum - 1);
+        if x < self.prob[i] {
+            i
+        } else {
+            self.alias[i]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shr3;
+
+    fn check<const N: usize>(weights: &[u16; N]) {
+        let table = AliasTable::new(weights);
+        let sum: u32 = weights.iter().map(|w| *w as u32).sum();
+
+        // Exact check of the table: Sum up the probability mass of each index.
+        let mut mass = [0_u64; N];
+        for i in 0..N {
+            mass[i] += table.prob[i] as u64;
+            mass[table.alias[i]] += (sum - table.prob[i]) as u64;
+        }
+        for i in 0..N {
+            assert_eq!(mass[i], weights[i] as u64 * N as u64);
+        }
+
+        // Statistical check.
+        let mut a = Shr3::new_state(42);
+        let mut count = [0_u32; N];
+        const COUNT: u32 = 20000;
+        for _ in 0..COUNT {
+            count[table.sample(&mut a)] += 1;
+        }
+        for i in 0..N {
+            let exp = COUNT * weights[i] as u32 / sum;
+            assert!(count[i].abs_diff(exp) <= exp / 10 + 10);
+        }
+    }
+
+    #[test]
+    fn test_alias() {
+        check(&[1]);
+        check(&[1, 1]);
+        check(&[0, 5]);
+        check(&[1, 2, 3, 4]);
+        check(&[10, 0, 0, 1, 7, 100, 3]);
+        check(&[u16::MAX, 1, u16::MAX, 0]);
+
+        const TABLE: AliasTable<3> = AliasTable::new(&[0, 0, 3]);
+        let mut a = Shr3::new_state(42);
+        for _ in 0..100 {
+            assert_eq!(TABLE.sample(&mut a), 2);
+        }
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
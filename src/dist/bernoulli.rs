@@ -0,0 +1,167 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+use crate::Shr3Ops;
+
+/// Bernoulli distribution.
+///
+/// The probability is stored as a precomputed Q0.32 fixed point threshold.
+/// Each `sample()` extracts exactly 32 bits and compares them to the threshold.
+/// There is no division or rejection loop on the sampling path.
+///
+/// The probability is rounded down to a multiple of `2**-32`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bernoulli {
+    /// Q0.32 probability threshold.
+    threshold: u32,
+    /// Probability is exactly 1.
+    always: bool,
+}
+
+impl Bernoulli {
+    /// Create a Bernoulli distribution with the probability `numerator / denominator`.
+    ///
+    /// `denominator` must not be 0 and `numerator` must be lower or equal to `denominator`.
+    pub const fn from_ratio(numerator: u32, denominator: u32) -> Bernoulli {
+        assert!(denominator > 0);
+        assert!(numerator <= denominator);
+        if numerator == denominator {
+            Bernoulli {
+                threshold: 0,
+                always: true,
+            }
+        } else {
+            Bernoulli {
+                threshold: (((numerator as u64) << 32) / denominator as u64) as u32,
+                always: false,
+            }
+        }
+    }
+
+    /// Create a Bernoulli distribution with the probability `threshold / 2**32`.
+    pub const fn from_q32(threshold: u32) -> Bernoulli {
+        Bernoulli {
+            threshold,
+            always: false,
+        }
+    }
+
+    /// Create a Bernoulli distribution with the probability `p`.
+    ///
+    /// `p` must be in the range `[0, 1]`.
+    pub fn new(p: f64) -> Bernoulli {
+        assert!((0.0..=1.0).contains(&p));
+        if p >= 1.0 {
+            Self::from_ratio(1, 1)
+        } else {
+            Self::from_q32((p * 4294967296.0) as u32)
+        }
+    }
+
+    /// Return `true` with the probability of this distribution.
+    #[inline]
+    pub fn sample<R: Shr3Ops<u32>>(&self, rng: &mut R) -> bool {
+        let value: u32 = rng.get();
+        self.always || value < self.threshold
+    }
+
+    /// Return `true` with the probability of this distribution.
+    ///
+    /// In contrast to `sample()` this compares the random bits to the threshold
+    /// lazily from the most significant bit to the least significant bit.
+    /// It stops as soon as the result is known.
+    /// On average that extracts only 2 bits.
+    pub(super) fn sample_lazy<R: Shr3Ops<u8>>(&self, rng: &mut R) -> bool {
+        if self.always {
+            return true;
+        }
+        for i in (0..32).rev() {
+            let bit: u8 = rng.get_bits(1);
+            let threshold_bit = ((self.threshold >> i) & 1) as u8;
+            if bit != threshold_bit {
+                return bit < threshold_bit;
+            }
+        }
+        false // equal
+    }
+
+    /// Check whether the probability is zero.
+    #[inline]
+    pub(super) const fn is_never(&self) -> bool {
+        !self.always && self.threshold == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shr3;
+
+    #[test]
+    fn test_bernoulli() {
+        assert_eq!(Bernoulli::from_ratio(1, 2), Bernoulli::from_q32(0x8000_0000));
+        assert_eq!(Bernoulli::from_ratio(1, 4), Bernoulli::new(0.25));
+        assert_eq!(Bernoulli::from_ratio(0, 3), Bernoulli::new(0.0));
+        assert_eq!(Bernoulli::from_ratio(3, 3), Bernoulli::new(1.0));
+        assert_eq!(Bernoulli::from_ratio(1, 3).threshold, 0x5555_5555);
+
+        let mut a = Shr3::new_state(42);
+        let never = Bernoulli::from_ratio(0, 5);
+        let always = Bernoulli::from_ratio(5, 5);
+        for _ in 0..1000 {
+            assert!(!never.sample(&mut a));
+            assert!(always.sample(&mut a));
+        }
+
+        let d = Bernoulli::from_ratio(1, 4);
+        let count = (0..10000).filter(|_| d.sample(&mut a)).count();
+        assert!((2300..2700).contains(&count));
+        let d = Bernoulli::new(0.9);
+        let count = (0..10000).filter(|_| d.sample(&mut a)).count();
+        assert!((8800..9200).contains(&count));
+    }
+
+    #[test]
+    fn test_bernoulli_draw() {
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        let d = Bernoulli::from_ratio(1, 2);
+        for _ in 0..100 {
+            let value: u32 = b.get();
+            assert_eq!(d.sample(&mut a), value < 0x8000_0000);
+        }
+    }
+
+    #[test]
+    fn test_bernoulli_lazy() {
+        let mut a = Shr3::new_state(42);
+        let never = Bernoulli::from_ratio(0, 5);
+        let always = Bernoulli::from_ratio(5, 5);
+        for _ in 0..1000 {
+            assert!(!never.sample_lazy(&mut a));
+            assert!(always.sample_lazy(&mut a));
+        }
+        assert!(never.is_never());
+        assert!(!always.is_never());
+
+        let d = Bernoulli::from_ratio(1, 3);
+        let count = (0..10000).filter(|_| d.sample_lazy(&mut a)).count();
+        assert!((3150..3500).contains(&count));
+
+        let d = Bernoulli::from_ratio(1, 2);
+        for seed in 1..100 {
+            let mut a = Shr3::new_state(seed);
+            let mut b = Shr3::new_state(seed);
+            let bit: u8 = b.get_bits(1);
+            assert_eq!(d.sample_lazy(&mut a), bit == 0);
+        }
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,206 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+use crate::Shr3Ops;
+
+/// Exponential distribution with the rate `lambda`.
+///
+/// The samples are generated by inversion: `-ln(u) / lambda`.
+/// Each `sample()` extracts exactly 53 bits.
+///
+/// This requires the `libm` feature.
+/// See `ExpU32` for an integer only alternative.
+#[cfg(feature="libm")]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Exp {
+    lambda: f64,
+}
+
+#[cfg(feature="libm")]
+impl Exp {
+    /// Create an exponential distribution with the rate `lambda`.
+    ///
+    /// The mean of the distribution is `1 / lambda`.
+    /// `lambda` must be bigger than zero.
+    pub fn new(lambda: f64) -> Exp {
+        assert!(lambda > 0.0);
+        Exp {
+            lambda,
+        }
+    }
+
+    /// Get the rate of the distribution.
+    #[inline]
+    pub fn lambda(&self) -> f64 {
+        self.lambda
+    }
+
+    /// Get a random sample from the distribution.
+    pub fn sample<R: Shr3Ops<u64>>(&self, rng: &mut R) -> f64 {
+        const BITS: u32 = f64::MANTISSA_DIGITS;
+        const SCALE: f64 = 1.0 / (1_u64 << BITS) as f64;
+
+        // u in (0, 1]
+        let u: u64 = rng.get_bits(BITS as u8);
+        let u = (u + 1) as f64 * SCALE;
+
+        -libm::log(u) / self.lambda
+    }
+}
+
+/// `log2(1 + i / 64)` in Q0.16 format.
+const LOG2_TAB: [u16; 64] = [
+        0,  1466,  2909,  4331,  5732,  7112,  8473,  9814,
+    11136, 12440, 13727, 14996, 16248, 17484, 18704, 19909,
+    21098, 22272, 23433, 24579, 25711, 26830, 27936, 29029,
+    30109, 31178, 32234, 33279, 34312, 35334, 36346, 37346,
+    38336, 39316, 40286, 41246, 42196, 43137, 44068, 44990,
+    45904, 46809, 47705, 48593, 49472, 50344, 51207, 52063,
+    52911, 53751, 54584, 55410, 56229, 57040, 57845, 58643,
+    59434, 60219, 60997, 61769, 62534, 63294, 64047, 64794,
+];
+
+/// `ln(2)` in Q0.16 format.
+const LN2_Q16: u32 = 45426;
+
+/// Calculate `-ln(v / 2**32)` in Q16.16 format.
+///
+/// The logarithm is interpolated linearly from a 64 entry table.
+/// The absolute error is below `0.0001`.
+fn neg_ln_q16(v: u32) -> u32 {
+    if v == 0 {
+        return 32 * LN2_Q16;
+    }
+    // Normalize v to m = 1.f * 2**31
+    let lz = v.leading_zeros();
+    let f = (v << lz) & 0x7FFF_FFFF;
+    // log2(1.f)
+    let idx = (f >> 25) as usize;
+    let frac = (f >> 9) & 0xFFFF;
+    let y0 = LOG2_TAB[idx] as u32;
+    let y1 = if idx < LOG2_TAB.len() - 1 { LOG2_TAB[idx + 1] as u32 } else { 1 << 16 };
+    let log2_m = y0 + (((y1 - y0) * frac) >> 16);
+    // -log2(v / 2**32) = lz + 1 - log2(1.f)
+    let neg_log2 = ((lz + 1) << 16) - log2_m;
+    ((neg_log2 as u64 * LN2_Q16 as u64) >> 16) as u32
+}
+
+/// Integer only exponential distribution with the given `mean`.
+///
+/// The samples are generated by inversion: `-ln(u) * mean`.
+/// The logarithm is calculated with a small table and linear interpolation.
+/// No floating point arithmetic is used.
+/// Each `sample()` extracts exactly 32 bits.
+///
+/// The result is rounded to an integer.
+/// Samples beyond approximately `22 * mean` are not generated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ExpU32 {
+    mean: u32,
+}
+
+impl ExpU32 {
+    /// Create an integer exponential distribution with the given `mean`.
+    pub const fn new(mean: u32) -> ExpU32 {
+        ExpU32 {
+            mean,
+        }
+    }
+
+    /// Get the mean of the distribution.
+    #[inline]
+    pub fn mean(&self) -> u32 {
+        self.mean
+    }
+
+    /// Get a random sample from the distribution.
+    ///
+    /// The result is saturated to `u32::MAX`.
+    pub fn sample<R: Shr3Ops<u32>>(&self, rng: &mut R) -> u32 {
+        let u: u32 = rng.get();
+        let x = neg_ln_q16(u) as u64;
+        let value = (x * self.mean as u64 + 0x8000) >> 16;
+        value.min(u32::MAX as u64) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shr3;
+
+    #[test]
+    fn test_neg_ln() {
+        assert_eq!(neg_ln_q16(0x8000_0000), LN2_Q16);
+        assert_eq!(neg_ln_q16(0x4000_0000), 2 * LN2_Q16);
+        assert_eq!(neg_ln_q16(0x0000_0001), 32 * LN2_Q16);
+        assert_eq!(neg_ln_q16(0), 32 * LN2_Q16);
+        assert!(neg_ln_q16(0xFFFF_FFFF) < 2);
+        // -ln(0.75) = 0.287682
+        assert!(neg_ln_q16(0xC000_0000).abs_diff(18854) <= 4);
+        // -ln(0.1) = 2.302585
+        assert!(neg_ln_q16(0x1999_999A).abs_diff(150902) <= 4);
+    }
+
+    #[test]
+    fn test_exp_u32() {
+        let mut a = Shr3::new_state(42);
+        let d = ExpU32::new(1000);
+        assert_eq!(d.mean(), 1000);
+
+        const COUNT: u64 = 10000;
+        let mut sum = 0;
+        let mut above_mean = 0;
+        let mut above_2mean = 0;
+        for _ in 0..COUNT {
+            let x = d.sample(&mut a);
+            sum += x as u64;
+            if x > 1000 {
+                above_mean += 1;
+            }
+            if x > 2000 {
+                above_2mean += 1;
+            }
+        }
+        assert!((970..1030).contains(&(sum / COUNT)));
+        // exp(-1) = 0.3679
+        assert!((3500..3850).contains(&above_mean));
+        // exp(-2) = 0.1353
+        assert!((1250..1450).contains(&above_2mean));
+
+        let d = ExpU32::new(0);
+        assert_eq!(d.sample(&mut a), 0);
+        let d = ExpU32::new(u32::MAX);
+        let _ = d.sample(&mut a);
+    }
+
+    #[cfg(feature="libm")]
+    #[test]
+    fn test_exp() {
+        let mut a = Shr3::new_state(42);
+        let d = Exp::new(0.5);
+        assert_eq!(d.lambda(), 0.5);
+
+        const COUNT: usize = 10000;
+        let mut sum = 0.0;
+        let mut above_mean = 0;
+        for _ in 0..COUNT {
+            let x = d.sample(&mut a);
+            assert!(x >= 0.0 && x.is_finite());
+            sum += x;
+            if x > 2.0 {
+                above_mean += 1;
+            }
+        }
+        assert!((sum / COUNT as f64 - 2.0).abs() < 0.06);
+        assert!((3500..3850).contains(&above_mean));
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
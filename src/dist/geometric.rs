@@ -0,0 +1,95 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+use crate::Shr3Ops;
+use crate::dist::Bernoulli;
+
+/// Geometric distribution.
+///
+/// A sample is the number of failed Bernoulli trials before the first success.
+///
+/// Each Bernoulli trial compares the random bits lazily to the
+/// Q0.32 fixed point success probability, from the most significant bit downwards.
+/// The comparison stops as soon as the first bit differs.
+/// Therefore, a trial only extracts 2 bits on average.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Geometric {
+    p: Bernoulli,
+}
+
+impl Geometric {
+    /// Create a geometric distribution with the success probability `numerator / denominator`.
+    ///
+    /// `numerator` must not be 0 and must be lower or equal to `denominator`.
+    pub const fn from_ratio(numerator: u32, denominator: u32) -> Geometric {
+        Self::from_bernoulli(Bernoulli::from_ratio(numerator, denominator))
+    }
+
+    /// Create a geometric distribution with the success probability `threshold / 2**32`.
+    ///
+    /// `threshold` must not be 0.
+    pub const fn from_q32(threshold: u32) -> Geometric {
+        Self::from_bernoulli(Bernoulli::from_q32(threshold))
+    }
+
+    /// Create a geometric distribution with the success probability `p`.
+    ///
+    /// `p` must be in the range `(0, 1]` and must not be rounded to 0 in Q0.32 format.
+    pub fn new(p: f64) -> Geometric {
+        Self::from_bernoulli(Bernoulli::new(p))
+    }
+
+    const fn from_bernoulli(p: Bernoulli) -> Geometric {
+        assert!(!p.is_never());
+        Geometric {
+            p,
+        }
+    }
+
+    /// Get a random sample from the distribution.
+    ///
+    /// The result is saturated to `u32::MAX`.
+    pub fn sample<R: Shr3Ops<u8>>(&self, rng: &mut R) -> u32 {
+        let mut failures = 0_u32;
+        while failures < u32::MAX && !self.p.sample_lazy(rng) {
+            failures += 1;
+        }
+        failures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shr3;
+
+    #[test]
+    fn test_geometric() {
+        let mut a = Shr3::new_state(42);
+        let d = Geometric::from_ratio(1, 1);
+        for _ in 0..100 {
+            assert_eq!(d.sample(&mut a), 0);
+        }
+
+        // Mean of failures: (1 - p) / p
+        for (d, exp_mean) in [(Geometric::from_ratio(1, 2), 1.0),
+                              (Geometric::from_ratio(1, 5), 4.0),
+                              (Geometric::new(0.05), 19.0)] {
+            const COUNT: usize = 10000;
+            let mut sum = 0;
+            for _ in 0..COUNT {
+                sum += d.sample(&mut a);
+            }
+            let mean = sum as f64 / COUNT as f64;
+            assert!((mean - exp_mean).abs() < exp_mean * 0.05);
+        }
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,102 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+use crate::Shr3Ops;
+use core::f64::consts::PI;
+
+/// Normal (Gaussian) distribution `N(mean, std_dev**2)`.
+///
+/// The samples are generated with the Box-Muller transform.
+/// Each `sample()` extracts exactly 106 bits (two times 53 bits).
+///
+/// This requires the `libm` feature.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Normal {
+    mean: f64,
+    std_dev: f64,
+}
+
+impl Normal {
+    /// Create a normal distribution with the given `mean` and standard deviation `std_dev`.
+    ///
+    /// `std_dev` must not be negative.
+    pub fn new(mean: f64, std_dev: f64) -> Normal {
+        assert!(std_dev >= 0.0);
+        Normal {
+            mean,
+            std_dev,
+        }
+    }
+
+    /// Get the mean of the distribution.
+    #[inline]
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Get the standard deviation of the distribution.
+    #[inline]
+    pub fn std_dev(&self) -> f64 {
+        self.std_dev
+    }
+
+    /// Get a random sample from the distribution.
+    pub fn sample<R: Shr3Ops<u64>>(&self, rng: &mut R) -> f64 {
+        const BITS: u32 = f64::MANTISSA_DIGITS;
+        const SCALE: f64 = 1.0 / (1_u64 << BITS) as f64;
+
+        // u1 in (0, 1]
+        let u1: u64 = rng.get_bits(BITS as u8);
+        let u1 = (u1 + 1) as f64 * SCALE;
+        // u2 in [0, 1)
+        let u2: u64 = rng.get_bits(BITS as u8);
+        let u2 = u2 as f64 * SCALE;
+
+        let z = libm::sqrt(-2.0 * libm::log(u1)) * libm::cos(2.0 * PI * u2);
+        self.mean + z * self.std_dev
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shr3;
+
+    #[test]
+    fn test_normal() {
+        let mut a = Shr3::new_state(42);
+        let d = Normal::new(10.0, 2.0);
+        assert_eq!(d.mean(), 10.0);
+        assert_eq!(d.std_dev(), 2.0);
+
+        const COUNT: usize = 10000;
+        let mut sum = 0.0;
+        let mut sum_sq = 0.0;
+        let mut within_1sd = 0;
+        for _ in 0..COUNT {
+            let x = d.sample(&mut a);
+            assert!(x.is_finite());
+            sum += x;
+            sum_sq += x * x;
+            if (8.0..=12.0).contains(&x) {
+                within_1sd += 1;
+            }
+        }
+        let mean = sum / COUNT as f64;
+        let var = sum_sq / COUNT as f64 - mean * mean;
+        assert!((mean - 10.0).abs() < 0.1);
+        assert!((var - 4.0).abs() < 0.3);
+        assert!((6600..7000).contains(&within_1sd));
+
+        let d = Normal::new(-3.0, 0.0);
+        assert_eq!(d.sample(&mut a), -3.0);
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
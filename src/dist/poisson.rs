@@ -0,0 +1,142 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+use crate::Shr3Ops;
+
+/// Biggest supported `lambda`.
+const LAMBDA_MAX: f64 = 32.0;
+
+/// Calculate `exp(-x)` for `x` in the range `[0, LAMBDA_MAX]`.
+///
+/// This only uses basic arithmetic and does not depend on `libm`.
+fn exp_neg(x: f64) -> f64 {
+    // exp(-x) = exp(-1)**int(x) * exp(-frac(x))
+    let int = x as u32;
+    let frac = x - int as f64;
+    // Taylor series of exp(-frac).
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    for n in 1..=20 {
+        term *= -frac / n as f64;
+        sum += term;
+    }
+    for _ in 0..int {
+        sum *= 1.0 / core::f64::consts::E;
+    }
+    sum
+}
+
+/// Multiply the Q0.64 value `p` with the Q0.32 value `u`. The result is Q0.64.
+#[inline]
+fn mul_q64_q32(p: u64, u: u32) -> u64 {
+    let hi = (p >> 32) * u as u64;
+    let lo = ((p & 0xFFFF_FFFF) * u as u64) >> 32;
+    hi + lo
+}
+
+/// Poisson distribution for small rates `lambda`.
+///
+/// The samples are generated with Knuth's multiplication method in Q0.64 fixed point arithmetic.
+/// The threshold `exp(-lambda)` is precomputed once.
+/// Each `sample()` extracts `32 * (k + 1)` bits for a result of `k`.
+/// Therefore, the runtime grows linearly with `lambda`.
+///
+/// No floating point arithmetic is used for sampling.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Poisson {
+    /// Q0.64 threshold exp(-lambda).
+    threshold: u64,
+}
+
+impl Poisson {
+    /// Create a Poisson distribution with the rate `lambda`.
+    ///
+    /// `lambda` must be in the range `[0, 32]`.
+    pub fn new(lambda: f64) -> Poisson {
+        assert!((0.0..=LAMBDA_MAX).contains(&lambda));
+        let threshold = if lambda == 0.0 {
+            u64::MAX
+        } else {
+            (exp_neg(lambda) * 18446744073709551616.0) as u64
+        };
+        Poisson {
+            threshold,
+        }
+    }
+
+    /// Get a random sample from the distribution.
+    pub fn sample<R: Shr3Ops<u32>>(&self, rng: &mut R) -> u32 {
+        let mut k = 0;
+        let mut p = u64::MAX;
+        loop {
+            let u: u32 = rng.get();
+            p = mul_q64_q32(p, u);
+            if p <= self.threshold {
+                break k;
+            }
+            k += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shr3;
+
+    #[test]
+    fn test_exp_neg() {
+        assert_eq!(exp_neg(0.0), 1.0);
+        assert!((exp_neg(0.5) - 0.6065306597126334).abs() < 1e-15);
+        assert!((exp_neg(1.0) - 0.36787944117144233).abs() < 1e-15);
+        assert!((exp_neg(2.7) - 0.06720551273974976).abs() < 1e-15);
+        assert!((exp_neg(32.0) - 1.2664165549094176e-14).abs() < 1e-27);
+    }
+
+    #[test]
+    fn test_mul() {
+        assert_eq!(mul_q64_q32(0x8000_0000_0000_0000, 0x8000_0000), 0x4000_0000_0000_0000);
+        assert_eq!(mul_q64_q32(u64::MAX, 0), 0);
+        assert_eq!(mul_q64_q32(0, u32::MAX), 0);
+        assert_eq!(mul_q64_q32(u64::MAX, u32::MAX), 0xFFFF_FFFE_FFFF_FFFF);
+    }
+
+    #[test]
+    fn test_poisson() {
+        let mut a = Shr3::new_state(42);
+        let d = Poisson::new(0.0);
+        for _ in 0..100 {
+            assert_eq!(d.sample(&mut a), 0);
+        }
+
+        for lambda in [0.1, 1.0, 3.5, 10.0] {
+            let d = Poisson::new(lambda);
+            const COUNT: usize = 10000;
+            let mut sum = 0;
+            let mut sum_sq = 0;
+            for _ in 0..COUNT {
+                let k = d.sample(&mut a) as u64;
+                sum += k;
+                sum_sq += k * k;
+            }
+            // Mean and variance both are lambda.
+            let mean = sum as f64 / COUNT as f64;
+            let var = sum_sq as f64 / COUNT as f64 - mean * mean;
+            assert!((mean - lambda).abs() < lambda * 0.05);
+            assert!((var - lambda).abs() < lambda * 0.1);
+        }
+
+        // P(0) = exp(-1)
+        let d = Poisson::new(1.0);
+        let zeros = (0..10000).filter(|_| d.sample(&mut a) == 0).count();
+        assert!((3500..3850).contains(&zeros));
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
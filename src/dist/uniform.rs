@@ -0,0 +1,114 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+use crate::{BaseOps, Shr3Ops, range_to_minmax};
+use core::num::Wrapping;
+use core::ops::{Add, RangeBounds, Sub};
+
+/// Uniform distribution over a fixed range of values of type `T`.
+///
+/// The range size and the number of bits to extract are precomputed once.
+/// The extracted random stream is identical to `Shr3Ops::get_minmax()`
+/// with the same range.
+pub struct Uniform<T: BaseOps> {
+    /// Lower bound as unsigned.
+    min: Wrapping<T::U>,
+    /// Range size minus one as unsigned.
+    range: Wrapping<T::U>,
+    /// Number of bits to extract per draw.
+    num_bits: u8,
+}
+
+impl<T> Uniform<T>
+    where T: BaseOps + PartialOrd,
+          T::U: BaseOps,
+          Wrapping<T::U>: Sub<Output=Wrapping<T::U>> + Add<Output=Wrapping<T::U>> + PartialOrd,
+{
+    /// Create a uniform distribution in the range between `min_value` and `max_value`.
+    pub fn new_minmax(min_value: T, max_value: T) -> Uniform<T> {
+        debug_assert!(max_value >= min_value);
+        let min = min_value.to_unsigned();
+        let range = max_value.to_unsigned() - min;
+        Uniform {
+            min,
+            range,
+            num_bits: range.0.fls(),
+        }
+    }
+
+    /// Create a uniform distribution in the given `range`.
+    pub fn new(range: impl RangeBounds<T>) -> Uniform<T> {
+        let (min, max) = range_to_minmax(&range);
+        Self::new_minmax(min, max)
+    }
+
+    /// Get a random sample from the distribution.
+    pub fn sample<R: Shr3Ops<T>>(&self, rng: &mut R) -> T {
+        loop {
+            let value = rng.get_bits_unsigned(self.num_bits);
+            if value <= self.range {
+                break T::from_unsigned(value + self.min);
+            }
+        }
+    }
+}
+
+impl<T: BaseOps> Clone for Uniform<T>
+    where T::U: Copy,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: BaseOps> Copy for Uniform<T>
+    where T::U: Copy,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shr3;
+
+    #[test]
+    fn test_uniform() {
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        let d = Uniform::new_minmax(60_u32, 170);
+        for _ in 0..1000 {
+            let exp: u32 = b.get_minmax(60, 170);
+            assert_eq!(d.sample(&mut a), exp);
+        }
+
+        let d = Uniform::new(-60_i16..170);
+        let d2 = d;
+        for _ in 0..1000 {
+            let exp: i16 = b.get_range(-60..170);
+            assert_eq!(d2.sample(&mut a), exp);
+        }
+
+        let d = Uniform::<u64>::new(..);
+        for _ in 0..1000 {
+            let exp: u64 = b.get();
+            assert_eq!(d.sample(&mut a), exp);
+        }
+
+        let d = Uniform::new('a'..='z');
+        for _ in 0..1000 {
+            assert!(d.sample(&mut a).is_ascii_lowercase());
+        }
+
+        let d = Uniform::new_minmax(7_u8, 7);
+        assert_eq!(d.sample(&mut a), 7);
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
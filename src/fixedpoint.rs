@@ -0,0 +1,143 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! `BaseOps` implementations for the fixed point types of the `fixed` crate.
+//!
+//! The random bits are mapped directly onto the raw representation of the fixed point value.
+//! Therefore, `BaseOps::from_u8()` constructs a value from raw bits
+//! and `from_u8(1)` is the smallest representable step (`DELTA`).
+
+use crate::BaseOps;
+use core::num::Wrapping;
+use fixed::types::extra::{LeEqU8, LeEqU16, LeEqU32, LeEqU64};
+use fixed::{
+    FixedI8, FixedU8,
+    FixedI16, FixedU16,
+    FixedI32, FixedU32,
+    FixedI64, FixedU64,
+};
+#[cfg(has_u128)]
+use fixed::types::extra::LeEqU128;
+#[cfg(has_u128)]
+use fixed::{FixedI128, FixedU128};
+
+macro_rules! impl_base_ops_fixed {
+    // $u = unsigned fixed type.
+    // $s = companion signed fixed type.
+    // $ui = unsigned raw integer type.
+    // $si = signed raw integer type.
+    // $le = Frac bound.
+    ($( ($u:ident, $s:ident, $ui:ty, $si:ty, $le:ident) ),*) => {
+        $(
+            impl<Frac: $le> BaseOps for $u<Frac> {
+                type U = $ui;
+                const NUMBITS: u8 = <$ui>::BITS as u8;
+                const MINVAL: Self = Self::MIN;
+                const MAXVAL: Self = Self::MAX;
+                #[inline]
+                fn from_u8(v: u8) -> Self {
+                    Self::from_bits(v as $ui)
+                }
+                #[inline]
+                fn from_unsigned(v: Wrapping<Self::U>) -> Self {
+                    Self::from_bits(v.0)
+                }
+                #[inline]
+                fn to_unsigned(&self) -> Wrapping<Self::U> {
+                    Wrapping(self.to_bits())
+                }
+                #[inline]
+                fn fls(&self) -> u8 {
+                    self.to_bits().fls()
+                }
+            }
+            impl<Frac: $le> BaseOps for $s<Frac> {
+                type U = $ui;
+                const NUMBITS: u8 = <$si>::BITS as u8;
+                const MINVAL: Self = Self::MIN;
+                const MAXVAL: Self = Self::MAX;
+                #[inline]
+                fn from_u8(v: u8) -> Self {
+                    Self::from_bits(v as $si)
+                }
+                #[inline]
+                fn from_unsigned(v: Wrapping<Self::U>) -> Self {
+                    Self::from_bits(v.0 as $si)
+                }
+                #[inline]
+                fn to_unsigned(&self) -> Wrapping<Self::U> {
+                    Wrapping(self.to_bits() as $ui)
+                }
+                #[inline]
+                fn fls(&self) -> u8 {
+                    self.to_bits().fls()
+                }
+            }
+        )*
+    }
+}
+
+impl_base_ops_fixed!(
+    (FixedU8, FixedI8, u8, i8, LeEqU8),
+    (FixedU16, FixedI16, u16, i16, LeEqU16),
+    (FixedU32, FixedI32, u32, i32, LeEqU32),
+    (FixedU64, FixedI64, u64, i64, LeEqU64)
+);
+#[cfg(has_u128)]
+impl_base_ops_fixed!((FixedU128, FixedI128, u128, i128, LeEqU128));
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use fixed::types::{I16F16, I8F8, U0F32, U8F8};
+
+    #[test]
+    fn test_get() {
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        let x: I16F16 = a.get();
+        let y: i32 = b.get();
+        assert_eq!(x.to_bits(), y);
+        let x: U0F32 = a.get();
+        let y: u32 = b.get();
+        assert_eq!(x.to_bits(), y);
+        let x: U8F8 = a.get_bits(5);
+        let y: u16 = b.get_bits(5);
+        assert_eq!(x.to_bits(), y);
+    }
+
+    #[test]
+    fn test_range() {
+        let mut a = Shr3::new_state(42);
+        let min = I8F8::from_num(-1.5);
+        let max = I8F8::from_num(2.25);
+        for _ in 0..1000 {
+            let x: I8F8 = a.get_range(min..=max);
+            assert!((min..=max).contains(&x));
+        }
+        for _ in 0..1000 {
+            let x: I8F8 = a.get_range(min..max);
+            assert!((min..max).contains(&x));
+        }
+        for _ in 0..1000 {
+            let x: I8F8 = a.get_minmax(min, max);
+            assert!((min..=max).contains(&x));
+        }
+        let x: I8F8 = a.get_range(min..min + I8F8::DELTA);
+        assert_eq!(x, min);
+
+        let max = U0F32::from_num(0.25);
+        for _ in 0..1000 {
+            let x: U0F32 = a.get_max(max);
+            assert!(x <= max);
+        }
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
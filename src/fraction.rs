@@ -0,0 +1,67 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Helpers for integer fixed point fractions.
+
+/// Scale the Q0.32 fraction `fraction` to the range between `0` and `max_value`.
+///
+/// This computes `fraction * (max_value + 1) / 2**32` without overflow and without division.
+///
+/// *Note*: If `max_value + 1` is not a power of two,
+///        then the result distribution is slightly uneven.
+///        The relative error of each result probability is below `max_value / 2**32`.
+///        Use `Shr3Ops::get_max()` for an exactly even distribution.
+#[inline]
+pub const fn scale_q32(fraction: u32, max_value: u32) -> u32 {
+    ((fraction as u64 * (max_value as u64 + 1)) >> 32) as u32
+}
+
+/// Scale the Q0.16 fraction `fraction` to the range between `0` and `max_value`.
+///
+/// This computes `fraction * (max_value + 1) / 2**16` without overflow and without division.
+///
+/// *Note*: If `max_value + 1` is not a power of two,
+///        then the result distribution is slightly uneven.
+///        The relative error of each result probability is below `max_value / 2**16`.
+///        Use `Shr3Ops::get_max()` for an exactly even distribution.
+#[inline]
+pub const fn scale_q16(fraction: u16, max_value: u16) -> u16 {
+    ((fraction as u32 * (max_value as u32 + 1)) >> 16) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scale_q32() {
+        assert_eq!(scale_q32(0, 0), 0);
+        assert_eq!(scale_q32(0xFFFF_FFFF, 0), 0);
+        assert_eq!(scale_q32(0, 100), 0);
+        assert_eq!(scale_q32(0xFFFF_FFFF, 100), 100);
+        assert_eq!(scale_q32(0x8000_0000, 100), 50);
+        assert_eq!(scale_q32(0x8000_0000, 99), 50);
+        assert_eq!(scale_q32(0x1234_5678, u32::MAX), 0x1234_5678);
+        assert_eq!(scale_q32(0xFFFF_FFFF, u32::MAX), u32::MAX);
+    }
+
+    #[test]
+    fn test_scale_q16() {
+        assert_eq!(scale_q16(0, 0), 0);
+        assert_eq!(scale_q16(0xFFFF, 0), 0);
+        assert_eq!(scale_q16(0, 100), 0);
+        assert_eq!(scale_q16(0xFFFF, 100), 100);
+        assert_eq!(scale_q16(0x8000, 100), 50);
+        assert_eq!(scale_q16(0x8000, 99), 50);
+        assert_eq!(scale_q16(0x1234, u16::MAX), 0x1234);
+        assert_eq!(scale_q16(0xFFFF, u16::MAX), u16::MAX);
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
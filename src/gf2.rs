@@ -0,0 +1,557 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Linear algebra over GF(2) for the SHR3 state transition.
+//!
+//! The SHR3 round is a linear function over GF(2).
+//! It can be represented as 32x32 bit matrix.
+//! Powers of this matrix advance the state by many rounds at once.
+//!
+//! Vectors are represented as `u32`, where bit `i` is element `i`.
+//! Polynomials are represented as `u64`, where bit `i` is the coefficient of `x**i`.
+//!
+//! ```
+//!     use shr3::gf2::Matrix;
+//!
+//!     let t = Matrix::shr3();
+//!     assert_eq!(t.apply(42), shr3::shr3(42));
+//!     assert_eq!(t.pow(0xFFFF_FFFF), Matrix::identity());
+//!     assert_eq!(t.charpoly() >> 32, 1);
+//! ```
+
+use crate::arch::generic::shr3;
+
+/// Number of rounds after which the SHR3 state repeats.
+pub(crate) const PERIOD: u64 = 0xFFFF_FFFF;
+
+/// 32x32 bit matrix over GF(2).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Matrix {
+    /// Column `i` is the image of the unit vector `1 << i`.
+    cols: [u32; 32],
+}
+
+impl Matrix {
+    /// The zero matrix.
+    pub const fn zero() -> Self {
+        Self { cols: [0; 32] }
+    }
+
+    /// The identity matrix.
+    pub const fn identity() -> Self {
+        let mut cols = [0; 32];
+        let mut i = 0;
+        while i < 32 {
+            cols[i] = 1 << i;
+            i += 1;
+        }
+        Self { cols }
+    }
+
+    /// Create a matrix from its columns.
+    ///
+    /// Column `i` is the image of the unit vector `1 << i`.
+    pub const fn from_cols(cols: [u32; 32]) -> Self {
+        Self { cols }
+    }
+
+    /// Get the columns of the matrix.
+    pub const fn cols(&self) -> [u32; 32] {
+        self.cols
+    }
+
+    /// The transition matrix of one SHR3 round.
+    pub const fn shr3() -> Self {
+        let mut cols = [0; 32];
+        let mut i = 0;
+        while i < 32 {
+            cols[i] = shr3(1 << i);
+            i += 1;
+        }
+        Self { cols }
+    }
+
+    /// The transition matrix of one round of a 32 bit xorshift with the shift triple `(a, b, c)`:
+    ///
+    /// ```text
+    ///     state ^= state << a;
+    ///     state ^= state >> b;
+    ///     state ^= state << c;
+    /// ```
+    ///
+    /// SHR3 is the triple `(13, 17, 5)`.
+    /// All shifts must be lower than 32.
+    pub const fn xorshift(a: u32, b: u32, c: u32) -> Self {
+        assert!(a < 32 && b < 32 && c < 32);
+        let mut cols = [0; 32];
+        let mut i = 0;
+        while i < 32 {
+            let mut state: u32 = 1 << i;
+            state ^= state << a;
+            state ^= state >> b;
+            state ^= state << c;
+            cols[i] = state;
+            i += 1;
+        }
+        Self { cols }
+    }
+
+    /// Get the element in `row` and `col`.
+    pub const fn get(&self, row: usize, col: usize) -> bool {
+        self.cols[col] & (1 << row) != 0
+    }
+
+    /// Multiply the matrix with the vector `v`.
+    pub const fn apply(&self, v: u32) -> u32 {
+        let mut ret = 0;
+        let mut i = 0;
+        while i < 32 {
+            if v & (1 << i) != 0 {
+                ret ^= self.cols[i];
+            }
+            i += 1;
+        }
+        ret
+    }
+
+    /// Add the matrix `other`.
+    pub const fn add(&self, other: &Self) -> Self {
+        let mut cols = [0; 32];
+        let mut i = 0;
+        while i < 32 {
+            cols[i] = self.cols[i] ^ other.cols[i];
+            i += 1;
+        }
+        Self { cols }
+    }
+
+    /// Multiply the matrix with `other`.
+    /// Applying the result is the same as applying `other` first and `self` second.
+    pub const fn mul(&self, other: &Self) -> Self {
+        let mut cols = [0; 32];
+        let mut i = 0;
+        while i < 32 {
+            cols[i] = self.apply(other.cols[i]);
+            i += 1;
+        }
+        Self { cols }
+    }
+
+    /// Raise the matrix to the power of `n`.
+    ///
+    /// This needs `O(log n)` matrix multiplications.
+    pub const fn pow(&self, mut n: u64) -> Self {
+        let mut ret = Self::identity();
+        let mut m = *self;
+        while n > 0 {
+            if n & 1 != 0 {
+                ret = ret.mul(&m);
+            }
+            n >>= 1;
+            if n > 0 {
+                m = m.mul(&m);
+            }
+        }
+        ret
+    }
+
+    /// Evaluate the polynomial `poly` with the matrix as variable.
+    pub const fn eval_poly(&self, poly: u64) -> Self {
+        let mut ret = Self::zero();
+        let mut m = Self::identity();
+        let mut i = 0;
+        while i < 64 {
+            if poly & (1 << i) != 0 {
+                ret = ret.add(&m);
+            }
+            m = m.mul(self);
+            i += 1;
+        }
+        ret
+    }
+
+    /// Compute the characteristic polynomial of the matrix.
+    ///
+    /// The result is of degree 32 (bit 32 is set).
+    /// The matrix is reduced to upper Hessenberg form first
+    /// and the polynomial is computed from the Hessenberg form.
+    pub const fn charpoly(&self) -> u64 {
+        // h[row] bit col = element (row, col).
+        let mut h = [0_u32; 32];
+        let mut i = 0;
+        while i < 32 {
+            let mut j = 0;
+            while j < 32 {
+                if self.get(i, j) {
+                    h[i] |= 1 << j;
+                }
+                j += 1;
+            }
+            i += 1;
+        }
+
+        // Similarity transformation to upper Hessenberg form.
+        let mut m = 1;
+        while m < 31 {
+            let col = 1 << (m - 1);
+            let mut pivot = m;
+            while pivot < 32 && h[pivot] & col == 0 {
+                pivot += 1;
+            }
+            if pivot < 32 {
+                if pivot != m {
+                    // Swap rows and columns pivot and m.
+                    let tmp = h[pivot];
+                    h[pivot] = h[m];
+                    h[m] = tmp;
+                    let mut r = 0;
+                    while r < 32 {
+                        let a = (h[r] >> pivot) & 1;
+                        let b = (h[r] >> m) & 1;
+                        if a != b {
+                            h[r] ^= (1 << pivot) | (1 << m);
+                        }
+                        r += 1;
+                    }
+                }
+                let mut k = m + 1;
+                while k < 32 {
+                    if h[k] & col != 0 {
+                        // Row k -= row m; column m += column k.
+                        h[k] ^= h[m];
+                        let mut r = 0;
+                        while r < 32 {
+                            if h[r] & (1 << k) != 0 {
+                                h[r] ^= 1 << m;
+                            }
+                            r += 1;
+                        }
+                    }
+                    k += 1;
+                }
+            }
+            m += 1;
+        }
+
+        // Characteristic polynomials of the leading principal submatrices.
+        let mut p = [0_u64; 33];
+        p[0] = 1;
+        let mut m = 0;
+        while m < 32 {
+            let mut next = p[m] << 1;
+            if h[m] & (1 << m) != 0 {
+                next ^= p[m];
+            }
+            let mut sub = true;
+            let mut i = m;
+            while i > 0 {
+                // sub: product of the subdiagonal elements (i, i - 1) .. (m, m - 1).
+                sub = sub && h[i] & (1 << (i - 1)) != 0;
+                if !sub {
+                    break;
+                }
+                if h[i - 1] & (1 << m) != 0 {
+                    next ^= p[i - 1];
+                }
+                i -= 1;
+            }
+            p[m + 1] = next;
+            m += 1;
+        }
+        p[32]
+    }
+}
+
+/// Advance the SHR3 `state` by `rounds` rounds.
+///
+/// This needs `O(log rounds)` matrix squarings instead of `rounds` SHR3 rounds.
+pub(crate) const fn jump(mut state: u32, rounds: u64) -> u32 {
+    let mut rounds = rounds % PERIOD;
+    let mut m = Matrix::shr3();
+    while rounds > 0 {
+        if rounds & 1 != 0 {
+            state = m.apply(state);
+        }
+        rounds >>= 1;
+        if rounds > 0 {
+            m = m.mul(&m);
+        }
+    }
+    state
+}
+
+/// Prime factors of `PERIOD`: `2**32 - 1 = 3 * 5 * 17 * 257 * 65537`
+const PERIOD_FACTORS: [u64; 5] = [3, 5, 17, 257, 65537];
+
+/// Multiply `a` and `b` in the field GF(2**32) with the reduction polynomial `poly`.
+fn field_mul(a: u32, b: u32, poly: u64) -> u32 {
+    let mut r = 0_u64;
+    for i in 0..32 {
+        if b & (1 << i) != 0 {
+            r ^= (a as u64) << i;
+        }
+    }
+    for i in (32..63).rev() {
+        if r & (1 << i) != 0 {
+            r ^= poly << (i - 32);
+        }
+    }
+    r as u32
+}
+
+/// Raise `a` to the power of `n` in the field GF(2**32) with the reduction polynomial `poly`.
+fn field_pow(a: u32, mut n: u64, poly: u64) -> u32 {
+    let mut ret = 1;
+    let mut a = a;
+    while n > 0 {
+        if n & 1 != 0 {
+            ret = field_mul(ret, a, poly);
+        }
+        n >>= 1;
+        a = field_mul(a, a, poly);
+    }
+    ret
+}
+
+/// Find `e` in `0..q` with `gamma**e == h`. `gamma` has the prime order `q`.
+fn field_log_prime(h: u32, gamma: u32, q: u64, poly: u64) -> Option<u64> {
+    // Baby-step giant-step with a fixed table size.
+    const M: usize = 257;
+    let mut baby = [(0_u32, 0_u16); M];
+    let mut x = 1;
+    for (j, b) in baby.iter_mut().enumerate() {
+        *b = (x, j as u16);
+        x = field_mul(x, gamma, poly);
+    }
+    baby.sort_unstable();
+    // gamma**-M
+    let giant = field_pow(gamma, q - M as u64 % q, poly);
+    let mut y = h;
+    for i in 0..=(q / M as u64) {
+        if let Ok(pos) = baby.binary_search_by_key(&y, |b| b.0) {
+            return Some((i * M as u64 + baby[pos].1 as u64) % q);
+        }
+        y = field_mul(y, giant, poly);
+    }
+    None
+}
+
+/// Compute the number of SHR3 rounds from the state `from` to the state `to`.
+///
+/// Returns `n` in `0..2**32 - 1` with `to == shr3(shr3(...shr3(from)))` (`n` rounds).
+/// All non-zero states are on the same cycle.
+/// Therefore, `None` is only returned, if exactly one of the states is 0.
+///
+/// The SHR3 state space is identified with the field GF(2**32).
+/// `to` is expressed as polynomial in the transition matrix applied to `from`.
+/// The discrete logarithm of that polynomial is computed with the Pohlig-Hellman algorithm
+/// over the small prime factors of `2**32 - 1`.
+/// No loop over the rounds is done.
+///
+/// ```
+///     let mut state = 42;
+///     for _ in 0..1000 {
+///         state = shr3::shr3(state);
+///     }
+///     assert_eq!(shr3::gf2::distance(42, state), Some(1000));
+/// ```
+pub fn distance(from: u32, to: u32) -> Option<u32> {
+    match (from, to) {
+        (0, 0) => return Some(0),
+        (0, _) | (_, 0) => return None,
+        _ => (),
+    }
+    let t = Matrix::shr3();
+    let poly = t.charpoly();
+
+    // Solve to == g(T) * from for the polynomial g of degree < 32.
+    // pivots[b] = (vector with highest bit b, polynomial that creates it)
+    let mut pivots = [(0_u32, 0_u32); 32];
+    let mut v = from;
+    for i in 0..32 {
+        let mut vec = v;
+        let mut mask = 1 << i;
+        while vec != 0 {
+            let b = 31 - vec.leading_zeros() as usize;
+            if pivots[b].0 == 0 {
+                pivots[b] = (vec, mask);
+                break;
+            }
+            vec ^= pivots[b].0;
+            mask ^= pivots[b].1;
+        }
+        v = t.apply(v);
+    }
+    let mut rest = to;
+    let mut g = 0;
+    while rest != 0 {
+        let b = 31 - rest.leading_zeros() as usize;
+        if pivots[b].0 == 0 {
+            return None;
+        }
+        rest ^= pivots[b].0;
+        g ^= pivots[b].1;
+    }
+
+    // Discrete logarithm of g to the base x.
+    let mut n = 0;
+    for q in PERIOD_FACTORS {
+        let m = PERIOD / q;
+        let h = field_pow(g, m, poly);
+        let gamma = field_pow(2, m, poly);
+        let e = field_log_prime(h, gamma, q, poly)?;
+        // Chinese remainder theorem.
+        // The inverse of m modulo the prime q is m**(q - 2).
+        let mut m_inv = 1;
+        let mut a = m % q;
+        let mut k = q - 2;
+        while k > 0 {
+            if k & 1 != 0 {
+                m_inv = m_inv * a % q;
+            }
+            a = a * a % q;
+            k >>= 1;
+        }
+        n = (n + m * (e * m_inv % q)) % PERIOD;
+    }
+    Some(n as u32)
+}
+
+/// Check whether the 32 bit xorshift with the shift triple `(a, b, c)` has the full period.
+///
+/// A triple has the full period, if every non-zero state repeats after exactly `2**32 - 1` rounds.
+/// This is checked with the order of the transition matrix `T = Matrix::xorshift(a, b, c)`:
+/// `T**(2**32 - 1)` must be the identity and `T**((2**32 - 1) / q)` must not be the identity
+/// for all prime factors `q` of `2**32 - 1`.
+/// No loop over the rounds is done.
+///
+/// Shifts of 0 or 32 and above never give the full period.
+///
+/// ```
+///     use shr3::gf2::is_full_period;
+///
+///     assert!(is_full_period(13, 17, 5));
+///     assert!(is_full_period(1, 3, 10));
+///     assert!(!is_full_period(13, 17, 6));
+/// ```
+#[cfg(feature="validate")]
+pub fn is_full_period(a: u32, b: u32, c: u32) -> bool {
+    if !(1..32).contains(&a) || !(1..32).contains(&b) || !(1..32).contains(&c) {
+        return false;
+    }
+    let t = Matrix::xorshift(a, b, c);
+    let id = Matrix::identity();
+    t.pow(PERIOD) == id &&
+    PERIOD_FACTORS.iter().all(|q| t.pow(PERIOD / q) != id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matrix() {
+        let t = Matrix::shr3();
+        let id = Matrix::identity();
+        assert_eq!(Matrix::xorshift(13, 17, 5), t);
+        assert_eq!(Matrix::from_cols(t.cols()), t);
+        assert_eq!(t.mul(&id), t);
+        assert_eq!(id.mul(&t), t);
+        assert_eq!(t.add(&t), Matrix::zero());
+        assert_eq!(t.add(&Matrix::zero()), t);
+        assert_eq!(t.pow(0), id);
+        assert_eq!(t.pow(1), t);
+        assert_eq!(t.pow(PERIOD), id);
+        assert_eq!(t.pow(5).mul(&t.pow(7)), t.pow(12));
+        assert!(id.get(3, 3));
+        assert!(!id.get(3, 4));
+        for v in [1, 42, 0x8000_0000, 0xFFFF_FFFF, 0x1234_5678] {
+            assert_eq!(t.apply(v), shr3(v));
+            assert_eq!(id.apply(v), v);
+            assert_eq!(t.pow(1000).apply(v), jump(v, 1000));
+            assert_eq!(t.mul(&t).apply(v), shr3(shr3(v)));
+        }
+    }
+
+    #[test]
+    fn test_jump() {
+        let mut state = 42;
+        for i in 0..1000 {
+            assert_eq!(jump(42, i), state);
+            state = shr3(state);
+        }
+        assert_eq!(jump(42, PERIOD), 42);
+        assert_eq!(jump(42, PERIOD + 5), jump(42, 5));
+        assert_eq!(jump(jump(42, 123_456_789), PERIOD - 123_456_789), 42);
+        assert_eq!(jump(0, 1000), 0);
+    }
+
+    #[test]
+    fn test_charpoly() {
+        // (x + 1)**32 == x**32 + 1
+        assert_eq!(Matrix::identity().charpoly(), (1 << 32) | 1);
+        assert_eq!(Matrix::zero().charpoly(), 1 << 32);
+
+        // Cayley-Hamilton theorem.
+        for m in [
+            Matrix::shr3(),
+            Matrix::xorshift(1, 3, 10),
+            Matrix::xorshift(5, 7, 22),
+            Matrix::xorshift(3, 3, 3),
+            Matrix::shr3().pow(12345),
+        ] {
+            let poly = m.charpoly();
+            assert_eq!(poly >> 32, 1);
+            assert_eq!(m.eval_poly(poly), Matrix::zero());
+        }
+
+        // Any similar matrix has the same characteristic polynomial.
+        let t = Matrix::shr3();
+        let x = Matrix::xorshift(1, 3, 10);
+        let x_inv = x.pow(PERIOD - 1);
+        assert_eq!(x_inv.mul(&x), Matrix::identity());
+        assert_eq!(x.mul(&t).mul(&x_inv).charpoly(), t.charpoly());
+    }
+
+    #[test]
+    fn test_distance() {
+        assert_eq!(PERIOD_FACTORS.iter().product::<u64>(), PERIOD);
+        for n in [0, 1, 2, 1000, 123_456_789, PERIOD - 1] {
+            assert_eq!(distance(42, jump(42, n)), Some(n as u32));
+            assert_eq!(distance(0xDEAD_BEEF, jump(0xDEAD_BEEF, n)), Some(n as u32));
+        }
+        assert_eq!(distance(42, 42), Some(0));
+        assert_eq!(distance(0, 0), Some(0));
+        assert_eq!(distance(0, 1), None);
+        assert_eq!(distance(1, 0), None);
+        let d = distance(1, 42).unwrap();
+        assert_eq!(jump(1, d as u64), 42);
+        assert_eq!(distance(42, 1), Some((PERIOD - d as u64) as u32));
+    }
+
+    #[cfg(feature="validate")]
+    #[test]
+    fn test_is_full_period() {
+        // All full period triples with a = 1 from Marsaglia's list.
+        let full: [(u32, u32); 9] = [
+            (3, 10), (5, 16), (5, 19), (9, 29), (11, 6), (11, 16), (19, 3), (21, 20), (27, 27),
+        ];
+        for b in 1..32 {
+            for c in 1..32 {
+                assert_eq!(is_full_period(1, b, c), full.contains(&(b, c)));
+            }
+        }
+        assert!(is_full_period(13, 17, 5));
+        assert!(is_full_period(5, 17, 13));
+        assert!(!is_full_period(0, 17, 5));
+        assert!(!is_full_period(13, 32, 5));
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
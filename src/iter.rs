@@ -0,0 +1,322 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Iterator adapters over the SHR3 random stream.
+
+use crate::{BaseOps, Shr3Ops};
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::num::Wrapping;
+use core::ops::{Add, Sub};
+
+/// Endless iterator over random values of type `T`.
+///
+/// Each item is extracted like `Shr3Ops::get()`.
+/// The iterator borrows the generator mutably and advances its state.
+///
+/// Create it with `Shr3::iter()`.
+pub struct Shr3Iter<'a, R, T> {
+    rng: &'a mut R,
+    _phantom: PhantomData<T>,
+}
+
+impl<'a, R, T> Shr3Iter<'a, R, T> {
+    #[inline]
+    pub(crate) fn new(rng: &'a mut R) -> Self {
+        Self {
+            rng,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<R, T> Iterator for Shr3Iter<'_, R, T>
+    where R: Shr3Ops<T>,
+          T: BaseOps + PartialOrd,
+          T::U: BaseOps,
+          Wrapping<T::U>: Sub<Output=Wrapping<T::U>> + Add<Output=Wrapping<T::U>> + PartialOrd,
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        Some(self.rng.get())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl<R, T> FusedIterator for Shr3Iter<'_, R, T>
+    where R: Shr3Ops<T>,
+          T: BaseOps + PartialOrd,
+          T::U: BaseOps,
+          Wrapping<T::U>: Sub<Output=Wrapping<T::U>> + Add<Output=Wrapping<T::U>> + PartialOrd,
+{
+}
+
+/// Endless iterator over single random bits.
+///
+/// Each item is one bit extracted like `Shr3Ops::get_bits(1)`.
+/// Therefore, each item costs exactly one SHR3 round.
+///
+/// Create it with `Shr3::bits()`.
+pub struct Shr3Bits<'a, R> {
+    rng: &'a mut R,
+}
+
+impl<'a, R> Shr3Bits<'a, R> {
+    #[inline]
+    pub(crate) fn new(rng: &'a mut R) -> Self {
+        Self {
+            rng,
+        }
+    }
+}
+
+impl<R: Shr3Ops<u8>> Iterator for Shr3Bits<'_, R> {
+    type Item = bool;
+
+    #[inline]
+    fn next(&mut self) -> Option<bool> {
+        let bit: u8 = self.rng.get_bits(1);
+        Some(bit != 0)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl<R: Shr3Ops<u8>> FusedIterator for Shr3Bits<'_, R> {
+}
+
+/// Endless iterator over random byte arrays of fixed size `N`.
+///
+/// Each array is filled like `Shr3Ops::fill_slice()`.
+///
+/// Create it with `Shr3::chunks()`.
+pub struct Shr3Chunks<'a, R, const N: usize> {
+    rng: &'a mut R,
+}
+
+impl<'a, R, const N: usize> Shr3Chunks<'a, R, N> {
+    #[inline]
+    pub(crate) fn new(rng: &'a mut R) -> Self {
+        Self {
+            rng,
+        }
+    }
+}
+
+impl<R: Shr3Ops<u8>, const N: usize> Iterator for Shr3Chunks<'_, R, N> {
+    type Item = [u8; N];
+
+    #[inline]
+    fn next(&mut self) -> Option<[u8; N]> {
+        let mut chunk = [0; N];
+        self.rng.fill_slice(&mut chunk);
+        Some(chunk)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+impl<R: Shr3Ops<u8>, const N: usize> FusedIterator for Shr3Chunks<'_, R, N> {
+}
+
+/// Number of rounds of the keyed index permutation.
+const PERM_ROUNDS: usize = 3;
+
+/// Iterator over all indices in the range `0..n` in random order.
+///
+/// Each index is returned exactly once.
+/// The permutation is not stored in memory.
+/// Instead, each index is computed from a keyed bijective function on the
+/// smallest power-of-two range that contains `0..n`.
+/// Results outside of `0..n` are mapped again (cycle walking) until they are inside of the range.
+/// On average less than two evaluations of the function are needed per index.
+///
+/// The keys are extracted from the generator once on construction.
+/// They are extracted as `u64` independent of the width of `usize`.
+/// Therefore, the permutation and the following random stream are identical on all targets.
+/// The iterator does not borrow the generator.
+///
+/// *Note*: This is not a cryptographically secure permutation.
+///
+/// Create it with `Shr3::permutation()`.
+#[derive(Clone, Debug)]
+pub struct Shr3Permutation {
+    n: usize,
+    pos: usize,
+    mask: usize,
+    shift: u32,
+    keys: [(usize, usize); PERM_ROUNDS],
+}
+
+impl Shr3Permutation {
+    pub(crate) fn new<R: Shr3Ops<u64>>(rng: &mut R, n: usize) -> Self {
+        let bits = n.saturating_sub(1).fls() as u32;
+        let mask = if bits >= usize::BITS { usize::MAX } else { (1 << bits) - 1 };
+        let mut keys = [(0, 0); PERM_ROUNDS];
+        for key in &mut keys {
+            // Only the low bits of the keys up to the mask width affect the result.
+            let add = Shr3Ops::<u64>::get(rng) as usize;
+            let mul = Shr3Ops::<u64>::get(rng) as usize;
+            // An odd multiplier is invertible modulo a power of two.
+            *key = (add, mul | 1);
+        }
+        Self {
+            n,
+            pos: 0,
+            mask,
+            shift: bits.div_ceil(2),
+            keys,
+        }
+    }
+
+    /// Bijective function on the range `0..=mask`.
+    fn permute(&self, mut x: usize) -> usize {
+        for (add, mul) in &self.keys {
+            x = x.wrapping_add(*add) & self.mask;
+            x = x.wrapping_mul(*mul) & self.mask;
+            x ^= x >> self.shift;
+        }
+        x
+    }
+}
+
+impl Iterator for Shr3Permutation {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.pos >= self.n {
+            return None;
+        }
+        let mut x = self.permute(self.pos);
+        while x >= self.n {
+            x = self.permute(x);
+        }
+        self.pos += 1;
+        Some(x)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.n - self.pos;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for Shr3Permutation {
+}
+
+impl FusedIterator for Shr3Permutation {
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PERM_ROUNDS;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_iter() {
+        let mut a = Shr3::new_state(42);
+        let mut it = a.iter::<u16>().take(6);
+        for exp in [0x20D3, 0x2C5C, 0x2A17, 0xD3C5, 0xAF08, 0x9E5B] {
+            assert_eq!(it.next(), Some(exp));
+        }
+        assert_eq!(it.next(), None);
+
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        let mut buf = [0_i32; 8];
+        b.fill_slice(&mut buf);
+        assert!(a.iter::<i32>().zip(buf).take(8).all(|(x, y)| x == y));
+    }
+
+    #[test]
+    fn test_bits() {
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        let exp: u16 = b.get();
+        let mut it = a.bits();
+        for i in (0..16).rev() {
+            assert_eq!(it.next(), Some(exp & (1 << i) != 0));
+        }
+        let exp: u16 = b.get();
+        let mut x = 0_u16;
+        for bit in a.bits().take(16) {
+            x = (x << 1) | bit as u16;
+        }
+        assert_eq!(x, exp);
+    }
+
+    #[test]
+    fn test_chunks() {
+        let mut a = Shr3::new_state(42);
+        let mut it = a.chunks::<4>();
+        assert_eq!(it.next(), Some([0x20, 0xD3, 0x2C, 0x5C]));
+        assert_eq!(it.next(), Some([0x2A, 0x17, 0xD3, 0xC5]));
+
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        let mut buf = [0_u8; 9];
+        b.fill_bytes(&mut buf);
+        let mut it = a.chunks::<3>();
+        for exp in buf.chunks(3) {
+            assert_eq!(&it.next().unwrap(), exp);
+        }
+
+        let mut a = Shr3::new_state(42);
+        assert_eq!(a.chunks::<0>().next(), Some([]));
+    }
+
+    #[test]
+    fn test_permutation() {
+        let mut a = Shr3::new_state(42);
+        for n in [0, 1, 2, 3, 7, 8, 9, 100, 1000, 1025] {
+            let mut seen = [false; 1025];
+            let it = a.permutation(n);
+            assert_eq!(it.len(), n);
+            for i in it {
+                assert!(i < n);
+                assert!(!seen[i]);
+                seen[i] = true;
+            }
+            assert!(seen[..n].iter().all(|s| *s));
+        }
+
+        // Different generator states give different permutations.
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(43);
+        assert!(a.permutation(1000).zip(b.permutation(1000)).any(|(x, y)| x != y));
+        let mut it = a.permutation(1000);
+        assert!(it.by_ref().take(10).enumerate().filter(|(i, x)| i != x).count() > 0);
+        assert_eq!(it.len(), 990);
+
+        // The permutation is identical on all targets.
+        let mut a = Shr3::new_state(42);
+        let mut perm = [0; 10];
+        for (p, i) in perm.iter_mut().zip(a.permutation(10)) {
+            *p = i;
+        }
+        assert_eq!(perm, [3, 0, 5, 7, 1, 9, 4, 8, 6, 2]);
+        // 2 * PERM_ROUNDS keys of 64 bits each.
+        assert_eq!(a.state(), Shr3::new_state_warmed(42, 64 * 2 * PERM_ROUNDS as u32).state());
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
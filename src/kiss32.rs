@@ -0,0 +1,128 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! KISS32 combined generator.
+
+use crate::shr3;
+use crate::wordbits::{WordBits, impl_shr3_ops_words};
+
+/// Default initial state `[z, w, jsr, jcong]` from Marsaglia's KISS post.
+const DEFAULT_STATE: [u32; 4] = [362436069, 521288629, 123456789, 380116160];
+
+/// KISS32 combined generator ("Keep It Simple Stupid").
+///
+/// KISS algorithm from sci.math post by George Marsaglia (Feb 25 2003, 10:25 am).
+/// It combines three simple generators:
+///
+/// * MWC: Two 16 bit multiply-with-carry generators `z` and `w`.
+/// * CONG: The 32 bit linear congruential generator `jcong = 69069 * jcong + 1234567`.
+/// * SHR3: The `shr3()` 3-shift register generator `jsr`.
+///
+/// The output word is `(MWC ^ CONG) + SHR3`.
+/// The generator has a period of approximately `2**123`.
+///
+/// The shuffle function produces one 32 bit output word per step.
+/// `Shr3Ops` extracts the bits of each output word from MSB to LSB.
+/// Therefore, extracting a `u32` at a word boundary returns the unchanged output word.
+///
+/// This generator is *not* cryptographically secure!
+pub struct Kiss32 {
+    z: u32,
+    w: u32,
+    jsr: u32,
+    jcong: u32,
+    bits: WordBits,
+}
+
+impl Kiss32 {
+    /// Create a new instance with the default initial state from Marsaglia's post.
+    #[inline]
+    pub const fn new() -> Kiss32 {
+        Self::new_state(DEFAULT_STATE)
+    }
+
+    /// Create a new instance with user specified initial state `[z, w, jsr, jcong]`.
+    ///
+    /// Special state 0: The states `z`, `w` and `jsr` must not be 0.
+    ///                  If 0 is passed for one of them, then its default initial state is picked instead.
+    ///                  `jcong` can have any value.
+    #[inline]
+    pub const fn new_state(state: [u32; 4]) -> Kiss32 {
+        let [z, w, jsr, jcong] = state;
+        Kiss32 {
+            z: if z == 0 { DEFAULT_STATE[0] } else { z },
+            w: if w == 0 { DEFAULT_STATE[1] } else { w },
+            jsr: if jsr == 0 { DEFAULT_STATE[2] } else { jsr },
+            jcong,
+            bits: WordBits::new(),
+        }
+    }
+
+    /// Get the current state `[z, w, jsr, jcong]`.
+    ///
+    /// Bits of the current output word that have not been extracted yet are not part of the state.
+    #[inline]
+    pub const fn state(&self) -> [u32; 4] {
+        [self.z, self.w, self.jsr, self.jcong]
+    }
+
+    /// Advance the generator by one step and get the 32 bit output word.
+    ///
+    /// This bypasses the bit extraction of `Shr3Ops`.
+    #[inline]
+    pub fn next_u32(&mut self) -> u32 {
+        self.z = 36969 * (self.z & 0xFFFF) + (self.z >> 16);
+        self.w = 18000 * (self.w & 0xFFFF) + (self.w >> 16);
+        let mwc = (self.z << 16).wrapping_add(self.w);
+        self.jcong = self.jcong.wrapping_mul(69069).wrapping_add(1234567);
+        self.jsr = shr3(self.jsr);
+        (mwc ^ self.jcong).wrapping_add(self.jsr)
+    }
+}
+
+impl Default for Kiss32 {
+    /// Create a new instance with the default initial state.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl_shr3_ops_words!(Kiss32);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shr3Ops;
+
+    #[test]
+    fn test_kiss32() {
+        let mut a = Kiss32::new();
+        assert_eq!(a.next_u32(), 0x9BDD_F92E);
+        assert_eq!(a.next_u32(), 0xD5A4_1E38);
+        assert_eq!(a.next_u32(), 0xB2F6_FF02);
+
+        let mut a = Kiss32::new();
+        let x: u32 = a.get();
+        assert_eq!(x, 0x9BDD_F92E);
+        let x: u8 = a.get();
+        assert_eq!(x, 0xD5);
+
+        let a = Kiss32::new_state([0, 0, 0, 0]);
+        assert_eq!(a.state(), [DEFAULT_STATE[0], DEFAULT_STATE[1], DEFAULT_STATE[2], 0]);
+
+        let mut a = Kiss32::default();
+        for _ in 0..1000 {
+            let x: u16 = a.get_max(1000);
+            assert!(x <= 1000);
+        }
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,103 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Leapfrog substreams of the SHR3 bit stream.
+
+use crate::gf2::{Matrix, jump};
+use crate::{BaseOps, Shr3Ops};
+use core::num::Wrapping;
+use core::ops::{Add, BitOrAssign, ShlAssign, Sub};
+
+/// Generator that extracts every `k`-th bit of a base SHR3 bit stream.
+///
+/// The leapfrog substreams `0..k` of the same base stream are disjoint
+/// and together they contain every bit of the base stream exactly once.
+/// That allows `k` consumers to share one logical random stream
+/// without any overlap.
+///
+/// Each extracted bit costs one application of the 32x32 bit GF(2) matrix
+/// of `k` SHR3 rounds instead of one SHR3 round.
+/// The matrix is computed once on construction with `O(log k)` matrix squarings.
+///
+/// Create it with `Shr3::leapfrog()`.
+#[derive(Clone, Debug)]
+pub struct Shr3Leapfrog {
+    state: u32,
+    step: Matrix,
+}
+
+impl Shr3Leapfrog {
+    pub(crate) const fn new(state: u32, k: u64, i: u64) -> Self {
+        assert!(k > 0);
+        assert!(i < k);
+        Self {
+            state: jump(state, i + 1),
+            step: Matrix::shr3().pow(k),
+        }
+    }
+}
+
+impl<T> Shr3Ops<T> for Shr3Leapfrog
+    where T: BaseOps + PartialOrd,
+          T::U: BaseOps,
+          Wrapping<T::U>: Sub<Output=Wrapping<T::U>> + Add<Output=Wrapping<T::U>> + PartialOrd + ShlAssign<usize> + BitOrAssign,
+{
+    #[inline]
+    fn get_bits(&mut self, bitcount: u8) -> T {
+        T::from_unsigned(Shr3Ops::<T>::get_bits_unsigned(self, bitcount))
+    }
+
+    fn get_bits_unsigned(&mut self, bitcount: u8) -> Wrapping<T::U> {
+        debug_assert!(bitcount <= T::NUMBITS);
+        let mut ret = Wrapping(T::U::from_u8(0));
+        for _ in 0..bitcount {
+            ret <<= 1;
+            ret |= Wrapping(T::U::from_u8(self.state as u8 & 1));
+            self.state = self.step.apply(self.state);
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_leapfrog() {
+        let mut base = Shr3::new_state(42);
+        let bits: [u8; 60] = core::array::from_fn(|_| base.get_bits(1));
+
+        for k in [1, 2, 3, 5] {
+            let mut sub: [_; 5] = core::array::from_fn(|i| Shr3::new_state(42).leapfrog(k, i as u64 % k));
+            for (j, bit) in bits.iter().enumerate() {
+                let x: u8 = sub[j % k as usize].get_bits(1);
+                assert_eq!(x, *bit);
+            }
+        }
+
+        let mut a = Shr3::new_state(42).leapfrog(1, 0);
+        let mut b = Shr3::new_state(42);
+        for _ in 0..100 {
+            let x: u32 = a.get();
+            let y: u32 = b.get();
+            assert_eq!(x, y);
+        }
+
+        let mut a = Shr3::new_state(42).leapfrog(3, 1);
+        let x: u16 = a.get();
+        let mut exp = 0;
+        for i in 0..16 {
+            exp = (exp << 1) | bits[1 + i * 3] as u16;
+        }
+        assert_eq!(x, exp);
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
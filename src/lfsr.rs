@@ -0,0 +1,185 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Galois linear feedback shift register.
+
+use crate::{BaseOps, Shr3Ops};
+use core::num::Wrapping;
+use core::ops::{Add, BitOrAssign, ShlAssign, Sub};
+
+/// Galois LFSR with the user specified feedback polynomial `POLY`.
+///
+/// `POLY` is the toggle mask of the right shifting Galois LFSR.
+/// The width of the register is the position of the highest set bit of `POLY` plus one.
+/// For example the polynomial `x**16 + x**14 + x**13 + x**11 + 1` is the mask `0xB400`
+/// and gives a 16 bit register.
+///
+/// Each step shifts the register one bit to the right.
+/// The bit shifted out at the LSB is the output bit.
+/// If the output bit is 1, then the register is XORed with `POLY`.
+/// One step is done per extracted random bit.
+///
+/// If `POLY` is a maximal-length polynomial, then the generator will loop back
+/// to the beginning after `2**WIDTH - 1` steps.
+/// See `GaloisLfsr8`, `GaloisLfsr16`, `GaloisLfsr24` and `GaloisLfsr32` for maximal-length presets.
+///
+/// This generator is *not* cryptographically secure!
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GaloisLfsr<const POLY: u32> {
+    state: u32,
+}
+
+/// 8 bit Galois LFSR with the maximal-length polynomial `x**8 + x**6 + x**5 + x**4 + 1`.
+pub type GaloisLfsr8 = GaloisLfsr<0xB8>;
+
+/// 16 bit Galois LFSR with the maximal-length polynomial `x**16 + x**14 + x**13 + x**11 + 1`.
+pub type GaloisLfsr16 = GaloisLfsr<0xB400>;
+
+/// 24 bit Galois LFSR with the maximal-length polynomial `x**24 + x**23 + x**22 + x**17 + 1`.
+pub type GaloisLfsr24 = GaloisLfsr<0xE1_0000>;
+
+/// 32 bit Galois LFSR with the maximal-length polynomial `x**32 + x**22 + x**2 + x + 1`.
+pub type GaloisLfsr32 = GaloisLfsr<0x8020_0003>;
+
+impl<const POLY: u32> GaloisLfsr<POLY> {
+    /// The feedback polynomial toggle mask.
+    pub const POLY: u32 = POLY;
+
+    /// The width of the register, in bits.
+    pub const WIDTH: u32 = {
+        assert!(POLY != 0, "GaloisLfsr: The polynomial must not be 0.");
+        u32::BITS - POLY.leading_zeros()
+    };
+
+    /// The period of the generator, if `POLY` is a maximal-length polynomial.
+    pub const PERIOD: u64 = (1 << Self::WIDTH) - 1;
+
+    /// Mask of all register bits.
+    const MASK: u32 = (Self::PERIOD & u32::MAX as u64) as u32;
+
+    /// Create a new instance with default initial `state = 1`.
+    #[inline]
+    pub const fn new() -> Self {
+        Self::new_state(1)
+    }
+
+    /// Create a new instance with user specified initial state.
+    ///
+    /// Bits above the register width are ignored.
+    ///
+    /// Special state 0: The state must not be 0. If 0 is passed to this function,
+    ///                  then the state 1 is picked instead.
+    #[inline]
+    pub const fn new_state(state: u32) -> Self {
+        let state = state & Self::MASK;
+        Self {
+            state: if state == 0 { 1 } else { state },
+        }
+    }
+
+    /// Get the current register state.
+    #[inline]
+    pub const fn state(&self) -> u32 {
+        self.state
+    }
+
+    /// Do one LFSR step and get the output bit.
+    #[inline]
+    pub fn next_bit(&mut self) -> bool {
+        let bit = self.state & 1 != 0;
+        self.state >>= 1;
+        if bit {
+            self.state ^= POLY;
+        }
+        bit
+    }
+}
+
+impl<const POLY: u32> Default for GaloisLfsr<POLY> {
+    /// Create a new instance with default initial `state = 1`.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const POLY: u32> Shr3Ops<T> for GaloisLfsr<POLY>
+    where T: BaseOps + PartialOrd,
+          T::U: BaseOps,
+          Wrapping<T::U>: Sub<Output=Wrapping<T::U>> + Add<Output=Wrapping<T::U>> + PartialOrd + ShlAssign<usize> + BitOrAssign,
+{
+    #[inline]
+    fn get_bits(&mut self, bitcount: u8) -> T {
+        T::from_unsigned(Shr3Ops::<T>::get_bits_unsigned(self, bitcount))
+    }
+
+    fn get_bits_unsigned(&mut self, bitcount: u8) -> Wrapping<T::U> {
+        debug_assert!(bitcount <= T::NUMBITS);
+        let mut ret = Wrapping(T::U::from_u8(0));
+        for _ in 0..bitcount {
+            let bit = self.next_bit();
+            ret <<= 1;
+            ret |= Wrapping(T::U::from_u8(bit as u8));
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn period<const POLY: u32>(start: u32) -> u64 {
+        let mut a = GaloisLfsr::<POLY>::new_state(start);
+        let mut count = 0;
+        loop {
+            a.next_bit();
+            count += 1;
+            if a.state() == start {
+                break count;
+            }
+        }
+    }
+
+    #[test]
+    fn test_galois_lfsr() {
+        assert_eq!(GaloisLfsr8::WIDTH, 8);
+        assert_eq!(GaloisLfsr16::WIDTH, 16);
+        assert_eq!(GaloisLfsr24::WIDTH, 24);
+        assert_eq!(GaloisLfsr32::WIDTH, 32);
+        assert_eq!(GaloisLfsr32::PERIOD, 0xFFFF_FFFF);
+
+        assert_eq!(period::<0xB8>(1), GaloisLfsr8::PERIOD);
+        assert_eq!(period::<0xB400>(0xACE1), GaloisLfsr16::PERIOD);
+        // Not maximal-length: x**4 + x**2 + 1 = (x**2 + x + 1)**2
+        assert!(period::<0xA>(1) < GaloisLfsr::<0xA>::PERIOD);
+
+        let mut a = GaloisLfsr16::new_state(0xACE1);
+        assert!(a.next_bit());
+        assert_eq!(a.state(), 0xE270);
+        assert!(!a.next_bit());
+        assert_eq!(a.state(), 0x7138);
+        let mut a = GaloisLfsr16::new_state(0xACE1);
+        let x: u8 = a.get_bits(4);
+        assert_eq!(x, 0x8);
+        assert_eq!(a.state(), 0x1C4E);
+
+        assert_eq!(GaloisLfsr8::new_state(0x100).state(), 1);
+        assert_eq!(GaloisLfsr8::new_state(0x1FF).state(), 0xFF);
+        assert_eq!(GaloisLfsr8::default(), GaloisLfsr8::new());
+
+        let mut a = GaloisLfsr32::new_state(42);
+        for _ in 0..1000 {
+            let x: u32 = a.get_max(12345);
+            assert!(x <= 12345);
+        }
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
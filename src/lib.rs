@@ -33,33 +33,284 @@
 //!     assert_eq!(y, 0x2CC);                       // Extracted random value.
 //!
 //!     let mut shr3 = Shr3::new_state(42);         // SHR3 with custom seed (42).
+//!     let mut buf = [0_u16; 16];
+//!     shr3.fill_slice(&mut buf);                  // Fill all elements of buf with random values.
 //!
 //!     let mut shr3: Shr3 = Default::default();    // Alternative to Shr::new().
 //! ```
 //!
+//! # Word mode
+//!
+//! `Shr3::get_word32()`, `Shr3::fill_words32()` and `Shr3::fill_bytes_word32()` return the whole
+//! 32 bit state after each round instead of one bit per round.
+//! That is about 32 times faster for bulk fills, but the word mode stream is different
+//! from the bit stream of `Shr3Ops` and consecutive words are strongly correlated.
+//!
+//! # Const evaluation
+//!
+//! `shr3_const()`, `Shr3::new_state()`, `Shr3::new_state_warmed()` and `Shr3::get_bits_const()` are `const fn`s.
+//! Lookup tables of random values (e.g. dither matrices or hash salts) can be generated at compile time.
+//! The values are identical to the values generated at runtime.
+//!
+//! # Other generators
+//!
+//! Besides `Shr3`, the crate includes these generators with the same `Shr3Ops` interface:
+//!
+//! * `Xorshift<A, B, C>`: 32 bit xorshift with any shift triple, e.g. one of Marsaglia's full period triples. `Shr3` is `Xorshift<13, 17, 5>`.
+//! * `Shr3Block`: Block buffered SHR3 with a bit exact identical stream. Cheaper small extractions.
+//! * `Shr3Buffered`: SHR3 wrapper with one round per 32 bits (word mode) and a cache for the unconsumed bits. Faster, but the words are correlated.
+//! * `Shr3x4`, `Shr3x8`: Batch generators with 4 or 8 interleaved SHR3 states for fast bulk fills in word mode.
+//! * `Shr3Tap<TAP>`: SHR3 with a configurable output bit (or the state parity) instead of the LSB.
+//! * `Shr3_16`: 16 bit xorshift with a short period of `2**16 - 1`, for very small microcontrollers.
+//! * `Shr3_64`: 64 bit xorshift with a period of `2**64 - 1`.
+//! * `Shr3Weyl`: SHR3 plus a Weyl sequence with a period of approximately `2**64`.
+//! * `Xorshift128`: Marsaglia's xorshift128 with four 32 bit words of state and a period of `2**128 - 1`.
+//! * `Xorwow`: Marsaglia's xorwow (xorshift plus Weyl counter). Requires the `xorwow` feature.
+//! * `Kiss32`: Marsaglia's KISS, the combination of SHR3, multiply-with-carry and a congruential generator.
+//! * `Taus88`: L'Ecuyer's maximally equidistributed combined Tausworthe generator with a period of approximately `2**88`.
+//! * `GaloisLfsr<POLY>`: Galois LFSR with a user specified feedback polynomial. One step per bit.
+//! * `Prbs7`, `Prbs15`, `Prbs23`, `Prbs31`: Bit exact standard ITU-T O.150 test sequences for BER testing.
+//!   `PrbsChecker` synchronizes onto a received sequence and counts the bit errors.
+//!
+//! # Scrambling
+//!
+//! `Scrambler` and `Descrambler` are self-synchronizing (multiplicative) scramblers
+//! with a configurable polynomial for DC balancing serial links.
+//! Presets for IEEE 802.3 64b/66b and the ATM/SDH `1 + x**43` polynomial are included.
+//!
+//! # Whitening
+//!
+//! `Pn9Whitening` whitens and de-whitens radio packets in place.
+//! It is compatible with the PN9 whitening of the TI CC1101 and Semtech SX127x transceivers.
+//! `BleWhitening` implements the channel dependent Bluetooth Low Energy whitening
+//! and `Ieee802154Whitening` the IEEE 802.15.4g whitening.
+//!
+//! # Memory test
+//!
+//! `MemTestPattern` fills a RAM region with a reproducible word stream from a seed.
+//! With the same seed it verifies a RAM or flash region and reports the first mismatching address.
+//!
 //! # no_std
 //!
-//! This crate does not require the Rust std library. It does not link to std.
+//! This crate does not require the Rust std library. It does not link to std,
+//! unless the `std` feature is enabled.
+//!
+//! # rand_core
+//!
+//! If the `rand_core` feature is enabled, then `Shr3` implements the
+//! `rand_core::RngCore` and `rand_core::SeedableRng` traits.
+//! That makes `Shr3` usable with all crates of the `rand` ecosystem.
+//!
+//! # fixed
+//!
+//! If the `fixed` feature is enabled, then the fixed point types of the `fixed` crate
+//! (e.g. `I16F16` or `U0F32`) can be used as output types of `Shr3Ops`.
+//! The random bits are mapped directly onto the raw representation of the fixed point value.
+//!
+//! # libm
+//!
+//! If the `libm` feature is enabled, then the floating point distributions
+//! (e.g. `dist::Normal`) are available.
+//! They use the `libm` crate for the math functions and therefore also work on `no_std`.
+//!
+//! # heapless
+//!
+//! If the `heapless` feature is enabled, then `Shr3::fill_vec()` and `Shr3::fill_string()`
+//! fill the `heapless::Vec` and `heapless::String` collections with random contents.
+//! `Shr3::choose()` and `Shr3::shuffle()` work on these collections without the feature.
+//!
+//! # alloc
+//!
+//! If the `alloc` feature is enabled, then `Shr3::random_vec()`, `Shr3::random_vec_range()`
+//! and `Shr3::random_bytes()` create `Vec`s of random values.
+//! The crate still does not link to std. It only requires the `alloc` crate.
+//!
+//! # serde
+//!
+//! If the `serde` feature is enabled, then `Shr3` implements the
+//! `serde::Serialize` and `serde::Deserialize` traits.
+//! The generator is serialized as its `u32` state.
+//!
+//! # bytemuck
+//!
+//! If the `bytemuck` feature is enabled, then the raw state type `Shr3State`
+//! implements the `bytemuck::Pod` and `bytemuck::Zeroable` traits.
+//!
+//! # getrandom
+//!
+//! If the `getrandom` feature is enabled, then `Shr3::from_entropy()` creates a generator
+//! with a seed from the operating system or hardware random number generator.
+//!
+//! # std
+//!
+//! If the `std` feature is enabled, then `Shr3::from_time()` creates a generator
+//! with a seed from the current system time.
+//! The `std` feature also enables the `alloc` feature.
+//!
+//! # rayon
+//!
+//! If the `rayon` feature is enabled, then `Shr3::par_fill()` fills large byte slices on all rayon worker threads.
+//! Each thread starts at a jump ahead offset of the same seed.
+//! The result is bit exact identical to `Shr3::fill_bytes()`.
+//! The `rayon` feature also enables the `std` feature.
+//!
+//! # macros
+//!
+//! If the `macros` feature is enabled, then `shr3_table!(u8; 256; seed = 42)` expands to
+//! an array of random values generated at compile time.
+//! The values are bit exact identical to `Shr3::fill_slice()` of `Shr3::new_state(42)`.
+//! That replaces table generation in build scripts.
+//!
+//! The feature also enables `#[derive(Random)]` for structs and enums.
+//! The fields are generated with the `Random` trait or with `#[random(range = 1..=10)]` in a range.
+//! The variant of an enum is chosen uniformly.
+//! `Shr3Random::get_random()` generates a value of a derived type with any generator.
+//!
+//! # xorwow
+//!
+//! If the `xorwow` feature is enabled, then the `Xorwow` generator is available.
+//!
+//! # validate
+//!
+//! If the `validate` feature is enabled, then `gf2::is_full_period()` checks whether
+//! a shift triple of `Xorshift<A, B, C>` has the full period of `2**32 - 1` iterations.
+//!
+//! # simd
+//!
+//! If the `simd` feature is enabled, then the batch generators `Shr3x4` and `Shr3x8`
+//! process their lanes with `core::simd`.
+//! `core::simd` requires a nightly compiler.
+//! On a stable compiler this feature is ignored and the lanes are processed without `core::simd`.
+//!
+//! Independent of this feature, `fill()` of the batch generators uses
+//! hand written SSE2 or AVX2 kernels on x86 and x86_64, NEON kernels on AArch64
+//! and SIMD128 kernels on WebAssembly (with `-C target-feature=+simd128`).
+//! If the `std` feature is enabled, then the best kernel is selected at runtime by CPU feature detection.
+//! Otherwise the kernel is selected by the target features enabled at compile time (e.g. `-C target-cpu=native`).
 //!
 //! # Optimized implementation
 //!
-//! This crate includes an optimized implementation for AVR 8-bit.
+//! This crate includes an optimized implementation of `shr3()` and `shr3_16()` for AVR 8-bit.
+//! `shr3_const()` always uses the generic implementation.
+//! On AVR the bit extraction of `Shr3` also runs in assembly, 8 bits at a time.
+//! AVR cores without the `mul` instruction (e.g. classic ATtiny) use a variant without multiplications.
+//! It is selected automatically by the `mul` target feature.
+//!
+//! If the `tiny-code` feature is enabled, then a smaller loop based AVR implementation is used instead.
+//! It needs fewer instructions, but more cycles per round.
+//! That is useful for bootloaders and devices with little flash memory.
+//!
+//! On ARMv6-M (Cortex-M0 and Cortex-M0+) `shr3()` and the bit extraction of `Shr3`
+//! use a Thumb-1 assembly implementation.
+//!
+//! On 32 bit RISC-V (RV32I and RV32E, e.g. CH32V003) `shr3()` and the bit extraction of `Shr3`
+//! use an assembly implementation with a small register footprint.
+//!
+//! On Xtensa (ESP32, ESP32-S2 and ESP32-S3) `shr3()` and the bit extraction of `Shr3`
+//! use an assembly implementation, too.
 //!
 //! All other architectures use the generic implementation.
 //! On most architectures, this generic implementation will be compiled to rather efficient code.
+//!
+//! If the `force-generic` feature is enabled, then the generic implementation is used on all architectures.
+//! No assembly and no `unsafe` code is compiled for the shuffle function then.
+//! That is useful to compare both implementations in benchmarks.
+//!
+//! The `arch` module gives access to the individual implementations, e.g. `arch::generic::shr3()`.
+//! `arch::current()` tells which implementation `shr3()` uses on the target.
 
 #![no_std]
-#![cfg_attr(target_arch="avr", feature(asm_experimental_arch))]
+#![cfg_attr(all(any(target_arch="avr", target_arch="xtensa"), not(feature="force-generic")), feature(asm_experimental_arch))]
+#![cfg_attr(all(feature="simd", has_portable_simd), feature(portable_simd))]
+
+#[cfg(feature="alloc")]
+extern crate alloc;
+#[cfg(feature="std")]
+extern crate std;
+// The derive macros refer to `::shr3`.
+#[cfg(all(test, feature="macros"))]
+extern crate self as shr3;
 
 pub mod prelude {
     pub use crate::Shr3;
     pub use crate::Shr3Ops as _;
+    pub use crate::Shr3Random as _;
 }
 
-#[cfg(not(feature="__devmode__"))]
-mod arch;
-#[cfg(feature="__devmode__")]
 pub mod arch;
+mod batch;
+mod block;
+mod buffered;
+pub mod charset;
+mod counted;
+pub mod dist;
+mod fraction;
+pub mod gf2;
+mod iter;
+mod kiss32;
+mod leapfrog;
+mod lfsr;
+mod memtest;
+mod prbs;
+mod random;
+mod reseeding;
+mod scrambler;
+mod seed;
+mod shr3_16;
+mod shr3_64;
+mod state;
+mod tap;
+mod taus88;
+mod tuple;
+mod weyl;
+mod whitening;
+mod wordbits;
+mod xorshift128;
+#[cfg(feature="rand_core")]
+mod rng;
+#[cfg(feature="fixed")]
+mod fixedpoint;
+#[cfg(feature="heapless")]
+mod collections;
+#[cfg(feature="alloc")]
+mod vec;
+#[cfg(feature="serde")]
+mod serde;
+#[cfg(feature="xorwow")]
+mod xorwow;
+#[cfg(feature="rayon")]
+mod par;
+
+pub use crate::batch::{Shr3x4, Shr3x8};
+pub use crate::block::Shr3Block;
+pub use crate::buffered::Shr3Buffered;
+pub use crate::counted::Shr3Counted;
+pub use crate::fraction::{scale_q16, scale_q32};
+pub use crate::iter::{Shr3Bits, Shr3Chunks, Shr3Iter, Shr3Permutation};
+pub use crate::kiss32::Kiss32;
+pub use crate::leapfrog::Shr3Leapfrog;
+pub use crate::lfsr::{GaloisLfsr, GaloisLfsr8, GaloisLfsr16, GaloisLfsr24, GaloisLfsr32};
+pub use crate::memtest::{MemTestMismatch, MemTestPattern};
+pub use crate::prbs::{Prbs, Prbs7, Prbs15, Prbs23, Prbs31};
+pub use crate::prbs::{PrbsChecker, Prbs7Checker, Prbs15Checker, Prbs23Checker, Prbs31Checker};
+pub use crate::random::{Random, Shr3Random};
+pub use crate::reseeding::Reseeding;
+pub use crate::scrambler::{Descrambler, Descrambler64b66b, DescramblerX43, Scrambler, Scrambler64b66b, ScramblerX43};
+pub use crate::seed::{mix32, seed_from_str};
+pub use crate::shr3_16::{Shr3_16, shr3_16};
+pub use crate::shr3_64::{Shr3_64, shr3_64};
+pub use crate::state::{PersistentState, Shr3State};
+pub use crate::tap::{Shr3Tap, TAP_PARITY};
+use crate::tap::tap_bit;
+pub use crate::taus88::Taus88;
+pub use crate::tuple::Shr3Tuple;
+pub use crate::weyl::Shr3Weyl;
+pub use crate::whitening::{BleWhitening, Ieee802154Whitening, Pn9Whitening};
+pub use crate::xorshift128::Xorshift128;
+#[cfg(feature="xorwow")]
+pub use crate::xorwow::Xorwow;
+#[cfg(feature="macros")]
+pub use shr3_macros::{Random, shr3_table};
 
 use core::ops::{
     Add,
@@ -67,9 +318,19 @@ use core::ops::{
     Bound,
     RangeBounds,
     ShlAssign,
+    Shr,
     Sub,
 };
-use core::num::Wrapping;
+use core::num::{
+    NonZeroI8, NonZeroU8,
+    NonZeroI16, NonZeroU16,
+    NonZeroI32, NonZeroU32,
+    NonZeroI64, NonZeroU64,
+    NonZeroIsize, NonZeroUsize,
+    Wrapping,
+};
+#[cfg(has_u128)]
+use core::num::{NonZeroI128, NonZeroU128};
 
 /// One round of the SHR3 shuffle function.
 ///
@@ -86,240 +347,1471 @@ use core::num::Wrapping;
 ///
 /// The fixed variant with a full `2**32 - 1` cycle is implemented.
 pub fn shr3(state: u32) -> u32 {
-    #[cfg(target_arch="avr")]
+    #[cfg(all(target_arch="avr", not(feature="force-generic")))]
     let state = arch::avr::shr3(state);
 
-    #[cfg(not(target_arch="avr"))]
+    #[cfg(all(armv6m, not(feature="force-generic")))]
+    let state = arch::armv6m::shr3(state);
+
+    #[cfg(all(target_arch="riscv32", not(feature="force-generic")))]
+    let state = arch::riscv32::shr3(state);
+
+    #[cfg(all(target_arch="xtensa", not(feature="force-generic")))]
+    let state = arch::xtensa::shr3(state);
+
+    #[cfg(any(feature="force-generic",
+              not(any(target_arch="avr", armv6m, target_arch="riscv32", target_arch="xtensa"))))]
     let state = arch::generic::shr3(state);
 
     state
 }
 
+/// One round of the SHR3 shuffle function in a const context.
+///
+/// This is identical to `shr3()`, but it is a `const fn`.
+/// It can be used to generate tables at compile time.
+/// Therefore, it always uses the generic implementation instead of the optimized implementation.
+///
+/// ```
+///     const TABLE: [u32; 4] = {
+///         let mut table = [0; 4];
+///         let mut state = 42;
+///         let mut i = 0;
+///         while i < table.len() {
+///             state = shr3::shr3_const(state);
+///             table[i] = state;
+///             i += 1;
+///         }
+///         table
+///     };
+///     assert_eq!(TABLE[0], shr3::shr3(42));
+/// ```
+#[inline]
+pub const fn shr3_const(state: u32) -> u32 {
+    arch::generic::shr3(state)
+}
+
+/// One round of the inverse SHR3 shuffle function.
+///
+/// This reverts one round of `shr3()`: `shr3_inv(shr3(x)) == x`
+///
+/// *Hint*: You probably want to use `Shr3::step_back()` or `Shr3::prev_bits()` instead.
+pub const fn shr3_inv(mut state: u32) -> u32 {
+    // Revert `state ^= state << 5`.
+    state ^= state << 5;
+    state ^= state << 10;
+    state ^= state << 20;
+    // Revert `state ^= state >> 17`.
+    state ^= state >> 17;
+    // Revert `state ^= state << 13`.
+    state ^= state << 13;
+    state ^= state << 26;
+    state
+}
+
+/// Xorshift generator register state with the shift triple (`A`, `B`, `C`).
+///
+/// One round of the shuffle function is
+/// `state ^= state << A; state ^= state >> B; state ^= state << C`.
+/// The shuffle function is evaluated once per extracted random bit
+/// and the state bit selected by `TAP` is extracted as output (see `Shr3Tap`).
+///
+/// `Shr3` is the instance with the triple (13, 17, 5) and the LSB as output.
+/// Any of the 81 full period triples from "Xorshift RNGs" by George Marsaglia (2003)
+/// can be instantiated, e.g. `Xorshift<1, 3, 10>`.
+/// Other triples do not have the full period of `2**32 - 1` iterations.
+/// `gf2::is_full_period()` checks a triple (requires the `validate` feature).
+///
+/// The additional methods of `Shr3` (e.g. `Shr3::discard()`) are only available for the SHR3 triple.
+///
+/// ```
+///     use shr3::prelude::*;
+///     use shr3::Xorshift;
+///
+///     let mut a = Xorshift::<13, 17, 5>::new_state(42);
+///     let mut b = Shr3::new_state(42);
+///     let x: u32 = a.get();
+///     let y: u32 = b.get();
+///     assert_eq!(x, y);
+///
+///     let mut c = Xorshift::<1, 3, 10>::new_state(42);
+///     let z: u32 = c.get();
+///     assert_ne!(x, z);
+/// ```
+pub struct Xorshift<const A: u32, const B: u32, const C: u32, const TAP: u32 = 0> {
+    state: u32,
+}
+
 /// SHR3 generator register state.
-pub struct Shr3 {
+pub type Shr3 = Xorshift<13, 17, 5>;
+
+/// Saved position in the random stream of a `Shr3` generator.
+///
+/// Create it with `Shr3::checkpoint()` and rewind the generator with `Shr3::restore()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shr3Checkpoint {
     state: u32,
 }
 
-impl Shr3 {
-    /// Create a new SHR3 instance with default initial `state = 1`.
+impl<const A: u32, const B: u32, const C: u32, const TAP: u32> Xorshift<A, B, C, TAP> {
+    /// Compile time check of the generic parameters.
+    const PARAMS_OK: () = {
+        assert!(A > 0 && A < 32 && B > 0 && B < 32 && C > 0 && C < 32,
+                "Xorshift: Invalid shift triple.");
+        assert!(TAP <= TAP_PARITY, "Xorshift: Invalid TAP.");
+    };
+
+    /// Create a new generator instance with default initial `state = 1`.
     #[inline]
-    pub const fn new() -> Shr3 {
+    pub const fn new() -> Self {
         Self::new_state(1)
     }
 
-    /// Create a new SHR3 instance with user specified initial state.
+    /// Create a new generator instance with user specified initial state.
     ///
-    /// Special state 0: The SHR3 state must not be 0. If 0 is passed to this function,
+    /// Special state 0: The state must not be 0. If 0 is passed to this function,
     ///                  then the state 0x7FFFFFFF is picked instead.
     #[inline]
-    pub const fn new_state(state: u32) -> Shr3 {
-        Shr3 {
+    pub const fn new_state(state: u32) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::PARAMS_OK;
+        Self {
             state: if state == 0 { 0x7FFFFFFF } else { state },
         }
     }
-}
 
-impl Default for Shr3 {
-    /// Create a new SHR3 instance with default initial `state = 1`.
+    /// Get the current generator state.
+    ///
+    /// The state can be persisted and later restored with `Shr3::new_state()` or `Shr3::set_state()`.
+    /// The restored generator continues with the identical random stream.
     #[inline]
-    fn default() -> Self {
-        Self::new()
+    pub const fn state(&self) -> u32 {
+        self.state
     }
-}
-
-/// Internal trait for basic operations on the output type.
-pub trait BaseOps: Copy
-{
-    /// Unsigned companion of Self.
-    type U;
-
-    /// Number of bits in type `Self`.
-    const NUMBITS: u8;
-    /// Smallest possible value of type `Self`.
-    const MINVAL: Self;
-    /// Biggest possible value of type `Self`.
-    const MAXVAL: Self;
 
-    /// Convert an `u8` to `Self`.
-    fn from_u8(v: u8) -> Self;
-
-    /// Convert a wrapping unsigned to a bit identical Self.
-    fn from_unsigned(v: Wrapping<Self::U>) -> Self;
-    /// Convert self to a bit identical wrapping unsigned.
-    fn to_unsigned(&self) -> Wrapping<Self::U>;
-
-    /// Find last bit set in `self`.
-    ///
-    /// Bit 0 -> returns 1; Bit 1 -> returns 2; ...
+    /// Set the generator state.
     ///
-    /// If no bit set -> returns 0.
-    fn fls(&self) -> u8;
-}
+    /// The same special state 0 handling as in `Shr3::new_state()` applies.
+    #[inline]
+    pub fn set_state(&mut self, state: u32) {
+        *self = Self::new_state(state);
+    }
 
-macro_rules! impl_base_ops {
-    // $u = unsigned type.
-    // $s = companion signed type.
-    ($( ($u:ty, $s:ty) ),*) => {
-        $(
-            impl BaseOps for $u {
-                type U = $u;
-                const NUMBITS: u8 = <$u>::BITS as u8;
-                const MINVAL: $u = <$u>::MIN;
-                const MAXVAL: $u = <$u>::MAX;
-                #[inline]
-                fn from_u8(v: u8) -> $u {
-                    v as $u
-                }
-                #[inline]
-                fn from_unsigned(v: Wrapping<Self::U>) -> Self {
-                    v.0
-                }
-                #[inline]
-                fn to_unsigned(&self) -> Wrapping<Self::U> {
-                    Wrapping(*self)
-                }
-                #[inline]
-                fn fls(&self) -> u8 {
-                    (<$u>::BITS - self.leading_zeros()) as u8
-                }
-            }
-            impl BaseOps for $s {
-                type U = $u;
-                const NUMBITS: u8 = <$s>::BITS as u8;
-                const MINVAL: $s = <$s>::MIN;
-                const MAXVAL: $s = <$s>::MAX;
-                #[inline]
-                fn from_u8(v: u8) -> $s {
-                    v as $s
-                }
-                #[inline]
-                fn from_unsigned(v: Wrapping<Self::U>) -> Self {
-                    v.0 as Self
-                }
-                #[inline]
-                fn to_unsigned(&self) -> Wrapping<Self::U> {
-                    Wrapping(*self as Self::U)
-                }
-                #[inline]
-                fn fls(&self) -> u8 {
-                    (<$s>::BITS - self.leading_zeros()) as u8
-                }
-            }
-        )*
+    /// One round of the shuffle function.
+    #[inline]
+    fn round(state: u32) -> u32 {
+        if A == 13 && B == 17 && C == 5 {
+            // Use the optimized implementation, if available.
+            shr3(state)
+        } else {
+            let mut state = state;
+            state ^= state << A;
+            state ^= state >> B;
+            state ^= state << C;
+            state
+        }
     }
 }
 
-impl_base_ops!((u8, i8), (u16, i16), (u32, i32), (u64, i64), (usize, isize));
-#[cfg(has_u128)]
-impl_base_ops!((u128, i128));
-
-/// Main operations for extracting bits from SHR3 generator.
-///
-/// The type `T` can be either of
-/// `u8`, `i8`, `u16`, `i16`, `u32`, `i32`, `u64`, `i64`, `u128`, `i128`, `usize` or `isize`.
-pub trait Shr3Ops<T>:
-    where T: BaseOps + Sub<Output=T> + PartialOrd,
-          T::U: BaseOps,
-          Wrapping<T::U>: Sub<Output=Wrapping<T::U>> + Add<Output=Wrapping<T::U>> + PartialOrd,
-{
-    /// Get a number of `bitcount` bits from SHR3 and store them in the lower
-    /// bits of the returned type `T`.
+impl Shr3 {
+    /// Create a new SHR3 instance with user specified initial state
+    /// and run `rounds` burn-in rounds.
     ///
-    /// `bitcount` must be lower or equal to the number of bits in `T`.
-    fn get_bits(&mut self, bitcount: u8) -> T;
+    /// Seeds with few set bits (e.g. 1 or a small counter) produce visibly structured first outputs.
+    /// The burn-in rounds spread the seed bits over the whole state.
+    /// 32 rounds are a reasonable choice.
+    ///
+    /// This is identical to `Shr3::new_state()` followed by `Shr3::discard(rounds)`.
+    /// The same special state 0 handling as in `Shr3::new_state()` applies.
+    pub const fn new_state_warmed(state: u32, rounds: u32) -> Shr3 {
+        let mut ret = Self::new_state(state);
+        let mut i = 0;
+        while i < rounds {
+            ret.state = shr3_const(ret.state);
+            i += 1;
+        }
+        ret
+    }
 
-    /// Get as many bits from SHR3 as fit into the return type `T`.
+    /// Extract `bitcount` (0 to 32) bits in a const context.
     ///
-    /// *Note*: Consider using `get_bits()` instead, if you don't need all returned bits.
-    #[inline]
-    fn get(&mut self) -> T {
-        self.get_bits(T::NUMBITS)
+    /// This is identical to `Shr3Ops::<u32>::get_bits()`,
+    /// but it is a `const fn` and it uses `shr3_const()`.
+    /// It can be used to generate tables of random values at compile time.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     const TABLE: [u8; 16] = {
+    ///         let mut shr3 = Shr3::new_state(42);
+    ///         let mut table = [0; 16];
+    ///         let mut i = 0;
+    ///         while i < table.len() {
+    ///             table[i] = shr3.get_bits_const(8) as u8;
+    ///             i += 1;
+    ///         }
+    ///         table
+    ///     };
+    ///
+    ///     let mut shr3 = Shr3::new_state(42);
+    ///     let x: u8 = shr3.get();
+    ///     assert_eq!(TABLE[0], x);
+    /// ```
+    pub const fn get_bits_const(&mut self, bitcount: u8) -> u32 {
+        assert!(bitcount <= 32);
+        let mut ret = 0;
+        let mut i = 0;
+        while i < bitcount {
+            self.state = shr3_const(self.state);
+            ret = (ret << 1) | (self.state & 1);
+            i += 1;
+        }
+        ret
     }
 
-    /// Get enough bits to construct a random value in the range between `min_value` and `max_value`.
+    /// Mix external entropy from `data` into the SHR3 state.
     ///
-    /// *Note*: If the extracted range is of non-power-of-two size,
-    ///        then the number of bits extracted from the SHR3 generator will
-    ///        be bigger to ensure an even distribution of the returned values.
-    fn get_minmax(&mut self, min_value: T, max_value: T) -> T {
-        debug_assert!(max_value >= min_value);
-        let range = max_value.to_unsigned() - min_value.to_unsigned();
-        let num_bits = range.0.fls();
-        let value = loop {
-            let value = self.get_bits(num_bits).to_unsigned();
-            if value <= range {
-                break value;
-            }
-        };
-        T::from_unsigned(value + min_value.to_unsigned())
+    /// Each little endian 32 bit word of `data` (the last one zero padded)
+    /// is XORed into the state, followed by one round of `shr3()`.
+    /// The same special state 0 handling as in `Shr3::new_state()` applies to the resulting state.
+    ///
+    /// Use this to stir cheap physical randomness such as ADC noise or timer jitter
+    /// into a running generator.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     let mut shr3 = Shr3::new_state(42);
+    ///     let adc_noise: u16 = 0x1234;
+    ///     shr3.absorb(&adc_noise.to_le_bytes());
+    /// ```
+    pub fn absorb(&mut self, data: &[u8]) {
+        let mut state = self.state;
+        for chunk in data.chunks(4) {
+            let mut word = [0; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            state = shr3(state ^ u32::from_le_bytes(word));
+        }
+        self.set_state(state);
     }
 
-    /// Get enough bits to construct a random value in the range between `0` and `max_value`.
+    /// Skip `rounds` SHR3 rounds (extracted bits) of the random stream.
     ///
-    /// *Note*: If the extracted range is of non-power-of-two size,
-    ///        then the number of bits extracted from the SHR3 generator will
-    ///        be bigger to ensure an even distribution of the returned values.
+    /// This is identical to extracting and discarding `rounds` bits,
+    /// but it does not iterate over the skipped rounds.
+    /// It uses GF(2) matrix exponentiation with `O(log rounds)` 32x32 bit matrix squarings.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     let mut shr3 = Shr3::new_state(42);
+    ///     shr3.discard(16);
+    ///     let x: u16 = shr3.get();
+    ///     assert_eq!(x, 0x2C5C);
+    /// ```
     #[inline]
-    fn get_max(&mut self, max_value: T) -> T {
-        self.get_minmax(T::MINVAL, max_value)
+    pub fn discard(&mut self, rounds: u64) {
+        self.state = gf2::jump(self.state, rounds);
     }
 
-    /// Get enough bits to construct a random value in the given `range`.
+    /// Walk the random stream backwards by `rounds` SHR3 rounds (extracted bits).
     ///
-    /// *Note*: If the extracted range is of non-power-of-two size,
-    ///        then the number of bits extracted from the SHR3 generator will
-    ///        be bigger to ensure an even distribution of the returned values.
-    fn get_range(&mut self, range: impl RangeBounds<T>) -> T {
-        let min = match range.start_bound() {
-            Bound::Included(x) => *x,
-            Bound::Excluded(_) | Bound::Unbounded => T::MINVAL,
-        };
-        let max = match range.end_bound() {
-            Bound::Included(x) => *x,
-            Bound::Excluded(x) => {
-                debug_assert!(*x > T::MINVAL);
-                *x - T::from_u8(1) // to included
-            },
-            Bound::Unbounded => T::MAXVAL,
-        };
-        self.get_minmax(min, max)
+    /// This reverts `Shr3::discard()` and the extraction of `rounds` bits.
+    /// It uses GF(2) matrix exponentiation like `Shr3::discard()`.
+    #[inline]
+    pub fn step_back(&mut self, rounds: u64) {
+        self.discard(gf2::PERIOD - rounds % gf2::PERIOD);
     }
-}
 
-/// Shr3Ops for struct Shr3.
-impl<T> Shr3Ops<T> for Shr3
-    where T: BaseOps + Sub<Output=T> + PartialOrd,
-          T::U: BaseOps,
-          Wrapping<T::U>: Sub<Output=Wrapping<T::U>> + Add<Output=Wrapping<T::U>> + PartialOrd + ShlAssign<usize> + BitOrAssign,
-{
-    fn get_bits(&mut self, bitcount: u8) -> T {
+    /// Get the `bitcount` most recently extracted bits and walk the random stream backwards.
+    ///
+    /// This is the reverse of `Shr3Ops::get_bits()`.
+    /// The returned value is the value returned by the reverted `get_bits(bitcount)` call.
+    /// Each bit is reverted with one round of `shr3_inv()`.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     let mut shr3 = Shr3::new_state(42);
+    ///     let x: u16 = shr3.get();
+    ///     let y: u16 = shr3.prev_bits(16);
+    ///     assert_eq!(x, y);
+    ///     assert_eq!(shr3.state(), 42);
+    /// ```
+    pub fn prev_bits<T>(&mut self, bitcount: u8) -> T
+        where T: BaseOps,
+              T::U: BaseOps,
+              Wrapping<T::U>: ShlAssign<usize> + BitOrAssign,
+    {
         debug_assert!(bitcount <= T::NUMBITS);
-        let mut ret = T::from_u8(0).to_unsigned();
-        for _ in 0..bitcount {
-            self.state = shr3(self.state);
-            ret <<= 1;
-            ret |= T::from_u8(self.state as u8 & 1).to_unsigned();
+        let mut ret = Wrapping(T::U::from_u8(0));
+        for i in 0..bitcount {
+            let mut bit = Wrapping(T::U::from_u8(self.state as u8 & 1));
+            bit <<= i as usize;
+            ret |= bit;
+            self.state = shr3_inv(self.state);
         }
         T::from_unsigned(ret)
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn test_alg() {
-        assert_eq!(shr3(0), 0);
-        assert_eq!(shr3(0xFFFF_FFFF), 0x0003E01F);
-        assert_eq!(shr3(0x5555_5555), 0x000EDFEA);
-        assert_eq!(shr3(0xAAAA_AAAA), 0x000D3FF5);
-        assert_eq!(shr3(0x4242_4242), 0x4B4AEFA7);
-        assert_eq!(shr3(0x3C95_A60C), 0x82D826E6);
+    /// Create a generator for the leapfrog substream `i` of `k` substreams.
+    ///
+    /// The substream consists of the bits `i`, `i + k`, `i + 2k`, ...
+    /// of the random bit stream of `self`, starting at the current position.
+    /// `k` must not be 0 and `i` must be lower than `k`.
+    ///
+    /// The state of `self` is not changed.
+    /// See `Shr3Leapfrog` for details.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     let master = Shr3::new_state(42);
+    ///     let mut task0 = master.leapfrog(2, 0);
+    ///     let mut task1 = master.leapfrog(2, 1);
+    ///     let x: u8 = task0.get();
+    ///     let y: u8 = task1.get();
+    /// ```
+    #[inline]
+    pub const fn leapfrog(&self, k: u64, i: u64) -> Shr3Leapfrog {
+        Shr3Leapfrog::new(self.state, k, i)
     }
 
-    #[test]
-    fn test_base_ops() {
+    /// Create a child generator with a random stream that is decorrelated from `self`.
+    ///
+    /// 32 bits are extracted from `self` and scrambled by an integer mixing function.
+    /// The result is the seed of the child.
+    /// The child is then advanced by half of the SHR3 period with `Shr3::discard()`.
+    /// Therefore, the child stream does not start at a position in the SHR3 cycle
+    /// that is directly derived from the extracted bits.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     let mut master = Shr3::new_state(42);
+    ///     let mut task0 = master.split();
+    ///     let mut task1 = master.split();
+    ///     let x: u32 = task0.get();
+    ///     let y: u32 = task1.get();
+    ///     assert_ne!(x, y);
+    /// ```
+    pub fn split(&mut self) -> Shr3 {
+        let seed: u32 = self.get();
+        let mut child = Shr3::new_state(mix32(seed));
+        child.discard(gf2::PERIOD / 2);
+        child
+    }
+
+    /// Derive a child generator for the subsystem `stream_id`.
+    ///
+    /// The child seed is computed from the current state of `self` and `stream_id`
+    /// with the integer mixing function `mix32()`.
+    /// The state of `self` is not changed.
+    /// Therefore, the child stream of a subsystem does not depend on
+    /// how much randomness other subsystems consume.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///     use shr3::seed_from_str;
+    ///
+    ///     let master = Shr3::new_state(42);
+    ///     let mut audio = master.derive_child(seed_from_str("audio"));
+    ///     let mut graphics = master.derive_child(seed_from_str("graphics"));
+    ///     let x: u32 = audio.get();
+    ///     let y: u32 = graphics.get();
+    /// ```
+    pub const fn derive_child(&self, stream_id: u32) -> Shr3 {
+        Shr3::new_state(mix32(mix32(self.state) ^ stream_id))
+    }
+
+    /// Save the current position in the random stream.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     let mut shr3 = Shr3::new_state(42);
+    ///     let cp = shr3.checkpoint();
+    ///     let x: u16 = shr3.get();
+    ///     shr3.restore(cp);
+    ///     let y: u16 = shr3.get();
+    ///     assert_eq!(x, y);
+    /// ```
+    #[inline]
+    pub const fn checkpoint(&self) -> Shr3Checkpoint {
+        Shr3Checkpoint {
+            state: self.state,
+        }
+    }
+
+    /// Rewind or advance the generator to the saved position `checkpoint`.
+    ///
+    /// The generator continues with the identical random stream
+    /// that followed the creation of the checkpoint.
+    #[inline]
+    pub fn restore(&mut self, checkpoint: Shr3Checkpoint) {
+        self.state = checkpoint.state;
+    }
+
+    /// Fill the byte slice `buf` with random bytes.
+    ///
+    /// The extracted random stream is identical to calling
+    /// `Shr3Ops::<u8>::get()` for each byte of `buf` in ascending order.
+    /// The extraction is batched internally into 32 bit words.
+    ///
+    /// See `Shr3Ops::fill_slice()` for filling slices of other element types.
+    pub fn fill_bytes(&mut self, buf: &mut [u8]) {
+        let mut chunks = buf.chunks_exact_mut(4);
+        for chunk in &mut chunks {
+            let word: u32 = self.get();
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        for b in chunks.into_remainder() {
+            *b = self.get();
+        }
+    }
+
+    /// Word mode: Do one SHR3 round and get the whole 32 bit state.
+    ///
+    /// This is about 32 times faster than `Shr3Ops::<u32>::get()`,
+    /// which does one round per bit.
+    ///
+    /// *Note*: The word mode stream is different from the bit stream of `Shr3Ops`.
+    ///         Consecutive words are consecutive SHR3 states and therefore strongly correlated
+    ///         (each word is a fixed linear function of the previous word).
+    ///         Mixing word mode and `Shr3Ops` calls on one generator is allowed.
+    ///         Both advance the same state.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     let mut shr3 = Shr3::new_state(42);
+    ///     assert_eq!(shr3.get_word32(), shr3::shr3(42));
+    ///     assert_eq!(shr3.state(), shr3::shr3(42));
+    /// ```
+    #[inline]
+    pub fn get_word32(&mut self) -> u32 {
+        self.state = shr3(self.state);
+        self.state
+    }
+
+    /// Word mode: Fill the slice `buf` with words from `Shr3::get_word32()`.
+    ///
+    /// See `Shr3::get_word32()` for the properties of the word mode stream.
+    pub fn fill_words32(&mut self, buf: &mut [u32]) {
+        for w in buf {
+            *w = self.get_word32();
+        }
+    }
+
+    /// Word mode: Fill the byte slice `buf` with random bytes from `Shr3::get_word32()`.
+    ///
+    /// Each word is stored in big endian byte order.
+    /// If the length of `buf` is not a multiple of 4, then the last word is truncated.
+    ///
+    /// See `Shr3::get_word32()` for the properties of the word mode stream.
+    pub fn fill_bytes_word32(&mut self, buf: &mut [u8]) {
+        for chunk in buf.chunks_mut(4) {
+            let word = self.get_word32().to_be_bytes();
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+    }
+
+    /// Fill the byte slice `buf` with random characters from `charset`.
+    ///
+    /// `charset` must not be empty.
+    /// Each character is chosen like `Shr3::choose()`.
+    /// Therefore, each character of `charset` is equally probable.
+    ///
+    /// See the `charset` module for predefined character sets.
+    pub fn fill_charset(&mut self, buf: &mut [u8], charset: &[u8]) {
+        debug_assert!(!charset.is_empty());
+        for b in buf {
+            *b = *self.choose(charset).unwrap();
+        }
+    }
+
+    /// Fill the byte slice `buf` with random ASCII characters from `charset::ALPHANUMERIC`.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     let mut shr3 = Shr3::new_state(42);
+    ///     let mut label = *b"dev-XXXXXX";
+    ///     shr3.fill_ascii_alphanumeric(&mut label[4..]);
+    ///     assert!(label[4..].iter().all(u8::is_ascii_alphanumeric));
+    /// ```
+    #[inline]
+    pub fn fill_ascii_alphanumeric(&mut self, buf: &mut [u8]) {
+        self.fill_charset(buf, charset::ALPHANUMERIC);
+    }
+
+    /// Fill the byte slice `buf` with random ASCII characters from `charset::HEX_LOWER`.
+    #[inline]
+    pub fn fill_ascii_hex(&mut self, buf: &mut [u8]) {
+        self.fill_charset(buf, charset::HEX_LOWER);
+    }
+
+    /// Fill the byte slice `buf` with random ASCII characters from `charset::DIGITS`.
+    #[inline]
+    pub fn fill_ascii_digits(&mut self, buf: &mut [u8]) {
+        self.fill_charset(buf, charset::DIGITS);
+    }
+
+    /// Fill the byte slice `buf` with random ASCII characters from `charset::PRINTABLE`.
+    #[inline]
+    pub fn fill_ascii_printable(&mut self, buf: &mut [u8]) {
+        self.fill_charset(buf, charset::PRINTABLE);
+    }
+
+    /// Fill the byte slice `buf` with a random token of lower case hexadecimal digits.
+    ///
+    /// 4 bits are extracted from SHR3 per character.
+    /// The token is returned as string slice of `buf`.
+    ///
+    /// *Note*: The token is *not* cryptographically secure and it is predictable.
+    ///        Do not use it as secret, password or session identifier.
+    ///        It is only suitable for non-critical identifiers such as log correlation IDs.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     let mut shr3 = Shr3::new_state(42);
+    ///     let mut buf = [0; 8];
+    ///     assert_eq!(shr3.fill_token_hex(&mut buf), "20d32c5c");
+    /// ```
+    pub fn fill_token_hex<'a>(&mut self, buf: &'a mut [u8]) -> &'a str {
+        for b in buf.iter_mut() {
+            let index: usize = self.get_bits(4);
+            *b = charset::HEX_LOWER[index];
+        }
+        core::str::from_utf8(buf).unwrap()
+    }
+
+    /// Fill the byte slice `buf` with a random token of Crockford's base32 characters.
+    ///
+    /// 5 bits are extracted from SHR3 per character.
+    /// The token is returned as string slice of `buf`.
+    ///
+    /// *Note*: The token is *not* cryptographically secure and it is predictable.
+    ///        Do not use it as secret, password or session identifier.
+    ///        It is only suitable for non-critical identifiers such as log correlation IDs.
+    pub fn fill_token_base32<'a>(&mut self, buf: &'a mut [u8]) -> &'a str {
+        for b in buf.iter_mut() {
+            let index: usize = self.get_bits(5);
+            *b = charset::CROCKFORD_BASE32[index];
+        }
+        core::str::from_utf8(buf).unwrap()
+    }
+
+    /// Get an endless iterator over random values of type `T`.
+    ///
+    /// Each item is extracted like `Shr3Ops::get()`.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     let mut shr3 = Shr3::new();
+    ///     let mut it = shr3.iter::<u8>();
+    ///     assert_eq!(it.next(), Some(0xF8));
+    /// ```
+    #[inline]
+    pub fn iter<T>(&mut self) -> Shr3Iter<'_, Shr3, T>
+        where Self: Shr3Ops<T>,
+              T: BaseOps + PartialOrd,
+              T::U: BaseOps,
+              Wrapping<T::U>: Sub<Output=Wrapping<T::U>> + Add<Output=Wrapping<T::U>> + PartialOrd,
+    {
+        Shr3Iter::new(self)
+    }
+
+    /// Get an endless iterator over single random bits.
+    ///
+    /// Each item costs exactly one SHR3 round.
+    /// The bits are yielded in the same order as they are shifted into
+    /// the values returned by `Shr3Ops::get_bits()`.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     let mut shr3 = Shr3::new();
+    ///     let mut bits = shr3.bits();
+    ///     assert_eq!(bits.next(), Some(true));
+    /// ```
+    #[inline]
+    pub fn bits(&mut self) -> Shr3Bits<'_, Shr3> {
+        Shr3Bits::new(self)
+    }
+
+    /// Get an endless iterator over random byte arrays of fixed size `N`.
+    ///
+    /// The extracted random stream is identical to `fill_bytes()`.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     let mut shr3 = Shr3::new_state(42);
+    ///     let nonce: [u8; 4] = shr3.chunks().next().unwrap();
+    ///     assert_eq!(nonce, [0x20, 0xD3, 0x2C, 0x5C]);
+    /// ```
+    #[inline]
+    pub fn chunks<const N: usize>(&mut self) -> Shr3Chunks<'_, Shr3, N> {
+        Shr3Chunks::new(self)
+    }
+
+    /// Get an iterator over all indices in the range `0..n` in random order.
+    ///
+    /// Each index is returned exactly once without storing the permutation in memory.
+    /// See `Shr3Permutation` for details.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     let mut shr3 = Shr3::new_state(42);
+    ///     let mut visited = [false; 100];
+    ///     for sector in shr3.permutation(visited.len()) {
+    ///         visited[sector] = true;
+    ///     }
+    ///     assert!(visited.iter().all(|v| *v));
+    /// ```
+    #[inline]
+    pub fn permutation(&mut self, n: usize) -> Shr3Permutation {
+        Shr3Permutation::new(self, n)
+    }
+
+    /// Get a tuple of random values.
+    ///
+    /// The tuple elements are extracted like `Shr3Ops::get()` from left to right.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     let mut shr3 = Shr3::new_state(42);
+    ///     let (x, y): (u8, u16) = shr3.get_tuple();
+    ///     assert_eq!(x, 0x20);
+    ///     assert_eq!(y, 0xD32C);
+    /// ```
+    #[inline]
+    pub fn get_tuple<T: Shr3Tuple<Shr3>>(&mut self) -> T {
+        T::extract(self)
+    }
+
+    /// Get a random `u32` in the range between `0` and `max_value`
+    /// using multiply-shift bounded generation (Lemire's method).
+    ///
+    /// A full 32 bit value is extracted and multiplied by the range size.
+    /// The upper 32 bits of the product are the result.
+    /// The draw is repeated only if the lower 32 bits of the product fall into
+    /// the small biased region of size `2**32 mod (max_value + 1)`.
+    /// Therefore, the probability of a repeated draw is below `(max_value + 1) / 2**32`,
+    /// compared to up to almost 50% for the power-of-two rejection loop of `Shr3Ops::get_max()`.
+    /// The result is exactly evenly distributed.
+    ///
+    /// *Note*: This produces a different random stream than `Shr3Ops::get_max()`.
+    ///        `Shr3Ops::get_max()` is kept unchanged for stream compatibility.
+    pub fn get_max_lemire(&mut self, max_value: u32) -> u32 {
+        let Some(size) = max_value.checked_add(1) else {
+            return self.get();
+        };
+        let mut product = Shr3Ops::<u32>::get(self) as u64 * size as u64;
+        if (product as u32) < size {
+            // Lower bound of the unbiased region: 2**32 mod size.
+            let threshold = size.wrapping_neg() % size;
+            while (product as u32) < threshold {
+                product = Shr3Ops::<u32>::get(self) as u64 * size as u64;
+            }
+        }
+        (product >> 32) as u32
+    }
+
+    /// Get a random `u32` in the range between `min_value` and `max_value`
+    /// using multiply-shift bounded generation (Lemire's method).
+    ///
+    /// See `Shr3::get_max_lemire()`.
+    #[inline]
+    pub fn get_minmax_lemire(&mut self, min_value: u32, max_value: u32) -> u32 {
+        debug_assert!(max_value >= min_value);
+        min_value + self.get_max_lemire(max_value - min_value)
+    }
+
+    /// Get a random `u32` in the range between `0` and `max_value`
+    /// with constant execution time.
+    ///
+    /// Exactly one 32 bit value is extracted and scaled with a multiply-high operation.
+    /// There is no rejection loop.
+    /// This is equivalent to `scale_q32(self.get_fraction_q32(), max_value)`.
+    ///
+    /// *Note*: If `max_value + 1` is not a power of two,
+    ///        then the result distribution is slightly uneven.
+    ///        Each value is returned with a probability of either
+    ///        `floor(2**32 / (max_value + 1)) / 2**32` or `ceil(2**32 / (max_value + 1)) / 2**32`.
+    ///        Therefore, the worst-case relative bias of a value is below `(max_value + 1) / 2**32`.
+    ///        Use `Shr3Ops::get_max()` or `Shr3::get_max_lemire()` for an exactly even distribution.
+    #[inline]
+    pub fn get_max_fast(&mut self, max_value: u32) -> u32 {
+        scale_q32(self.get_fraction_q32(), max_value)
+    }
+
+    /// Return `true` with the probability `numerator / denominator`.
+    ///
+    /// `denominator` must not be 0 and `numerator` must be lower or equal to `denominator`.
+    ///
+    /// This uses the unbiased `Shr3Ops::get_max()` draw in the range `0..denominator`.
+    #[inline]
+    pub fn gen_ratio(&mut self, numerator: u32, denominator: u32) -> bool {
+        debug_assert!(denominator > 0);
+        debug_assert!(numerator <= denominator);
+        let value: u32 = self.get_max(denominator - 1);
+        value < numerator
+    }
+
+    /// Get a reference to a random element of `slice`.
+    ///
+    /// Each element is equally probable.
+    /// Returns `None`, if the slice is empty.
+    ///
+    /// This uses the unbiased `Shr3Ops::get_max()` draw in the range of the slice indices.
+    #[inline]
+    pub fn choose<'a, T>(&mut self, slice: &'a [T]) -> Option<&'a T> {
+        if slice.is_empty() {
+            None
+        } else {
+            let index: usize = self.get_max(slice.len() - 1);
+            Some(&slice[index])
+        }
+    }
+
+    /// Get a mutable reference to a random element of `slice`.
+    ///
+    /// See `Shr3::choose()`.
+    #[inline]
+    pub fn choose_mut<'a, T>(&mut self, slice: &'a mut [T]) -> Option<&'a mut T> {
+        if slice.is_empty() {
+            None
+        } else {
+            let index: usize = self.get_max(slice.len() - 1);
+            Some(&mut slice[index])
+        }
+    }
+
+    /// Shuffle the elements of `slice` into a random order.
+    ///
+    /// Each permutation is equally probable.
+    ///
+    /// This uses the Fisher-Yates algorithm
+    /// with one unbiased `Shr3Ops::get_max()` draw per element.
+    pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for i in (1..slice.len()).rev() {
+            let j: usize = self.get_max(i);
+            slice.swap(i, j);
+        }
+    }
+
+    /// Choose distinct random elements of `slice` without replacement.
+    ///
+    /// `buf` is filled with references to `min(buf.len(), slice.len())` distinct elements
+    /// and the number of chosen elements is returned.
+    /// Each subset of `slice` of that size is equally probable.
+    ///
+    /// *Note*: The order of the chosen elements in `buf` is not fully random.
+    ///
+    /// This uses Floyd's algorithm and does not allocate memory.
+    /// Exactly one unbiased `Shr3Ops::get_max()` draw is done per chosen element.
+    /// The duplicate check is linear in the number of already chosen elements.
+    pub fn choose_multiple<'a, T>(&mut self, slice: &'a [T], buf: &mut [&'a T]) -> usize {
+        let count = buf.len().min(slice.len());
+        for (i, j) in (slice.len() - count..slice.len()).enumerate() {
+            let index: usize = self.get_max(j);
+            let elem = &slice[index];
+            buf[i] = if buf[..i].iter().any(|e| core::ptr::eq(*e, elem)) {
+                &slice[j]
+            } else {
+                elem
+            };
+        }
+        count
+    }
+
+    /// Choose a random element from an iterator of unknown length.
+    ///
+    /// Each element is equally probable.
+    /// Returns `None`, if the iterator is empty.
+    ///
+    /// This uses reservoir sampling. The whole iterator is consumed
+    /// and one unbiased `Shr3Ops::get_max()` draw is done per element after the first one.
+    pub fn choose_from_iter<I: IntoIterator>(&mut self, iter: I) -> Option<I::Item> {
+        let mut chosen = None;
+        for (i, item) in iter.into_iter().enumerate() {
+            let index: usize = self.get_max(i);
+            if index == 0 {
+                chosen = Some(item);
+            }
+        }
+        chosen
+    }
+
+    /// Choose distinct random elements from an iterator of unknown length.
+    ///
+    /// `buf` is filled with `min(buf.len(), number of elements)` elements of the iterator
+    /// and the number of chosen elements is returned.
+    /// Each subset of the elements of that size is equally probable.
+    ///
+    /// *Note*: The order of the chosen elements in `buf` is not fully random.
+    ///
+    /// This uses reservoir sampling and does not allocate memory.
+    /// The whole iterator is consumed
+    /// and one unbiased `Shr3Ops::get_max()` draw is done per element after the first `buf.len()` ones.
+    pub fn choose_multiple_from_iter<I: IntoIterator>(&mut self, iter: I, buf: &mut [I::Item]) -> usize {
+        let mut count = 0;
+        for (i, item) in iter.into_iter().enumerate() {
+            if i < buf.len() {
+                buf[i] = item;
+                count += 1;
+            } else {
+                let index: usize = self.get_max(i);
+                if index < buf.len() {
+                    buf[index] = item;
+                }
+            }
+        }
+        count
+    }
+
+    /// Fill `out` with distinct random indices in the range `0..n`.
+    ///
+    /// `out.len()` must be lower or equal to `n`
+    /// and `n` must be lower or equal to `2**16`.
+    /// Each subset of `0..n` of size `out.len()` is equally probable.
+    ///
+    /// *Note*: The order of the indices in `out` is not fully random.
+    ///
+    /// This uses Floyd's algorithm like `Shr3::choose_multiple()`.
+    pub fn sample_indices(&mut self, n: usize, out: &mut [u16]) {
+        debug_assert!(out.len() <= n);
+        debug_assert!(n <= u16::MAX as usize + 1);
+        let count = out.len().min(n);
+        for (i, j) in (n - count..n).enumerate() {
+            let index: u16 = self.get_max(j as u16);
+            out[i] = if out[..i].contains(&index) {
+                j as u16
+            } else {
+                index
+            };
+        }
+    }
+
+    /// Get a random index into `weights`.
+    ///
+    /// The probability of each index is proportional to its weight.
+    /// An index with weight 0 is never returned.
+    ///
+    /// The sum of all weights must fit into `u32`.
+    ///
+    /// Panics, if `weights` is empty or if all weights are 0.
+    ///
+    /// This uses the unbiased `Shr3Ops::get_max()` draw in the range of the weight sum.
+    /// See `dist::AliasTable` for repeated sampling from the same weights.
+    pub fn choose_weighted(&mut self, weights: &[u16]) -> usize {
+        let sum = weights.iter().fold(0_u32, |sum, w| {
+            debug_assert!(sum.checked_add(*w as u32).is_some());
+            sum.wrapping_add(*w as u32)
+        });
+        assert!(sum > 0, "choose_weighted: The sum of the weights must not be 0.");
+        let mut value: u32 = self.get_max(sum - 1);
+        for (i, w) in weights.iter().enumerate() {
+            let w = *w as u32;
+            if value < w {
+                return i;
+            }
+            value -= w;
+        }
+        unreachable!();
+    }
+
+    /// Get the number of failed trials before the first success,
+    /// with the success probability `numerator / denominator` of each trial.
+    ///
+    /// `numerator` must not be 0 and must be lower or equal to `denominator`.
+    ///
+    /// See `dist::Geometric` for details and for a variant with precomputed probability.
+    #[inline]
+    pub fn get_geometric(&mut self, numerator: u32, denominator: u32) -> u32 {
+        dist::Geometric::from_ratio(numerator, denominator).sample(self)
+    }
+
+    /// Get an approximately normal (Gaussian) distributed random `i16`.
+    ///
+    /// The standard deviation is `2**stddev_shift`.
+    /// `stddev_shift` must be lower or equal to 15.
+    /// The result is saturated to the `i16` range.
+    ///
+    /// This only uses integer arithmetic.
+    /// It sums 12 uniform draws of `stddev_shift` bits each (central limit theorem).
+    /// Therefore, `12 * stddev_shift` bits are extracted from SHR3.
+    ///
+    /// Approximation error:
+    /// The maximum absolute error of the cumulative distribution function
+    /// compared to the exact normal distribution is approximately `0.0024`.
+    /// No values beyond `mean ± 6 * 2**stddev_shift` are generated.
+    pub fn get_normal_i16(&mut self, mean: i16, stddev_shift: u8) -> i16 {
+        debug_assert!(stddev_shift <= 15);
+        let mut sum: i32 = 0;
+        for _ in 0..12 {
+            let value: u16 = self.get_bits(stddev_shift);
+            sum += value as i32;
+        }
+        // Subtract the mean of the sum: 12 * (2**stddev_shift - 1) / 2
+        let offset = 6 * ((1_i32 << stddev_shift) - 1);
+        (mean as i32 + sum - offset).clamp(i16::MIN as i32, i16::MAX as i32) as i16
+    }
+
+    /// Get a uniformly distributed random `f32` in the range `[0, 1)`.
+    ///
+    /// 24 bits are extracted from SHR3 (24 SHR3 rounds)
+    /// and used as the full mantissa of the result.
+    /// Therefore, the result is a multiple of `2**-24`.
+    ///
+    /// This does not depend on any floating point library function.
+    #[inline]
+    pub fn get_f32(&mut self) -> f32 {
+        const BITS: u32 = f32::MANTISSA_DIGITS;
+        let value: u32 = self.get_bits(BITS as u8);
+        value as f32 * (1.0 / (1_u32 << BITS) as f32)
+    }
+
+    /// Get a uniformly distributed random `f64` in the range `[0, 1)`.
+    ///
+    /// 53 bits are extracted from SHR3 (53 SHR3 rounds)
+    /// and used as the full mantissa of the result.
+    /// Therefore, the result is a multiple of `2**-53`.
+    ///
+    /// This does not depend on any floating point library function.
+    #[inline]
+    pub fn get_f64(&mut self) -> f64 {
+        const BITS: u32 = f64::MANTISSA_DIGITS;
+        let value: u64 = self.get_bits(BITS as u8);
+        value as f64 * (1.0 / (1_u64 << BITS) as f64)
+    }
+
+    /// Get a uniformly distributed random `f64` in the range `[0, 1]`.
+    ///
+    /// 53 bits are extracted from SHR3 (53 SHR3 rounds).
+    /// The extracted integer in the range `[0, 2**53 - 1]` is divided by `2**53 - 1`.
+    /// Therefore, both `0.0` and `1.0` are possible results.
+    ///
+    /// This does not depend on any floating point library function.
+    #[inline]
+    pub fn get_f64_inclusive(&mut self) -> f64 {
+        const BITS: u32 = f64::MANTISSA_DIGITS;
+        let value: u64 = self.get_bits(BITS as u8);
+        value as f64 / ((1_u64 << BITS) - 1) as f64
+    }
+
+    /// Get a uniformly distributed random fraction in the range `[0, 1)` in Q0.32 format.
+    ///
+    /// The returned integer `x` represents the fraction `x / 2**32`.
+    /// 32 bits are extracted from SHR3.
+    ///
+    /// Use `scale_q32()` to scale the fraction to an integer range.
+    #[inline]
+    pub fn get_fraction_q32(&mut self) -> u32 {
+        self.get()
+    }
+
+    /// Get a uniformly distributed random fraction in the range `[0, 1)` in Q0.16 format.
+    ///
+    /// The returned integer `x` represents the fraction `x / 2**16`.
+    /// 16 bits are extracted from SHR3.
+    ///
+    /// Use `scale_q16()` to scale the fraction to an integer range.
+    #[inline]
+    pub fn get_fraction_q16(&mut self) -> u16 {
+        self.get()
+    }
+}
+
+impl<const A: u32, const B: u32, const C: u32, const TAP: u32> Default for Xorshift<A, B, C, TAP> {
+    /// Create a new generator instance with default initial `state = 1`.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Internal trait for basic operations on the output type.
+pub trait BaseOps: Copy
+{
+    /// Unsigned companion of Self.
+    type U;
+
+    /// Number of bits in type `Self`.
+    const NUMBITS: u8;
+    /// Smallest possible value of type `Self`.
+    const MINVAL: Self;
+    /// Biggest possible value of type `Self`.
+    const MAXVAL: Self;
+
+    /// Convert an `u8` to `Self`.
+    fn from_u8(v: u8) -> Self;
+
+    /// Convert a wrapping unsigned to a bit identical Self.
+    fn from_unsigned(v: Wrapping<Self::U>) -> Self;
+    /// Convert self to a bit identical wrapping unsigned.
+    fn to_unsigned(&self) -> Wrapping<Self::U>;
+
+    /// Find last bit set in `self`.
+    ///
+    /// Bit 0 -> returns 1; Bit 1 -> returns 2; ...
+    ///
+    /// If no bit set -> returns 0.
+    fn fls(&self) -> u8;
+}
+
+macro_rules! impl_base_ops {
+    // $u = unsigned type.
+    // $s = companion signed type.
+    ($( ($u:ty, $s:ty) ),*) => {
+        $(
+            impl BaseOps for $u {
+                type U = $u;
+                const NUMBITS: u8 = <$u>::BITS as u8;
+                const MINVAL: $u = <$u>::MIN;
+                const MAXVAL: $u = <$u>::MAX;
+                #[inline]
+                fn from_u8(v: u8) -> $u {
+                    v as $u
+                }
+                #[inline]
+                fn from_unsigned(v: Wrapping<Self::U>) -> Self {
+                    v.0
+                }
+                #[inline]
+                fn to_unsigned(&self) -> Wrapping<Self::U> {
+                    Wrapping(*self)
+                }
+                #[inline]
+                fn fls(&self) -> u8 {
+                    (<$u>::BITS - self.leading_zeros()) as u8
+                }
+            }
+            impl BaseOps for $s {
+                type U = $u;
+                const NUMBITS: u8 = <$s>::BITS as u8;
+                const MINVAL: $s = <$s>::MIN;
+                const MAXVAL: $s = <$s>::MAX;
+                #[inline]
+                fn from_u8(v: u8) -> $s {
+                    v as $s
+                }
+                #[inline]
+                fn from_unsigned(v: Wrapping<Self::U>) -> Self {
+                    v.0 as Self
+                }
+                #[inline]
+                fn to_unsigned(&self) -> Wrapping<Self::U> {
+                    Wrapping(*self as Self::U)
+                }
+                #[inline]
+                fn fls(&self) -> u8 {
+                    (<$s>::BITS - self.leading_zeros()) as u8
+                }
+            }
+        )*
+    }
+}
+
+impl_base_ops!((u8, i8), (u16, i16), (u32, i32), (u64, i64), (usize, isize));
+#[cfg(has_u128)]
+impl_base_ops!((u128, i128));
+
+macro_rules! impl_base_ops_nonzero {
+    // $nzu = unsigned non-zero type.
+    // $nzs = companion signed non-zero type.
+    // $u = unsigned integer type.
+    // $s = signed integer type.
+    ($( ($nzu:ty, $nzs:ty, $u:ty, $s:ty) ),*) => {
+        $(
+            impl BaseOps for $nzu {
+                type U = $u;
+                const NUMBITS: u8 = <$u>::BITS as u8;
+                const MINVAL: $nzu = <$nzu>::MIN;
+                const MAXVAL: $nzu = <$nzu>::MAX;
+                #[inline]
+                fn from_u8(v: u8) -> $nzu {
+                    Self::from_unsigned(Wrapping(v as $u))
+                }
+                #[inline]
+                fn from_unsigned(v: Wrapping<Self::U>) -> Self {
+                    <$nzu>::new(v.0.wrapping_add(1)).unwrap_or(<$nzu>::MAX)
+                }
+                #[inline]
+                fn to_unsigned(&self) -> Wrapping<Self::U> {
+                    Wrapping(self.get() - 1)
+                }
+                #[inline]
+                fn fls(&self) -> u8 {
+                    self.to_unsigned().0.fls()
+                }
+            }
+            impl BaseOps for $nzs {
+                type U = $u;
+                const NUMBITS: u8 = <$s>::BITS as u8;
+                const MINVAL: $nzs = <$nzs>::MIN;
+                const MAXVAL: $nzs = <$nzs>::MAX;
+                #[inline]
+                fn from_u8(v: u8) -> $nzs {
+                    Self::from_unsigned(Wrapping(v as $u))
+                }
+                #[inline]
+                fn from_unsigned(v: Wrapping<Self::U>) -> Self {
+                    let v = v.0 as $s;
+                    let v = if v >= 0 { v.saturating_add(1) } else { v };
+                    <$nzs>::new(v).unwrap_or(<$nzs>::MAX)
+                }
+                #[inline]
+                fn to_unsigned(&self) -> Wrapping<Self::U> {
+                    let v = self.get();
+                    Wrapping((if v > 0 { v - 1 } else { v }) as $u)
+                }
+                #[inline]
+                fn fls(&self) -> u8 {
+                    self.to_unsigned().0.fls()
+                }
+            }
+        )*
+    }
+}
+
+// The non-zero types are mapped to a contiguous unsigned index with the zero removed.
+// For the unsigned types that is `value - 1`.
+// Indices that don't correspond to a non-zero value are saturated to `MAXVAL`.
+impl_base_ops_nonzero!(
+    (NonZeroU8, NonZeroI8, u8, i8),
+    (NonZeroU16, NonZeroI16, u16, i16),
+    (NonZeroU32, NonZeroI32, u32, i32),
+    (NonZeroU64, NonZeroI64, u64, i64),
+    (NonZeroUsize, NonZeroIsize, usize, isize)
+);
+#[cfg(has_u128)]
+impl_base_ops_nonzero!((NonZeroU128, NonZeroI128, u128, i128));
+
+/// `Wrapping<T>` behaves exactly like its inner type `T`.
+impl<T: BaseOps> BaseOps for Wrapping<T> {
+    type U = T::U;
+    const NUMBITS: u8 = T::NUMBITS;
+    const MINVAL: Self = Wrapping(T::MINVAL);
+    const MAXVAL: Self = Wrapping(T::MAXVAL);
+    #[inline]
+    fn from_u8(v: u8) -> Self {
+        Wrapping(T::from_u8(v))
+    }
+    #[inline]
+    fn from_unsigned(v: Wrapping<Self::U>) -> Self {
+        Wrapping(T::from_unsigned(v))
+    }
+    #[inline]
+    fn to_unsigned(&self) -> Wrapping<Self::U> {
+        self.0.to_unsigned()
+    }
+    #[inline]
+    fn fls(&self) -> u8 {
+        self.0.fls()
+    }
+}
+
+/// First surrogate code point.
+const SURROGATE_FIRST: u32 = 0xD800;
+/// Number of surrogate code points.
+const SURROGATE_COUNT: u32 = 0x800;
+
+/// `char` is mapped to a contiguous unsigned index of all Unicode scalar values.
+///
+/// The surrogate code points `0xD800..=0xDFFF` are skipped in the index.
+/// Therefore, extracted `char` values never are surrogates.
+///
+/// *Note*: `get_bits()` with a `bitcount` of 21 can produce an index beyond `char::MAX`.
+///        Such an index is saturated to `char::MAX`.
+///        `get()`, `get_max()`, `get_minmax()` and `get_range()` always
+///        produce an even distribution.
+impl BaseOps for char {
+    type U = u32;
+    const NUMBITS: u8 = 21;
+    const MINVAL: char = '\0';
+    const MAXVAL: char = char::MAX;
+    #[inline]
+    fn from_u8(v: u8) -> char {
+        v as char
+    }
+    #[inline]
+    fn from_unsigned(v: Wrapping<Self::U>) -> Self {
+        let v = if v.0 >= SURROGATE_FIRST { v.0.saturating_add(SURROGATE_COUNT) } else { v.0 };
+        char::from_u32(v).unwrap_or(char::MAX)
+    }
+    #[inline]
+    fn to_unsigned(&self) -> Wrapping<Self::U> {
+        let v = *self as u32;
+        Wrapping(if v >= SURROGATE_FIRST { v - SURROGATE_COUNT } else { v })
+    }
+    #[inline]
+    fn fls(&self) -> u8 {
+        self.to_unsigned().0.fls()
+    }
+}
+
+/// Main operations for extracting bits from SHR3 generator.
+///
+/// The type `T` can be either of
+/// `u8`, `i8`, `u16`, `i16`, `u32`, `i32`, `u64`, `i64`, `u128`, `i128`, `usize`, `isize` or `char`
+/// or any of the corresponding `NonZero` integer types (e.g. `NonZeroU16`)
+/// or any of these types wrapped in `Wrapping` (e.g. `Wrapping<u8>`).
+///
+/// The `NonZero` types never return zero.
+/// `get()`, `get_max()`, `get_minmax()` and `get_range()` reject the extracted
+/// bit pattern that would correspond to zero and extract new bits instead.
+/// `get_bits()` returns the extracted bits plus one, saturated to the maximum value.
+///
+/// With the `fixed` feature enabled, `T` can also be any of the fixed point types
+/// of the `fixed` crate.
+pub trait Shr3Ops<T>:
+    where T: BaseOps + PartialOrd,
+          T::U: BaseOps,
+          Wrapping<T::U>: Sub<Output=Wrapping<T::U>> + Add<Output=Wrapping<T::U>> + PartialOrd,
+{
+    /// Get a number of `bitcount` bits from SHR3 and store them in the lower
+    /// bits of the returned type `T`.
+    ///
+    /// `bitcount` must be lower or equal to the number of bits in `T`.
+    fn get_bits(&mut self, bitcount: u8) -> T;
+
+    /// Get a number of `bitcount` bits from SHR3 and store them in the lower
+    /// bits of the returned unsigned companion type of `T`.
+    ///
+    /// In contrast to `get_bits()` the bits are not converted to `T`.
+    /// This matters for types where not all bit patterns are valid values of `T` (e.g. `char`).
+    ///
+    /// `bitcount` must be lower or equal to the number of bits in `T`.
+    #[doc(hidden)]
+    #[inline]
+    fn get_bits_unsigned(&mut self, bitcount: u8) -> Wrapping<T::U> {
+        self.get_bits(bitcount).to_unsigned()
+    }
+
+    /// Get as many bits from SHR3 as fit into the return type `T`.
+    ///
+    /// *Note*: Consider using `get_bits()` instead, if you don't need all returned bits.
+    ///
+    /// *Note*: If not all bit patterns are valid values of `T` (e.g. `char`),
+    ///        then this extracts an evenly distributed value like `get_range(..)`.
+    #[inline]
+    fn get(&mut self) -> T {
+        let range = T::MAXVAL.to_unsigned() - T::MINVAL.to_unsigned();
+        if range + Wrapping(T::U::from_u8(1)) == Wrapping(T::U::from_u8(0)) {
+            // All bit patterns are valid values of T.
+            self.get_bits(T::NUMBITS)
+        } else {
+            self.get_minmax(T::MINVAL, T::MAXVAL)
+        }
+    }
+
+    /// Fill all elements of the slice `buf` with random values.
+    ///
+    /// Each element is extracted like `get()` in ascending order.
+    ///
+    /// This is the recommended way to generate bulk random data.
+    fn fill_slice(&mut self, buf: &mut [T]) {
+        for v in buf {
+            *v = self.get();
+        }
+    }
+
+    /// Get enough bits to construct a random value in the range between `min_value` and `max_value`.
+    ///
+    /// *Note*: If the extracted range is of non-power-of-two size,
+    ///        then the number of bits extracted from the SHR3 generator will
+    ///        be bigger to ensure an even distribution of the returned values.
+    ///        Values outside of the range are rejected and the draw is repeated.
+    ///        For `u32` see `Shr3::get_minmax_lemire()` for a method with fewer repeated draws.
+    ///
+    /// See `dist::Uniform` for repeated draws from the same range.
+    fn get_minmax(&mut self, min_value: T, max_value: T) -> T {
+        debug_assert!(max_value >= min_value);
+        let range = max_value.to_unsigned() - min_value.to_unsigned();
+        let num_bits = range.0.fls();
+        let value = loop {
+            let value = self.get_bits_unsigned(num_bits);
+            if value <= range {
+                break value;
+            }
+        };
+        T::from_unsigned(value + min_value.to_unsigned())
+    }
+
+    /// Try to get a random value in the range between `min_value` and `max_value`
+    /// with a bounded number of draws.
+    ///
+    /// This is the same as `get_minmax()`, but the rejection loop runs at most
+    /// `max_attempts` times. If all attempts are rejected, then `None` is returned.
+    /// Therefore, the worst-case execution time is bounded by
+    /// `max_attempts` extractions of the number of bits of the range.
+    ///
+    /// The probability of a rejection per attempt is below 50%.
+    /// Therefore, the probability of `None` is below `2**-max_attempts`.
+    ///
+    /// A successful result is identical to the result of `get_minmax()`
+    /// with the same generator state.
+    fn try_get_minmax(&mut self, min_value: T, max_value: T, max_attempts: u32) -> Option<T> {
+        debug_assert!(max_value >= min_value);
+        let range = max_value.to_unsigned() - min_value.to_unsigned();
+        let num_bits = range.0.fls();
+        for _ in 0..max_attempts {
+            let value = self.get_bits_unsigned(num_bits);
+            if value <= range {
+                return Some(T::from_unsigned(value + min_value.to_unsigned()));
+            }
+        }
+        None
+    }
+
+    /// Get enough bits to construct a random value in the range between `0` and `max_value`.
+    ///
+    /// *Note*: If the extracted range is of non-power-of-two size,
+    ///        then the number of bits extracted from the SHR3 generator will
+    ///        be bigger to ensure an even distribution of the returned values.
+    #[inline]
+    fn get_max(&mut self, max_value: T) -> T {
+        self.get_minmax(T::MINVAL, max_value)
+    }
+
+    /// Get enough bits to construct a random value in the given `range`.
+    ///
+    /// *Note*: If the extracted range is of non-power-of-two size,
+    ///        then the number of bits extracted from the SHR3 generator will
+    ///        be bigger to ensure an even distribution of the returned values.
+    fn get_range(&mut self, range: impl RangeBounds<T>) -> T {
+        let (min, max) = range_to_minmax(&range);
+        self.get_minmax(min, max)
+    }
+
+    /// Get a random value with a triangular distribution
+    /// in the range between `min_value` and `max_value`.
+    ///
+    /// The value is the sum of two evenly distributed values that each span half of the range.
+    /// The most probable value is in the middle of the range.
+    /// If the range size is even, then the two middle values are equally probable.
+    ///
+    /// This is the distribution of triangular probability density function (TPDF) dither noise.
+    fn get_triangular(&mut self, min_value: T, max_value: T) -> T
+        where Wrapping<T::U>: Shr<usize, Output=Wrapping<T::U>>,
+    {
+        debug_assert!(max_value >= min_value);
+        let min = min_value.to_unsigned();
+        let range = max_value.to_unsigned() - min;
+        let half_a = range >> 1;
+        let half_b = range - half_a;
+        let a = self.get_minmax(min_value, T::from_unsigned(min + half_a)).to_unsigned();
+        let b = self.get_minmax(min_value, T::from_unsigned(min + half_b)).to_unsigned() - min;
+        T::from_unsigned(a + b)
+    }
+
+    /// Get an array of `N` random values.
+    ///
+    /// Each element is extracted like `get()` in ascending order.
+    #[inline]
+    fn get_array<const N: usize>(&mut self) -> [T; N] {
+        core::array::from_fn(|_| self.get())
+    }
+
+    /// Get an array of `N` random values in the given `range`.
+    ///
+    /// Each element is extracted like `get_range()` in ascending order.
+    fn get_array_range<const N: usize>(&mut self, range: impl RangeBounds<T>) -> [T; N] {
+        let (min, max) = range_to_minmax(&range);
+        core::array::from_fn(|_| self.get_minmax(min, max))
+    }
+}
+
+/// Convert a range to the included minimum and maximum values.
+pub(crate) fn range_to_minmax<T>(range: &impl RangeBounds<T>) -> (T, T)
+    where T: BaseOps + PartialOrd,
+          Wrapping<T::U>: Sub<Output=Wrapping<T::U>>,
+{
+    let min = match range.start_bound() {
+        Bound::Included(x) => *x,
+        Bound::Excluded(_) | Bound::Unbounded => T::MINVAL,
+    };
+    let max = match range.end_bound() {
+        Bound::Included(x) => *x,
+        Bound::Excluded(x) => {
+            debug_assert!(*x > T::MINVAL);
+            T::from_unsigned(x.to_unsigned() - T::from_u8(1).to_unsigned()) // to included
+        },
+        Bound::Unbounded => T::MAXVAL,
+    };
+    (min, max)
+}
+
+/// Shr3Ops for all `Xorshift` generators, including `Shr3`.
+impl<T, const A: u32, const B: u32, const C: u32, const TAP: u32> Shr3Ops<T> for Xorshift<A, B, C, TAP>
+    where T: BaseOps + PartialOrd,
+          T::U: BaseOps,
+          Wrapping<T::U>: Sub<Output=Wrapping<T::U>> + Add<Output=Wrapping<T::U>> + PartialOrd + ShlAssign<usize> + BitOrAssign,
+{
+    #[inline]
+    fn get_bits(&mut self, bitcount: u8) -> T {
+        T::from_unsigned(Shr3Ops::<T>::get_bits_unsigned(self, bitcount))
+    }
+
+    fn get_bits_unsigned(&mut self, bitcount: u8) -> Wrapping<T::U> {
+        debug_assert!(bitcount <= T::NUMBITS);
+        let mut ret = Wrapping(T::U::from_u8(0));
+
+        #[cfg(all(any(all(target_arch="avr", not(feature="tiny-code")), armv6m, target_arch="riscv32", target_arch="xtensa"),
+                  not(feature="force-generic")))]
+        if A == 13 && B == 17 && C == 5 && TAP == 0 {
+            // Extract up to 8 bits at a time entirely in assembly.
+            let mut remaining = bitcount;
+            while remaining > 0 {
+                let count = remaining.min(8);
+                #[cfg(target_arch="avr")]
+                let (state, bits) = arch::avr::shr3_bits8(self.state, count);
+                #[cfg(armv6m)]
+                let (state, bits) = arch::armv6m::shr3_bits8(self.state, count);
+                #[cfg(target_arch="riscv32")]
+                let (state, bits) = arch::riscv32::shr3_bits8(self.state, count);
+                #[cfg(target_arch="xtensa")]
+                let (state, bits) = arch::xtensa::shr3_bits8(self.state, count);
+                self.state = state;
+                ret <<= count as usize;
+                ret |= Wrapping(T::U::from_u8(bits));
+                remaining -= count;
+            }
+            return ret;
+        }
+
+        for _ in 0..bitcount {
+            self.state = Self::round(self.state);
+            ret <<= 1;
+            ret |= Wrapping(T::U::from_u8(tap_bit::<TAP>(self.state)));
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alg() {
+        assert_eq!(shr3(0), 0);
+        assert_eq!(shr3(0xFFFF_FFFF), 0x0003E01F);
+        assert_eq!(shr3(0x5555_5555), 0x000EDFEA);
+        assert_eq!(shr3(0xAAAA_AAAA), 0x000D3FF5);
+        assert_eq!(shr3(0x4242_4242), 0x4B4AEFA7);
+        assert_eq!(shr3(0x3C95_A60C), 0x82D826E6);
+    }
+
+    #[test]
+    fn test_base_ops() {
         // unsigned
         assert_eq!(u8::NUMBITS, 8);
         assert_eq!(u16::NUMBITS, 16);
@@ -327,260 +1819,1019 @@ mod tests {
         assert_eq!(u64::NUMBITS, 64);
         assert_eq!(usize::NUMBITS, usize::BITS as u8);
         #[cfg(has_u128)]
-        assert_eq!(u128::NUMBITS, 128);
+        assert_eq!(u128::NUMBITS, 128);
+
+        // signed
+        assert_eq!(i8::NUMBITS, 8);
+        assert_eq!(i16::NUMBITS, 16);
+        assert_eq!(i32::NUMBITS, 32);
+        assert_eq!(i64::NUMBITS, 64);
+        assert_eq!(isize::NUMBITS, usize::BITS as u8);
+        #[cfg(has_u128)]
+        assert_eq!(i128::NUMBITS, 128);
+
+        // unsigned
+        assert_eq!(u8::MINVAL, 0);
+        assert_eq!(u16::MINVAL, 0);
+        assert_eq!(u32::MINVAL, 0);
+        assert_eq!(u64::MINVAL, 0);
+        assert_eq!(usize::MINVAL, 0);
+        #[cfg(has_u128)]
+        assert_eq!(u128::MINVAL, 0);
+
+        // signed
+        assert_eq!(i8::MINVAL, i8::MIN);
+        assert_eq!(i16::MINVAL, i16::MIN);
+        assert_eq!(i32::MINVAL, i32::MIN);
+        assert_eq!(i64::MINVAL, i64::MIN);
+        assert_eq!(isize::MINVAL, isize::MIN);
+        #[cfg(has_u128)]
+        assert_eq!(i128::MINVAL, i128::MIN);
+
+        // unsigned
+        assert_eq!(u8::MAXVAL, u8::MAX);
+        assert_eq!(u16::MAXVAL, u16::MAX);
+        assert_eq!(u32::MAXVAL, u32::MAX);
+        assert_eq!(u64::MAXVAL, u64::MAX);
+        assert_eq!(usize::MAXVAL, usize::MAX);
+        #[cfg(has_u128)]
+        assert_eq!(u128::MAXVAL, u128::MAX);
+
+        // signed
+        assert_eq!(i8::MAXVAL, i8::MAX);
+        assert_eq!(i16::MAXVAL, i16::MAX);
+        assert_eq!(i32::MAXVAL, i32::MAX);
+        assert_eq!(i64::MAXVAL, i64::MAX);
+        assert_eq!(isize::MAXVAL, isize::MAX);
+        #[cfg(has_u128)]
+        assert_eq!(i128::MAXVAL, i128::MAX);
+
+        // unsigned
+        assert_eq!(u8::from_u8(42), 42);
+        assert_eq!(u16::from_u8(42), 42);
+        assert_eq!(u32::from_u8(42), 42);
+        assert_eq!(u64::from_u8(42), 42);
+        assert_eq!(usize::from_u8(42), 42);
+        #[cfg(has_u128)]
+        assert_eq!(u128::from_u8(42), 42);
+
+        // signed
+        assert_eq!(i8::from_u8(42), 42);
+        assert_eq!(i16::from_u8(42), 42);
+        assert_eq!(i32::from_u8(42), 42);
+        assert_eq!(i64::from_u8(42), 42);
+        assert_eq!(isize::from_u8(42), 42);
+        #[cfg(has_u128)]
+        assert_eq!(i128::from_u8(42), 42);
+
+        // signed
+        assert_eq!(i8::from_u8(0xFF), -1);
+        assert_eq!(i16::from_u8(0xFF), 0xFF);
+        assert_eq!(i32::from_u8(0xFF), 0xFF);
+        assert_eq!(i64::from_u8(0xFF), 0xFF);
+        assert_eq!(isize::from_u8(0xFF), 0xFF);
+        #[cfg(has_u128)]
+        assert_eq!(i128::from_u8(0xFF), 0xFF);
+
+        // from_unsigned (unsigned)
+        assert_eq!(u8::from_unsigned(Wrapping(0xF0_u8)), 0xF0);
+        assert_eq!(u16::from_unsigned(Wrapping(0xF0_u16)), 0xF0);
+        assert_eq!(u32::from_unsigned(Wrapping(0xF0_u32)), 0xF0);
+        assert_eq!(u64::from_unsigned(Wrapping(0xF0_u64)), 0xF0);
+        assert_eq!(usize::from_unsigned(Wrapping(0xF0_usize)), 0xF0);
+        #[cfg(has_u128)]
+        assert_eq!(u128::from_unsigned(Wrapping(0xF0_u128)), 0xF0);
+
+        // from_unsigned (signed)
+        assert_eq!(i8::from_unsigned(Wrapping(0xF0_u8)), -16);
+        assert_eq!(i16::from_unsigned(Wrapping(0xFFF0_u16)), -16);
+        assert_eq!(i32::from_unsigned(Wrapping(0xFFFF_FFF0_u32)), -16);
+        assert_eq!(i64::from_unsigned(Wrapping(0xFFFF_FFFF_FFFF_FFF0_u64)), -16);
+        assert_eq!(isize::from_unsigned(Wrapping((-16_isize) as usize)), -16);
+        #[cfg(has_u128)]
+        assert_eq!(i128::from_unsigned(Wrapping((-16_i128) as u128)), -16);
+
+        // to_unsigned (unsigned)
+        assert_eq!(0xF0_u8.to_unsigned(), Wrapping(0xF0_u8));
+        assert_eq!(0xF0_u16.to_unsigned(), Wrapping(0xF0_u16));
+        assert_eq!(0xF0_u32.to_unsigned(), Wrapping(0xF0_u32));
+        assert_eq!(0xF0_u64.to_unsigned(), Wrapping(0xF0_u64));
+        assert_eq!(0xF0_usize.to_unsigned(), Wrapping(0xF0_usize));
+        #[cfg(has_u128)]
+        assert_eq!(0xF0_u128.to_unsigned(), Wrapping(0xF0_u128));
+
+        // to_unsigned (signed)
+        assert_eq!((-16_i8).to_unsigned(), Wrapping(0xF0_u8));
+        assert_eq!((-16_i16).to_unsigned(), Wrapping(0xFFF0_u16));
+        assert_eq!((-16_i32).to_unsigned(), Wrapping(0xFFFF_FFF0_u32));
+        assert_eq!((-16_i64).to_unsigned(), Wrapping(0xFFFF_FFFF_FFFF_FFF0_u64));
+        assert_eq!((-16_isize).to_unsigned(), Wrapping((-16_isize) as usize));
+        #[cfg(has_u128)]
+        assert_eq!((-16_i128).to_unsigned(), Wrapping((-16_i128) as u128));
+
+        // unsigned fls
+        assert_eq!(0x00_u8.fls(), 0);
+        assert_eq!(0x80_u8.fls(), 8);
+        assert_eq!(0x4F_u8.fls(), 7);
+        assert_eq!(0x02_u8.fls(), 2);
+        assert_eq!(0x01_u8.fls(), 1);
+        assert_eq!(0x4000_u16.fls(), 15);
+        assert_eq!(0x4000_0000_u32.fls(), 31);
+        assert_eq!(0x4000_0000_0000_0000_u64.fls(), 63);
+        #[cfg(has_u128)]
+        assert_eq!(0x4000_0000_0000_0000_0000_0000_0000_0000_u128.fls(), 127);
+
+        // signed fls
+        assert_eq!(0x00_i8.fls(), 0);
+        assert_eq!((-127_i8).fls(), 8);
+        assert_eq!(0x4F_i8.fls(), 7);
+        assert_eq!(0x02_i8.fls(), 2);
+        assert_eq!(0x01_i8.fls(), 1);
+        assert_eq!(0x4000_i16.fls(), 15);
+        assert_eq!(0x4000_0000_i32.fls(), 31);
+        assert_eq!(0x4000_0000_0000_0000_i64.fls(), 63);
+        #[cfg(has_u128)]
+        assert_eq!(0x4000_0000_0000_0000_0000_0000_0000_0000_i128.fls(), 127);
+    }
+
+    #[test]
+    fn test_new() {
+        let a: Shr3 = Default::default();
+        assert_eq!(a.state, 1);
+        assert_eq!(Shr3::new().state, 1);
+        assert_eq!(Shr3::new_state(0).state, 0x7FFF_FFFF);
+        assert_eq!(Shr3::new_state(1).state, 1);
+        assert_eq!(Shr3::new_state(42).state, 42);
+        assert_eq!(Shr3::new_state(0x7FFF_FFFF).state, 0x7FFF_FFFF);
+        assert_eq!(Shr3::new_state(0xFFFF_FFFF).state, 0xFFFF_FFFF);
+    }
+
+    #[test]
+    fn test_types() {
+        // unsigned
+        {
+            let mut a = Shr3::new_state(42);
+            let b: u8 = a.get_bits(5);
+            assert_eq!(b, 4);
+        }
+        {
+            let mut a = Shr3::new_state(42);
+            let b: u16 = a.get_bits(5);
+            assert_eq!(b, 4);
+        }
+        {
+            let mut a = Shr3::new_state(42);
+            let b: u32 = a.get_bits(5);
+            assert_eq!(b, 4);
+        }
+        {
+            let mut a = Shr3::new_state(42);
+            let b: u64 = a.get_bits(5);
+            assert_eq!(b, 4);
+        }
+        {
+            let mut a = Shr3::new_state(42);
+            let b: usize = a.get_bits(5);
+            assert_eq!(b, 4);
+        }
+        #[cfg(has_u128)]
+        {
+            let mut a = Shr3::new_state(42);
+            let b: u128 = a.get_bits(5);
+            assert_eq!(b, 4);
+        }
 
         // signed
-        assert_eq!(i8::NUMBITS, 8);
-        assert_eq!(i16::NUMBITS, 16);
-        assert_eq!(i32::NUMBITS, 32);
-        assert_eq!(i64::NUMBITS, 64);
-        assert_eq!(isize::NUMBITS, usize::BITS as u8);
+        {
+            let mut a = Shr3::new_state(42);
+            let b: i8 = a.get_bits(5);
+            assert_eq!(b, 4);
+        }
+        {
+            let mut a = Shr3::new_state(42);
+            let b: i16 = a.get_bits(5);
+            assert_eq!(b, 4);
+        }
+        {
+            let mut a = Shr3::new_state(42);
+            let b: i32 = a.get_bits(5);
+            assert_eq!(b, 4);
+        }
+        {
+            let mut a = Shr3::new_state(42);
+            let b: i64 = a.get_bits(5);
+            assert_eq!(b, 4);
+        }
+        {
+            let mut a = Shr3::new_state(42);
+            let b: isize = a.get_bits(5);
+            assert_eq!(b, 4);
+        }
         #[cfg(has_u128)]
-        assert_eq!(i128::NUMBITS, 128);
+        {
+            let mut a = Shr3::new_state(42);
+            let b: i128 = a.get_bits(5);
+            assert_eq!(b, 4);
+        }
+    }
 
+    #[test]
+    fn test_get_bits() {
         // unsigned
-        assert_eq!(u8::MINVAL, 0);
-        assert_eq!(u16::MINVAL, 0);
-        assert_eq!(u32::MINVAL, 0);
-        assert_eq!(u64::MINVAL, 0);
-        assert_eq!(usize::MINVAL, 0);
-        #[cfg(has_u128)]
-        assert_eq!(u128::MINVAL, 0);
+        let mut a = Shr3::new_state(42);
+        for exp in [0x0001, 0x0000, 0x0001, 0x0005, 0x0001, 0x0004] {
+            let b: u16 = a.get_bits(3);
+            assert_eq!(b, exp);
+        }
+        let b: u16 = a.get_bits(0);
+        assert_eq!(b, 0);
 
         // signed
-        assert_eq!(i8::MINVAL, i8::MIN);
-        assert_eq!(i16::MINVAL, i16::MIN);
-        assert_eq!(i32::MINVAL, i32::MIN);
-        assert_eq!(i64::MINVAL, i64::MIN);
-        assert_eq!(isize::MINVAL, isize::MIN);
-        #[cfg(has_u128)]
-        assert_eq!(i128::MINVAL, i128::MIN);
+        let mut a = Shr3::new_state(42);
+        for exp in [0x0001, 0x0000, 0x0001, 0x0005, 0x0001, 0x0004] {
+            let b: i16 = a.get_bits(3);
+            assert_eq!(b, exp);
+        }
+        let b: i16 = a.get_bits(0);
+        assert_eq!(b, 0);
+    }
 
+    #[test]
+    fn test_get() {
         // unsigned
-        assert_eq!(u8::MAXVAL, u8::MAX);
-        assert_eq!(u16::MAXVAL, u16::MAX);
-        assert_eq!(u32::MAXVAL, u32::MAX);
-        assert_eq!(u64::MAXVAL, u64::MAX);
-        assert_eq!(usize::MAXVAL, usize::MAX);
-        #[cfg(has_u128)]
-        assert_eq!(u128::MAXVAL, u128::MAX);
+        let mut a = Shr3::new_state(42);
+        for exp in [0x20D3, 0x2C5C, 0x2A17, 0xD3C5, 0xAF08, 0x9E5B] {
+            let b: u16 = a.get();
+            assert_eq!(b, exp);
+        }
 
         // signed
-        assert_eq!(i8::MAXVAL, i8::MAX);
-        assert_eq!(i16::MAXVAL, i16::MAX);
-        assert_eq!(i32::MAXVAL, i32::MAX);
-        assert_eq!(i64::MAXVAL, i64::MAX);
-        assert_eq!(isize::MAXVAL, isize::MAX);
+        let mut a = Shr3::new_state(42);
+        for exp in [0x20D3, 0x2C5C, 0x2A17, 0xD3C5, 0xAF08, 0x9E5B] {
+            let b: i16 = a.get();
+            assert_eq!(b as u16, exp as u16);
+        }
+    }
+
+    #[test]
+    fn test_state() {
+        let mut a = Shr3::new_state(42);
+        assert_eq!(a.state(), 42);
+        assert_eq!(Shr3::new().state(), 1);
+        assert_eq!(Shr3::new_state(0).state(), 0x7FFFFFFF);
+
+        let _: u32 = a.get();
+        let state = a.state();
+        let mut b = Shr3::new();
+        b.set_state(state);
+        for _ in 0..100 {
+            assert_eq!(Shr3Ops::<u32>::get(&mut a), Shr3Ops::<u32>::get(&mut b));
+        }
+        b.set_state(0);
+        assert_eq!(b.state(), 0x7FFFFFFF);
+    }
+
+    #[test]
+    fn test_new_state_warmed() {
+        let a = Shr3::new_state_warmed(42, 0);
+        assert_eq!(a.state(), 42);
+        let mut a = Shr3::new_state_warmed(1, 32);
+        let mut b = Shr3::new_state(1);
+        let _: u32 = b.get();
+        assert_eq!(a.state(), b.state());
+        assert_eq!(Shr3Ops::<u16>::get(&mut a), Shr3Ops::<u16>::get(&mut b));
+        let mut c = Shr3::new_state(0);
+        c.discard(1000);
+        assert_eq!(Shr3::new_state_warmed(0, 1000).state(), c.state());
+    }
+
+    #[test]
+    fn test_absorb() {
+        let mut a = Shr3::new_state(42);
+        a.absorb(&[]);
+        assert_eq!(a.state(), 42);
+        a.absorb(&[1]);
+        assert_eq!(a.state(), shr3(43));
+
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        a.absorb(&[1, 2, 3, 4, 5]);
+        b.absorb(&[1, 2, 3, 4, 6]);
+        assert_ne!(a.state(), b.state());
+
+        // A zero state is fixed up.
+        let mut a = Shr3::new_state(42);
+        a.absorb(&[42]);
+        assert_eq!(a.state(), 0x7FFFFFFF);
+    }
+
+    #[test]
+    fn test_discard() {
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        for n in [0, 1, 7, 32, 100, 1000] {
+            a.discard(n);
+            for _ in 0..n {
+                let _: u8 = b.get_bits(1);
+            }
+            assert_eq!(a.state(), b.state());
+        }
+        a.discard(0xFFFF_FFFF);
+        assert_eq!(a.state(), b.state());
+        a.discard(0xFFFF_FFFF * 3 + 2);
+        let _: u8 = b.get_bits(2);
+        assert_eq!(a.state(), b.state());
+    }
+
+    #[test]
+    fn test_shr3_inv() {
+        let mut state = 42;
+        for _ in 0..10000 {
+            assert_eq!(shr3_inv(shr3(state)), state);
+            assert_eq!(shr3(shr3_inv(state)), state);
+            state = shr3(state);
+        }
+        assert_eq!(shr3_inv(0), 0);
+    }
+
+    #[test]
+    fn test_step_back() {
+        let mut a = Shr3::new_state(42);
+        let x: [u32; 4] = a.get_array();
+        a.step_back(64);
+        let y: [u32; 2] = a.get_array();
+        assert_eq!(y, x[2..]);
+        a.step_back(128);
+        assert_eq!(a.state(), 42);
+        a.step_back(1_000_000);
+        a.discard(1_000_000);
+        assert_eq!(a.state(), 42);
+
+        let x: i8 = a.get();
+        let y: u64 = a.get_bits(35);
+        let z: u32 = a.get_bits(21);
+        assert_eq!(a.prev_bits::<u32>(21), z);
+        assert_eq!(a.prev_bits::<u64>(35), y);
+        assert_eq!(a.prev_bits::<i8>(8), x);
+        assert_eq!(a.state(), 42);
+        assert_eq!(a.prev_bits::<u8>(0), 0);
+    }
+
+    #[test]
+    fn test_split() {
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        let mut c = a.split();
+        let mut d = b.split();
+        assert_eq!(a.state(), b.state());
+        assert_eq!(c.state(), d.state());
+        assert_ne!(a.state(), c.state());
+
+        let mut e = a.split();
+        let x: [u32; 8] = c.get_array();
+        let y: [u32; 8] = e.get_array();
+        let z: [u32; 8] = a.get_array();
+        assert_eq!(x, Shr3Ops::<u32>::get_array(&mut d));
+        assert!(x.iter().zip(y).all(|(x, y)| *x != y));
+        assert!(x.iter().zip(z).all(|(x, z)| *x != z));
+    }
+
+    #[test]
+    fn test_derive_child() {
+        let a = Shr3::new_state(42);
+        let b = Shr3::new_state(43);
+        assert_eq!(a.derive_child(1).state(), a.derive_child(1).state());
+        assert_ne!(a.derive_child(1).state(), a.derive_child(2).state());
+        assert_ne!(a.derive_child(1).state(), b.derive_child(1).state());
+        assert_ne!(a.derive_child(0).state(), a.state());
+        assert_eq!(a.state(), 42);
+
+        let mut x = a.derive_child(1);
+        let mut y = a.derive_child(2);
+        let x: [u32; 8] = x.get_array();
+        let y: [u32; 8] = y.get_array();
+        assert!(x.iter().zip(y).all(|(x, y)| *x != y));
+    }
+
+    #[test]
+    fn test_xorshift() {
+        let mut a = Xorshift::<1, 3, 10>::new_state(42);
+        let x: u16 = a.get();
+        assert_eq!(x, 0xF935);
+        let mut a = Xorshift::<1, 3, 10>::new_state(42);
+        let _: u8 = a.get_bits(1);
+        assert_eq!(a.state(), 0x0001_C471);
+
+        let mut a = Xorshift::<13, 17, 5>::new_state(42);
+        let mut b = Shr3::new_state(42);
+        for _ in 0..100 {
+            assert_eq!(Shr3Ops::<u32>::get(&mut a), Shr3Ops::<u32>::get(&mut b));
+        }
+
+        let a = Xorshift::<1, 3, 10>::new_state(0);
+        assert_eq!(a.state(), 0x7FFFFFFF);
+        let a: Xorshift<1, 3, 10> = Default::default();
+        assert_eq!(a.state(), 1);
+    }
+
+    #[test]
+    fn test_word32() {
+        let mut a = Shr3::new_state(42);
+        let mut state = 42;
+        for _ in 0..10 {
+            state = shr3(state);
+            assert_eq!(a.get_word32(), state);
+        }
+
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        let mut words = [0_u32; 3];
+        a.fill_words32(&mut words);
+        assert_eq!(words, [b.get_word32(), b.get_word32(), b.get_word32()]);
+        assert_eq!(a.state(), b.state());
+
+        let mut a = Shr3::new_state(42);
+        let mut bytes = [0_u8; 6];
+        a.fill_bytes_word32(&mut bytes);
+        assert_eq!(bytes[..4], shr3(42).to_be_bytes());
+        assert_eq!(bytes[4..], shr3(shr3(42)).to_be_bytes()[..2]);
+        assert_eq!(a.state(), shr3(shr3(42)));
+    }
+
+    #[test]
+    fn test_const() {
+        const A: Shr3 = Shr3::new_state_warmed(42, 32);
+        const X: (u32, u32) = {
+            let mut a = A;
+            let x = a.get_bits_const(32);
+            (x, a.get_bits_const(5))
+        };
+        let mut b = Shr3::new_state(42);
+        b.discard(32);
+        assert_eq!(A.state(), b.state());
+        assert_eq!(X.0, Shr3Ops::<u32>::get(&mut b));
+        assert_eq!(X.1, Shr3Ops::<u32>::get_bits(&mut b, 5));
+        const Y: u32 = shr3_const(42);
+        assert_eq!(Y, shr3(42));
+    }
+
+    #[cfg(feature="macros")]
+    #[test]
+    fn test_shr3_table() {
+        const A: [u8; 100] = shr3_table!(u8; 100; seed = 42);
+        const B: [i64; 10] = shr3_table!(i64; 10);
+        const C: [u32; 3] = shr3_table!(u32; 3; seed = 0);
+        let mut a = Shr3::new_state(42);
+        let mut buf = [0_u8; 100];
+        a.fill_slice(&mut buf);
+        assert_eq!(A, buf);
+        let mut b = Shr3::new();
+        let mut buf = [0_i64; 10];
+        b.fill_slice(&mut buf);
+        assert_eq!(B, buf);
+        let mut c = Shr3::new_state(0);
+        let mut buf = [0_u32; 3];
+        c.fill_slice(&mut buf);
+        assert_eq!(C, buf);
         #[cfg(has_u128)]
-        assert_eq!(i128::MAXVAL, i128::MAX);
+        {
+            const D: [u128; 4] = shr3_table!(u128; 4; seed = 7);
+            let mut d = Shr3::new_state(7);
+            let mut buf = [0_u128; 4];
+            d.fill_slice(&mut buf);
+            assert_eq!(D, buf);
+        }
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let mut a = Shr3::new_state(42);
+        let cp0 = a.checkpoint();
+        let x: [u16; 6] = a.get_array();
+        let cp1 = a.checkpoint();
+        assert_ne!(cp0, cp1);
+        let y: u64 = a.get();
+        a.restore(cp0);
+        assert_eq!(a.checkpoint(), cp0);
+        assert_eq!(x, [0x20D3, 0x2C5C, 0x2A17, 0xD3C5, 0xAF08, 0x9E5B]);
+        let z: [u16; 6] = a.get_array();
+        assert_eq!(x, z);
+        let cp2 = cp1;
+        a.restore(cp2);
+        assert_eq!(Shr3Ops::<u64>::get(&mut a), y);
+    }
+
+    #[test]
+    fn test_fill_bytes() {
+        for len in 0..10 {
+            let mut a = Shr3::new_state(42);
+            let mut buf = [0_u8; 10];
+            a.fill_bytes(&mut buf[..len]);
+
+            let mut b = Shr3::new_state(42);
+            for (i, x) in buf.iter().enumerate() {
+                let exp: u8 = if i < len { b.get() } else { 0 };
+                assert_eq!(*x, exp);
+            }
+            assert_eq!(a.state, b.state);
+        }
+    }
+
+    #[test]
+    fn test_fill_charset() {
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        let mut buf = [0_u8; 100];
+        a.fill_charset(&mut buf, b"xyz");
+        for c in buf {
+            let index: usize = b.get_max(2);
+            assert_eq!(c, b"xyz"[index]);
+        }
+
+        let mut count = [0_u32; 62];
+        let mut buf = [0_u8; 6200];
+        a.fill_ascii_alphanumeric(&mut buf);
+        for c in buf {
+            count[charset::ALPHANUMERIC.iter().position(|x| *x == c).unwrap()] += 1;
+        }
+        assert!(count.iter().all(|c| (70..130).contains(c)));
+
+        a.fill_ascii_hex(&mut buf);
+        assert!(buf.iter().all(|c| charset::HEX_LOWER.contains(c)));
+        a.fill_ascii_digits(&mut buf);
+        assert!(buf.iter().all(u8::is_ascii_digit));
+        a.fill_ascii_printable(&mut buf);
+        assert!(buf.iter().all(|c| (0x20..=0x7E).contains(c)));
+        a.fill_ascii_hex(&mut []);
+    }
+
+    #[test]
+    fn test_token() {
+        let mut a = Shr3::new_state(42);
+        let mut buf = [0; 8];
+        assert_eq!(a.fill_token_hex(&mut buf), "20d32c5c");
+        assert_eq!(a.fill_token_hex(&mut buf[..0]), "");
+
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        let mut buf = [0; 13];
+        let token = a.fill_token_base32(&mut buf);
+        assert_eq!(token.len(), 13);
+        for c in token.bytes() {
+            let index: usize = b.get_bits(5);
+            assert_eq!(c, charset::CROCKFORD_BASE32[index]);
+        }
+    }
 
+    #[test]
+    fn test_fill_slice() {
         // unsigned
-        assert_eq!(u8::from_u8(42), 42);
-        assert_eq!(u16::from_u8(42), 42);
-        assert_eq!(u32::from_u8(42), 42);
-        assert_eq!(u64::from_u8(42), 42);
-        assert_eq!(usize::from_u8(42), 42);
-        #[cfg(has_u128)]
-        assert_eq!(u128::from_u8(42), 42);
+        let mut a = Shr3::new_state(42);
+        let mut buf = [0_u16; 6];
+        a.fill_slice(&mut buf);
+        assert_eq!(buf, [0x20D3, 0x2C5C, 0x2A17, 0xD3C5, 0xAF08, 0x9E5B]);
 
         // signed
-        assert_eq!(i8::from_u8(42), 42);
-        assert_eq!(i16::from_u8(42), 42);
-        assert_eq!(i32::from_u8(42), 42);
-        assert_eq!(i64::from_u8(42), 42);
-        assert_eq!(isize::from_u8(42), 42);
-        #[cfg(has_u128)]
-        assert_eq!(i128::from_u8(42), 42);
+        let mut a = Shr3::new_state(42);
+        let mut buf = [0_i16; 6];
+        a.fill_slice(&mut buf);
+        for (b, exp) in buf.iter().zip([0x20D3, 0x2C5C, 0x2A17, 0xD3C5, 0xAF08, 0x9E5B]) {
+            assert_eq!(*b as u16, exp as u16);
+        }
+
+        // bytes
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        let mut buf_a = [0_u8; 7];
+        let mut buf_b = [0_u8; 7];
+        a.fill_slice(&mut buf_a);
+        b.fill_bytes(&mut buf_b);
+        assert_eq!(buf_a, buf_b);
+
+        let mut buf = [0_u32; 0];
+        a.fill_slice(&mut buf);
+    }
+
+    #[test]
+    fn test_f32() {
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        for _ in 0..1000 {
+            let x = a.get_f32();
+            assert!((0.0..1.0).contains(&x));
+            let exp: u32 = b.get_bits(24);
+            assert_eq!(x, exp as f32 / 16777216.0);
+        }
+        assert_eq!(a.state, b.state);
+    }
+
+    #[test]
+    fn test_f64() {
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        for _ in 0..1000 {
+            let x = a.get_f64();
+            assert!((0.0..1.0).contains(&x));
+            let exp: u64 = b.get_bits(53);
+            assert_eq!(x, exp as f64 / 9007199254740992.0);
+        }
+        assert_eq!(a.state, b.state);
+
+        for _ in 0..1000 {
+            let x = a.get_f64_inclusive();
+            assert!((0.0..=1.0).contains(&x));
+            let exp: u64 = b.get_bits(53);
+            assert_eq!(x, exp as f64 / 9007199254740991.0);
+        }
+        assert_eq!(a.state, b.state);
+    }
+
+    #[test]
+    fn test_fraction() {
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        for _ in 0..100 {
+            let exp: u32 = b.get();
+            assert_eq!(a.get_fraction_q32(), exp);
+            let exp: u16 = b.get();
+            assert_eq!(a.get_fraction_q16(), exp);
+        }
+        for _ in 0..1000 {
+            assert!(scale_q32(a.get_fraction_q32(), 1000) <= 1000);
+            assert!(scale_q16(a.get_fraction_q16(), 1000) <= 1000);
+        }
+    }
+
+    #[test]
+    fn test_char() {
+        assert_eq!('a'.to_unsigned(), Wrapping(0x61));
+        assert_eq!('\u{D7FF}'.to_unsigned(), Wrapping(0xD7FF));
+        assert_eq!('\u{E000}'.to_unsigned(), Wrapping(0xD800));
+        assert_eq!(char::MAX.to_unsigned(), Wrapping(0x10F7FF));
+        assert_eq!(char::from_unsigned(Wrapping(0x61)), 'a');
+        assert_eq!(char::from_unsigned(Wrapping(0xD7FF)), '\u{D7FF}');
+        assert_eq!(char::from_unsigned(Wrapping(0xD800)), '\u{E000}');
+        assert_eq!(char::from_unsigned(Wrapping(0x10F7FF)), char::MAX);
+        assert_eq!(char::from_unsigned(Wrapping(0x10F800)), char::MAX);
+        assert_eq!(char::from_unsigned(Wrapping(0x1FFFFF)), char::MAX);
+
+        let mut a = Shr3::new_state(42);
+        for _ in 0..1000 {
+            let c: char = a.get_range('a'..='z');
+            assert!(c.is_ascii_lowercase());
+        }
+        for _ in 0..1000 {
+            let c: char = a.get_range('0'..':');
+            assert!(c.is_ascii_digit());
+        }
+        for _ in 0..1000 {
+            let c: char = a.get_range('\u{D7FE}'..'\u{E002}');
+            assert!(matches!(c, '\u{D7FE}' | '\u{D7FF}' | '\u{E000}' | '\u{E001}'));
+        }
+        let mut seen_high = false;
+        for _ in 0..1000 {
+            let c: char = a.get();
+            assert!(!(0xD800..=0xDFFF).contains(&(c as u32)));
+            assert_ne!(c, char::MAX);
+            seen_high |= c as u32 > 0xFFFF;
+        }
+        assert!(seen_high);
+    }
+
+    #[test]
+    fn test_nonzero() {
+        // unsigned
+        assert_eq!(NonZeroU8::MIN.to_unsigned(), Wrapping(0));
+        assert_eq!(NonZeroU8::MAX.to_unsigned(), Wrapping(0xFE));
+        assert_eq!(NonZeroU8::from_unsigned(Wrapping(0)), NonZeroU8::MIN);
+        assert_eq!(NonZeroU8::from_unsigned(Wrapping(0xFE)), NonZeroU8::MAX);
+        assert_eq!(NonZeroU8::from_unsigned(Wrapping(0xFF)), NonZeroU8::MAX);
+        assert_eq!(NonZeroU8::from_u8(1).get(), 2);
 
         // signed
-        assert_eq!(i8::from_u8(0xFF), -1);
-        assert_eq!(i16::from_u8(0xFF), 0xFF);
-        assert_eq!(i32::from_u8(0xFF), 0xFF);
-        assert_eq!(i64::from_u8(0xFF), 0xFF);
-        assert_eq!(isize::from_u8(0xFF), 0xFF);
-        #[cfg(has_u128)]
-        assert_eq!(i128::from_u8(0xFF), 0xFF);
+        assert_eq!(NonZeroI8::MIN.to_unsigned(), Wrapping(0x80));
+        assert_eq!(NonZeroI8::new(-1).unwrap().to_unsigned(), Wrapping(0xFF));
+        assert_eq!(NonZeroI8::new(1).unwrap().to_unsigned(), Wrapping(0x00));
+        assert_eq!(NonZeroI8::MAX.to_unsigned(), Wrapping(0x7E));
+        assert_eq!(NonZeroI8::from_unsigned(Wrapping(0x80)), NonZeroI8::MIN);
+        assert_eq!(NonZeroI8::from_unsigned(Wrapping(0xFF)).get(), -1);
+        assert_eq!(NonZeroI8::from_unsigned(Wrapping(0x00)).get(), 1);
+        assert_eq!(NonZeroI8::from_unsigned(Wrapping(0x7E)), NonZeroI8::MAX);
+        assert_eq!(NonZeroI8::from_unsigned(Wrapping(0x7F)), NonZeroI8::MAX);
 
-        // from_unsigned (unsigned)
-        assert_eq!(u8::from_unsigned(Wrapping(0xF0_u8)), 0xF0);
-        assert_eq!(u16::from_unsigned(Wrapping(0xF0_u16)), 0xF0);
-        assert_eq!(u32::from_unsigned(Wrapping(0xF0_u32)), 0xF0);
-        assert_eq!(u64::from_unsigned(Wrapping(0xF0_u64)), 0xF0);
-        assert_eq!(usize::from_unsigned(Wrapping(0xF0_usize)), 0xF0);
-        #[cfg(has_u128)]
-        assert_eq!(u128::from_unsigned(Wrapping(0xF0_u128)), 0xF0);
+        let mut a = Shr3::new_state(42);
+        let mut count = [0_u32; 256];
+        for _ in 0..25500 {
+            let b: NonZeroU8 = a.get();
+            count[b.get() as usize] += 1;
+        }
+        assert_eq!(count[0], 0);
+        assert!(count[1..].iter().all(|x| (50..150).contains(x)));
 
-        // from_unsigned (signed)
-        assert_eq!(i8::from_unsigned(Wrapping(0xF0_u8)), -16);
-        assert_eq!(i16::from_unsigned(Wrapping(0xFFF0_u16)), -16);
-        assert_eq!(i32::from_unsigned(Wrapping(0xFFFF_FFF0_u32)), -16);
-        assert_eq!(i64::from_unsigned(Wrapping(0xFFFF_FFFF_FFFF_FFF0_u64)), -16);
-        assert_eq!(isize::from_unsigned(Wrapping((-16_isize) as usize)), -16);
-        #[cfg(has_u128)]
-        assert_eq!(i128::from_unsigned(Wrapping((-16_i128) as u128)), -16);
+        let mut count = [0_u32; 256];
+        for _ in 0..25500 {
+            let b: NonZeroI8 = a.get();
+            count[b.get() as u8 as usize] += 1;
+        }
+        assert_eq!(count[0], 0);
+        assert!(count[1..].iter().all(|x| (50..150).contains(x)));
 
-        // to_unsigned (unsigned)
-        assert_eq!(0xF0_u8.to_unsigned(), Wrapping(0xF0_u8));
-        assert_eq!(0xF0_u16.to_unsigned(), Wrapping(0xF0_u16));
-        assert_eq!(0xF0_u32.to_unsigned(), Wrapping(0xF0_u32));
-        assert_eq!(0xF0_u64.to_unsigned(), Wrapping(0xF0_u64));
-        assert_eq!(0xF0_usize.to_unsigned(), Wrapping(0xF0_usize));
-        #[cfg(has_u128)]
-        assert_eq!(0xF0_u128.to_unsigned(), Wrapping(0xF0_u128));
+        let min = NonZeroU16::new(5).unwrap();
+        let max = NonZeroU16::new(10).unwrap();
+        for _ in 0..1000 {
+            let b: NonZeroU16 = a.get_range(min..max);
+            assert!((min..max).contains(&b));
+        }
+        let min = NonZeroI32::new(-3).unwrap();
+        let max = NonZeroI32::new(3).unwrap();
+        for _ in 0..1000 {
+            let b: NonZeroI32 = a.get_minmax(min, max);
+            assert!((min..=max).contains(&b));
+        }
+        let b: NonZeroU32 = a.get_bits(3);
+        assert!((1..=8).contains(&b.get()));
+    }
 
-        // to_unsigned (signed)
-        assert_eq!((-16_i8).to_unsigned(), Wrapping(0xF0_u8));
-        assert_eq!((-16_i16).to_unsigned(), Wrapping(0xFFF0_u16));
-        assert_eq!((-16_i32).to_unsigned(), Wrapping(0xFFFF_FFF0_u32));
-        assert_eq!((-16_i64).to_unsigned(), Wrapping(0xFFFF_FFFF_FFFF_FFF0_u64));
-        assert_eq!((-16_isize).to_unsigned(), Wrapping((-16_isize) as usize));
-        #[cfg(has_u128)]
-        assert_eq!((-16_i128).to_unsigned(), Wrapping((-16_i128) as u128));
+    #[test]
+    fn test_get_array() {
+        let mut a = Shr3::new_state(42);
+        let b: [u16; 6] = a.get_array();
+        assert_eq!(b, [0x20D3, 0x2C5C, 0x2A17, 0xD3C5, 0xAF08, 0x9E5B]);
+        let b: [i8; 0] = a.get_array();
+        assert_eq!(b, [0_i8; 0]);
 
-        // unsigned fls
-        assert_eq!(0x00_u8.fls(), 0);
-        assert_eq!(0x80_u8.fls(), 8);
-        assert_eq!(0x4F_u8.fls(), 7);
-        assert_eq!(0x02_u8.fls(), 2);
-        assert_eq!(0x01_u8.fls(), 1);
-        assert_eq!(0x4000_u16.fls(), 15);
-        assert_eq!(0x4000_0000_u32.fls(), 31);
-        assert_eq!(0x4000_0000_0000_0000_u64.fls(), 63);
-        #[cfg(has_u128)]
-        assert_eq!(0x4000_0000_0000_0000_0000_0000_0000_0000_u128.fls(), 127);
+        for _ in 0..100 {
+            let b: [i32; 10] = a.get_array_range(-5..=5);
+            assert!(b.iter().all(|x| (-5..=5).contains(x)));
+            let b: [u8; 10] = a.get_array_range(..10);
+            assert!(b.iter().all(|x| *x < 10));
+        }
+    }
 
-        // signed fls
-        assert_eq!(0x00_i8.fls(), 0);
-        assert_eq!((-127_i8).fls(), 8);
-        assert_eq!(0x4F_i8.fls(), 7);
-        assert_eq!(0x02_i8.fls(), 2);
-        assert_eq!(0x01_i8.fls(), 1);
-        assert_eq!(0x4000_i16.fls(), 15);
-        assert_eq!(0x4000_0000_i32.fls(), 31);
-        assert_eq!(0x4000_0000_0000_0000_i64.fls(), 63);
-        #[cfg(has_u128)]
-        assert_eq!(0x4000_0000_0000_0000_0000_0000_0000_0000_i128.fls(), 127);
+    #[test]
+    fn test_wrapping() {
+        let mut a = Shr3::new_state(42);
+        for exp in [0x20D3, 0x2C5C, 0x2A17, 0xD3C5, 0xAF08, 0x9E5B] {
+            let b: Wrapping<u16> = a.get();
+            assert_eq!(b, Wrapping(exp));
+        }
+
+        let mut a = Shr3::new_state(42);
+        for exp in [0x20D3, 0x2C5C, 0x2A17, 0xD3C5, 0xAF08, 0x9E5B] {
+            let b: Wrapping<i16> = a.get();
+            assert_eq!(b, Wrapping(exp as u16 as i16));
+        }
+
+        let mut a = Shr3::new_state(42);
+        let b: Wrapping<u8> = a.get_bits(5);
+        assert_eq!(b, Wrapping(4));
+
+        for _ in 0..1000 {
+            let b: Wrapping<i32> = a.get_range(Wrapping(-60)..Wrapping(170));
+            assert!((-60..170).contains(&b.0));
+        }
+        for _ in 0..1000 {
+            let b: Wrapping<u64> = a.get_max(Wrapping(100));
+            assert!(b.0 <= 100);
+        }
     }
 
     #[test]
-    fn test_new() {
-        let a: Shr3 = Default::default();
-        assert_eq!(a.state, 1);
-        assert_eq!(Shr3::new().state, 1);
-        assert_eq!(Shr3::new_state(0).state, 0x7FFF_FFFF);
-        assert_eq!(Shr3::new_state(1).state, 1);
-        assert_eq!(Shr3::new_state(42).state, 42);
-        assert_eq!(Shr3::new_state(0x7FFF_FFFF).state, 0x7FFF_FFFF);
-        assert_eq!(Shr3::new_state(0xFFFF_FFFF).state, 0xFFFF_FFFF);
+    fn test_gen_ratio() {
+        let mut a = Shr3::new_state(42);
+        for _ in 0..1000 {
+            assert!(!a.gen_ratio(0, 7));
+            assert!(a.gen_ratio(7, 7));
+            assert!(a.gen_ratio(1, 1));
+        }
+        let count = (0..10000).filter(|_| a.gen_ratio(1, 4)).count();
+        assert!((2300..2700).contains(&count));
+        let count = (0..10000).filter(|_| a.gen_ratio(2, 3)).count();
+        assert!((6400..6900).contains(&count));
     }
 
     #[test]
-    fn test_types() {
-        // unsigned
-        {
-            let mut a = Shr3::new_state(42);
-            let b: u8 = a.get_bits(5);
-            assert_eq!(b, 4);
+    fn test_geometric() {
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        let d = dist::Geometric::from_ratio(1, 3);
+        for _ in 0..100 {
+            assert_eq!(a.get_geometric(1, 3), d.sample(&mut b));
         }
-        {
-            let mut a = Shr3::new_state(42);
-            let b: u16 = a.get_bits(5);
-            assert_eq!(b, 4);
+    }
+
+    #[test]
+    fn test_normal_i16() {
+        let mut a = Shr3::new_state(42);
+        for _ in 0..100 {
+            assert_eq!(a.get_normal_i16(-42, 0), -42);
         }
-        {
-            let mut a = Shr3::new_state(42);
-            let b: u32 = a.get_bits(5);
-            assert_eq!(b, 4);
+
+        const COUNT: i64 = 10000;
+        let mut sum = 0_i64;
+        let mut sum_sq = 0_i64;
+        for _ in 0..COUNT {
+            let x = a.get_normal_i16(1000, 6) as i64;
+            assert!((1000 - 6 * 64..=1000 + 6 * 64).contains(&x));
+            sum += x;
+            sum_sq += x * x;
         }
-        {
-            let mut a = Shr3::new_state(42);
-            let b: u64 = a.get_bits(5);
-            assert_eq!(b, 4);
+        let mean = sum as f64 / COUNT as f64;
+        let var = sum_sq as f64 / COUNT as f64 - mean * mean;
+        assert!((995.0..=1005.0).contains(&mean));
+        assert!((3800.0..=4400.0).contains(&var));
+
+        for _ in 0..1000 {
+            let _ = a.get_normal_i16(i16::MAX, 15);
+            let _ = a.get_normal_i16(i16::MIN, 15);
         }
-        {
-            let mut a = Shr3::new_state(42);
-            let b: usize = a.get_bits(5);
-            assert_eq!(b, 4);
+    }
+
+    #[test]
+    fn test_triangular() {
+        let mut a = Shr3::new_state(42);
+        let mut count = [0_u32; 11];
+        for _ in 0..36000 {
+            let b: u8 = a.get_triangular(10, 20);
+            count[(b - 10) as usize] += 1;
         }
-        #[cfg(has_u128)]
-        {
-            let mut a = Shr3::new_state(42);
-            let b: u128 = a.get_bits(5);
-            assert_eq!(b, 4);
+        // Probabilities: 1/36, 2/36, ... 6/36, ... 2/36, 1/36
+        for (i, c) in count.iter().enumerate() {
+            let exp = 1000 * (6 - (i as i32 - 5).abs()) as u32;
+            assert!((exp * 9 / 10..=exp * 11 / 10).contains(c));
         }
 
-        // signed
-        {
-            let mut a = Shr3::new_state(42);
-            let b: i8 = a.get_bits(5);
-            assert_eq!(b, 4);
-        }
-        {
-            let mut a = Shr3::new_state(42);
-            let b: i16 = a.get_bits(5);
-            assert_eq!(b, 4);
+        let mut count = [0_u32; 4];
+        for _ in 0..10000 {
+            let b: i32 = a.get_triangular(-2, 1);
+            count[(b + 2) as usize] += 1;
         }
-        {
-            let mut a = Shr3::new_state(42);
-            let b: i32 = a.get_bits(5);
-            assert_eq!(b, 4);
+        // Probabilities: 1/6, 2/6, 2/6, 1/6
+        assert!(count[0] < count[1] * 6 / 10);
+        assert!(count[3] < count[2] * 6 / 10);
+
+        let b: i16 = a.get_triangular(-7, -7);
+        assert_eq!(b, -7);
+        for _ in 0..1000 {
+            let _: i8 = a.get_triangular(i8::MIN, i8::MAX);
+            let c: char = a.get_triangular('a', 'z');
+            assert!(c.is_ascii_lowercase());
         }
-        {
-            let mut a = Shr3::new_state(42);
-            let b: i64 = a.get_bits(5);
-            assert_eq!(b, 4);
+    }
+
+    #[test]
+    fn test_choose() {
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        let table = [10, 20, 30, 40, 50];
+        for _ in 0..1000 {
+            let index: usize = b.get_max(4);
+            assert_eq!(a.choose(&table), Some(&table[index]));
         }
-        {
-            let mut a = Shr3::new_state(42);
-            let b: isize = a.get_bits(5);
-            assert_eq!(b, 4);
+        let empty: [u8; 0] = [];
+        assert_eq!(a.choose(&empty), None);
+        assert_eq!(a.choose(&[42]), Some(&42));
+
+        let mut table = [0_u32; 4];
+        for _ in 0..4000 {
+            *a.choose_mut(&mut table).unwrap() += 1;
         }
-        #[cfg(has_u128)]
-        {
-            let mut a = Shr3::new_state(42);
-            let b: i128 = a.get_bits(5);
-            assert_eq!(b, 4);
+        assert!(table.iter().all(|c| (900..1100).contains(c)));
+        let mut empty: [u8; 0] = [];
+        assert_eq!(a.choose_mut(&mut empty), None);
+    }
+
+    #[test]
+    fn test_shuffle() {
+        let mut a = Shr3::new_state(42);
+        let mut count = [[0_u32; 4]; 4];
+        for _ in 0..4000 {
+            let mut table = [0_usize, 1, 2, 3];
+            a.shuffle(&mut table);
+            for (i, x) in table.iter().enumerate() {
+                count[i][*x] += 1;
+            }
         }
+        assert!(count.iter().flatten().all(|c| (900..1100).contains(c)));
+
+        let mut table = [42];
+        a.shuffle(&mut table);
+        assert_eq!(table, [42]);
+        a.shuffle::<u8>(&mut []);
     }
 
     #[test]
-    fn test_get_bits() {
-        // unsigned
+    fn test_choose_multiple() {
         let mut a = Shr3::new_state(42);
-        for exp in [0x0001, 0x0000, 0x0001, 0x0005, 0x0001, 0x0004] {
-            let b: u16 = a.get_bits(3);
-            assert_eq!(b, exp);
+        let table = [0_usize, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let mut count = [0_u32; 10];
+        for _ in 0..2000 {
+            let mut buf = [&0; 5];
+            assert_eq!(a.choose_multiple(&table, &mut buf), 5);
+            for (i, x) in buf.iter().enumerate() {
+                assert!(!buf[..i].contains(x));
+                count[**x] += 1;
+            }
         }
-        let b: u16 = a.get_bits(0);
-        assert_eq!(b, 0);
+        assert!(count.iter().all(|c| (900..1100).contains(c)));
 
-        // signed
+        let mut buf = [&0; 12];
+        assert_eq!(a.choose_multiple(&table, &mut buf), 10);
+        let mut chosen = [false; 10];
+        for x in &buf[..10] {
+            chosen[**x] = true;
+        }
+        assert!(chosen.iter().all(|c| *c));
+
+        let mut buf: [&usize; 0] = [];
+        assert_eq!(a.choose_multiple(&table, &mut buf), 0);
+        let mut buf = [&0; 2];
+        assert_eq!(a.choose_multiple(&table[..0], &mut buf), 0);
+    }
+
+    #[test]
+    fn test_choose_from_iter() {
         let mut a = Shr3::new_state(42);
-        for exp in [0x0001, 0x0000, 0x0001, 0x0005, 0x0001, 0x0004] {
-            let b: i16 = a.get_bits(3);
-            assert_eq!(b, exp);
+        let mut count = [0_u32; 5];
+        for _ in 0..5000 {
+            let x = a.choose_from_iter(0..5).unwrap();
+            count[x] += 1;
         }
-        let b: i16 = a.get_bits(0);
-        assert_eq!(b, 0);
+        assert!(count.iter().all(|c| (900..1100).contains(c)));
+        assert_eq!(a.choose_from_iter(0..0), None);
+        assert_eq!(a.choose_from_iter([42]), Some(42));
+        assert_eq!(a.choose_from_iter(&[1, 2, 3]).map(|x| *x < 4), Some(true));
+
+        let mut count = [0_u32; 10];
+        for _ in 0..2000 {
+            let mut buf = [0; 5];
+            assert_eq!(a.choose_multiple_from_iter(0..10, &mut buf), 5);
+            for (i, x) in buf.iter().enumerate() {
+                assert!(!buf[..i].contains(x));
+                count[*x] += 1;
+            }
+        }
+        assert!(count.iter().all(|c| (900..1100).contains(c)));
+
+        let mut buf = [0; 4];
+        assert_eq!(a.choose_multiple_from_iter(10..13, &mut buf), 3);
+        assert_eq!(buf, [10, 11, 12, 0]);
+        assert_eq!(a.choose_multiple_from_iter(10..13, &mut buf[..0]), 0);
     }
 
     #[test]
-    fn test_get() {
-        // unsigned
+    fn test_sample_indices() {
         let mut a = Shr3::new_state(42);
-        for exp in [0x20D3, 0x2C5C, 0x2A17, 0xD3C5, 0xAF08, 0x9E5B] {
-            let b: u16 = a.get();
-            assert_eq!(b, exp);
+        let mut count = [0_u32; 10];
+        for _ in 0..2000 {
+            let mut out = [0; 5];
+            a.sample_indices(10, &mut out);
+            for (i, x) in out.iter().enumerate() {
+                assert!(!out[..i].contains(x));
+                count[*x as usize] += 1;
+            }
         }
+        assert!(count.iter().all(|c| (900..1100).contains(c)));
 
-        // signed
+        let mut out = [0; 10];
+        a.sample_indices(10, &mut out);
+        out.sort_unstable();
+        assert_eq!(out, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        let mut out = [0; 3];
+        a.sample_indices(0x10000, &mut out);
+        assert!(out[0] != out[1] && out[0] != out[2] && out[1] != out[2]);
+        a.sample_indices(0, &mut []);
+    }
+
+    #[test]
+    fn test_choose_weighted() {
         let mut a = Shr3::new_state(42);
-        for exp in [0x20D3, 0x2C5C, 0x2A17, 0xD3C5, 0xAF08, 0x9E5B] {
-            let b: i16 = a.get();
-            assert_eq!(b as u16, exp as u16);
+        let weights = [0, 1, 0, 3, 6, 0];
+        let mut count = [0_u32; 6];
+        for _ in 0..10000 {
+            count[a.choose_weighted(&weights)] += 1;
+        }
+        assert_eq!(count[0], 0);
+        assert_eq!(count[2], 0);
+        assert_eq!(count[5], 0);
+        assert!((900..1100).contains(&count[1]));
+        assert!((2800..3200).contains(&count[3]));
+        assert!((5700..6300).contains(&count[4]));
+
+        for _ in 0..100 {
+            assert_eq!(a.choose_weighted(&[0, 0, 42]), 2);
+            assert_eq!(a.choose_weighted(&[u16::MAX]), 0);
         }
     }
 
+    #[test]
+    #[should_panic]
+    fn test_choose_weighted_zero() {
+        let mut a = Shr3::new_state(42);
+        a.choose_weighted(&[0, 0]);
+    }
+
     #[test]
     fn test_max() {
         // unsigned
@@ -623,6 +2874,31 @@ mod tests {
         assert_eq!(b, -111);
     }
 
+    #[test]
+    fn test_try_minmax() {
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        for _ in 0..1000 {
+            let x: u32 = b.get_minmax(60, 170);
+            assert_eq!(a.try_get_minmax(60, 170, u32::MAX), Some(x));
+        }
+        let x: i32 = a.try_get_minmax(-111, -111, 1).unwrap();
+        assert_eq!(x, -111);
+        let x: Option<u8> = a.try_get_minmax(0, 100, 0);
+        assert_eq!(x, None);
+
+        // Range size 129 rejects almost 50% of the attempts.
+        let mut none = 0;
+        for _ in 0..1000 {
+            let x: Option<u8> = a.try_get_minmax(0, 128, 1);
+            match x {
+                Some(x) => assert!(x <= 128),
+                None => none += 1,
+            }
+        }
+        assert!((400..600).contains(&none));
+    }
+
     #[test]
     fn test_range() {
         let mut a = Shr3::new_state(42);
@@ -680,6 +2956,50 @@ mod tests {
         assert_eq!(b, -111);
     }
 
+    #[test]
+    fn test_max_fast() {
+        let mut a = Shr3::new_state(42);
+        let mut count = [0_u32; 6];
+        for _ in 0..6000 {
+            let b = a.get_max_fast(5);
+            count[b as usize] += 1;
+        }
+        assert!(count.iter().all(|c| (900..1100).contains(c)));
+        assert_eq!(a.get_max_fast(0), 0);
+
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        let exp: u32 = b.get();
+        assert_eq!(a.get_max_fast(u32::MAX), exp);
+        let exp: u32 = b.get();
+        assert_eq!(a.get_max_fast(0xFF), exp >> 24);
+    }
+
+    #[test]
+    fn test_lemire() {
+        let mut a = Shr3::new_state(42);
+        let mut count = [0_u32; 6];
+        for _ in 0..6000 {
+            let b = a.get_max_lemire(5);
+            count[b as usize] += 1;
+        }
+        assert!(count.iter().all(|c| (900..1100).contains(c)));
+        for _ in 0..1000 {
+            let b = a.get_minmax_lemire(60, 170);
+            assert!((60..=170).contains(&b));
+        }
+        assert_eq!(a.get_max_lemire(0), 0);
+        assert_eq!(a.get_minmax_lemire(111, 111), 111);
+
+        // One draw per value, if there is no rejection.
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        let exp: u32 = b.get();
+        assert_eq!(a.get_max_lemire(u32::MAX), exp);
+        let exp: u32 = b.get();
+        assert_eq!(a.get_max_lemire(0xFFFF), exp >> 16);
+    }
+
 /*
     #[test]
     fn test_cycle_unsigned() {
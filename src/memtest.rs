@@ -0,0 +1,156 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Memory test pattern.
+
+use crate::shr3;
+
+/// First mismatch found by `MemTestPattern::verify()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemTestMismatch {
+    /// Address of the mismatching word.
+    pub address: usize,
+    /// Index of the mismatching word in the verified region.
+    pub index: usize,
+    /// The expected pattern word.
+    pub expected: u32,
+    /// The word read from the memory region.
+    pub actual: u32,
+}
+
+/// Reproducible word stream for RAM and flash tests.
+///
+/// The pattern word stream is the sequence of SHR3 states starting at the seed.
+/// One `shr3()` round is done per word.
+/// That is the word mode stream of `Shr3::get_word32()`.
+/// The same seed always gives the same pattern.
+/// Therefore, a region written with `MemTestPattern::fill()` can be checked
+/// by `MemTestPattern::verify()` with a new pattern with the same seed.
+///
+/// All memory accesses are volatile.
+/// Therefore, the compiler does not optimize away the writes and reads.
+///
+/// ```
+///     use shr3::MemTestPattern;
+///
+///     let mut ram = [0_u32; 64];
+///     MemTestPattern::new(42).fill(&mut ram);
+///     assert_eq!(MemTestPattern::new(42).verify(&ram), Ok(()));
+///
+///     ram[10] ^= 0x100; // Simulate a stuck bit.
+///     let mismatch = MemTestPattern::new(42).verify(&ram).unwrap_err();
+///     assert_eq!(mismatch.index, 10);
+///     assert_eq!(mismatch.address, &ram[10] as *const u32 as usize);
+///     assert_eq!(mismatch.expected ^ mismatch.actual, 0x100);
+/// ```
+///
+/// *Note*: Use different seeds for consecutive test passes.
+///         Otherwise stale memory contents from the previous pass are not detected.
+#[derive(Clone, Debug)]
+pub struct MemTestPattern {
+    seed: u32,
+    state: u32,
+}
+
+impl MemTestPattern {
+    /// Create a new pattern stream from the seed.
+    ///
+    /// Special seed 0: The seed must not be 0. If 0 is passed to this function,
+    ///                 then the seed 0x7FFFFFFF is picked instead.
+    #[inline]
+    pub const fn new(seed: u32) -> Self {
+        let seed = if seed == 0 { 0x7FFFFFFF } else { seed };
+        Self {
+            seed,
+            state: seed,
+        }
+    }
+
+    /// Restart the pattern stream at the beginning.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.state = self.seed;
+    }
+
+    /// Get the next pattern word.
+    #[inline]
+    pub fn next_word(&mut self) -> u32 {
+        self.state = shr3(self.state);
+        self.state
+    }
+
+    /// Write the next pattern words to the memory `region`.
+    pub fn fill(&mut self, region: &mut [u32]) {
+        for word in region {
+            let pattern = self.next_word();
+            // SAFETY: The pointer is derived from a valid mutable reference.
+            unsafe { core::ptr::write_volatile(word, pattern) };
+        }
+    }
+
+    /// Compare the memory `region` with the next pattern words.
+    ///
+    /// Returns the first mismatch, if any.
+    /// The pattern stream is advanced by the number of compared words.
+    /// On mismatch the remaining words of the region are not compared.
+    pub fn verify(&mut self, region: &[u32]) -> Result<(), MemTestMismatch> {
+        for (index, word) in region.iter().enumerate() {
+            let expected = self.next_word();
+            // SAFETY: The pointer is derived from a valid reference.
+            let actual = unsafe { core::ptr::read_volatile(word) };
+            if actual != expected {
+                return Err(MemTestMismatch {
+                    address: word as *const u32 as usize,
+                    index,
+                    expected,
+                    actual,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memtest() {
+        let mut a = MemTestPattern::new(42);
+        assert_eq!(a.next_word(), shr3(42));
+        assert_eq!(a.next_word(), shr3(shr3(42)));
+        a.reset();
+        assert_eq!(a.next_word(), shr3(42));
+
+        let mut mem = [0_u32; 100];
+        let mut a = MemTestPattern::new(1);
+        a.fill(&mut mem[..50]);
+        a.fill(&mut mem[50..]);
+        assert_eq!(MemTestPattern::new(1).verify(&mem), Ok(()));
+        assert!(MemTestPattern::new(2).verify(&mem).is_err());
+
+        mem[70] = 0;
+        mem[80] = 0;
+        let err = MemTestPattern::new(1).verify(&mem).unwrap_err();
+        assert_eq!(err.index, 70);
+        assert_eq!(err.address, mem.as_ptr() as usize + 70 * 4);
+        assert_eq!(err.actual, 0);
+        let mut exp = MemTestPattern::new(1);
+        for _ in 0..70 {
+            exp.next_word();
+        }
+        assert_eq!(err.expected, exp.next_word());
+
+        let mut a = MemTestPattern::new(0);
+        assert_eq!(a.next_word(), shr3(0x7FFFFFFF));
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
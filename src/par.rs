@@ -0,0 +1,73 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Parallel fill with rayon.
+
+use crate::{Shr3, gf2};
+use rayon::prelude::*;
+
+/// Number of bytes per parallel task.
+/// This must be a multiple of 4 to keep the 32 bit word batching of `Shr3::fill_bytes()`.
+const PAR_CHUNK: usize = 64 * 1024;
+
+impl Shr3 {
+    /// Fill the byte slice `buf` with random bytes on all rayon worker threads.
+    ///
+    /// The slice is split into chunks.
+    /// Each chunk is filled from a generator that starts at the jump ahead offset
+    /// of the chunk in the stream of `self`.
+    /// Therefore, the extracted random stream is bit exact identical to `fill_bytes()`.
+    /// Afterwards, `self` is advanced by `8 * buf.len()` rounds like after `fill_bytes()`.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     let mut a = Shr3::new_state(42);
+    ///     let mut b = Shr3::new_state(42);
+    ///     let mut x = vec![0; 300_000];
+    ///     let mut y = vec![0; 300_000];
+    ///     a.par_fill(&mut x);
+    ///     b.fill_bytes(&mut y);
+    ///     assert_eq!(x, y);
+    ///     assert_eq!(a.state(), b.state());
+    /// ```
+    pub fn par_fill(&mut self, buf: &mut [u8]) {
+        let state = self.state();
+        buf.par_chunks_mut(PAR_CHUNK)
+            .enumerate()
+            .for_each(|(i, chunk)| {
+                let offset = (i * PAR_CHUNK) as u64 * 8;
+                let mut shr3 = Shr3::new_state(gf2::jump(state, offset));
+                shr3.fill_bytes(chunk);
+            });
+        self.discard(buf.len() as u64 * 8);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec;
+
+    #[test]
+    fn test_par_fill() {
+        for len in [0, 1, 5, PAR_CHUNK - 1, PAR_CHUNK, 3 * PAR_CHUNK + 7] {
+            let mut a = Shr3::new_state(42);
+            let mut b = Shr3::new_state(42);
+            let mut x = vec![0; len];
+            let mut y = vec![0; len];
+            a.par_fill(&mut x);
+            b.fill_bytes(&mut y);
+            assert_eq!(x, y);
+            assert_eq!(a.state(), b.state());
+        }
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,398 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Standard pseudo random binary sequences (PRBS).
+
+use crate::{BaseOps, Shr3Ops};
+use core::num::Wrapping;
+use core::ops::{Add, BitOrAssign, ShlAssign, Sub};
+
+/// Pseudo random binary sequence generator with the polynomial `x**N + x**M + 1`.
+///
+/// This is a Fibonacci LFSR with `N` bits of state.
+/// Each step computes the feedback bit from the state bits `N` and `M` (counting from 1),
+/// shifts the state one bit to the left and inserts the feedback bit at the LSB.
+/// The feedback bit (inverted, if `INVERT` is true) is the output bit.
+/// One step is done per extracted random bit.
+/// The extracted values are filled from MSB to LSB.
+/// Therefore, the first sequence bit is the MSB of the first extracted byte.
+///
+/// The standard sequences are available as `Prbs7`, `Prbs15`, `Prbs23` and `Prbs31`.
+/// Starting from the default all-ones state, they emit the canonical sequences.
+///
+/// ```
+///     use shr3::prelude::*;
+///     use shr3::Prbs7;
+///
+///     let mut prbs = Prbs7::new();
+///     let x: u16 = prbs.get();
+///     assert_eq!(x, 0b0000_0010_0000_1100);
+/// ```
+///
+/// This generator is *not* suitable as a general purpose random number generator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Prbs<const N: u32, const M: u32, const INVERT: bool> {
+    state: u32,
+}
+
+/// PRBS7 with the polynomial `x**7 + x**6 + 1` (ITU-T O.150, non-inverted).
+pub type Prbs7 = Prbs<7, 6, false>;
+
+/// PRBS15 with the polynomial `x**15 + x**14 + 1` (ITU-T O.150, inverted).
+pub type Prbs15 = Prbs<15, 14, true>;
+
+/// PRBS23 with the polynomial `x**23 + x**18 + 1` (ITU-T O.150, inverted).
+pub type Prbs23 = Prbs<23, 18, true>;
+
+/// PRBS31 with the polynomial `x**31 + x**28 + 1` (ITU-T O.150, inverted).
+pub type Prbs31 = Prbs<31, 28, true>;
+
+impl<const N: u32, const M: u32, const INVERT: bool> Prbs<N, M, INVERT> {
+    /// Mask of all state bits.
+    pub const MASK: u32 = {
+        assert!(N > M && M > 0 && N <= 32, "Prbs: Invalid polynomial.");
+        (((1_u64 << N) - 1) & u32::MAX as u64) as u32
+    };
+
+    /// The period of the sequence, if the polynomial is primitive.
+    pub const PERIOD: u64 = (1 << N) - 1;
+
+    /// Create a new instance with the standard all-ones initial state.
+    #[inline]
+    pub const fn new() -> Self {
+        Self::new_state(Self::MASK)
+    }
+
+    /// Create a new instance with user specified initial state.
+    ///
+    /// Bits above bit `N - 1` are ignored.
+    ///
+    /// Special state 0: The state must not be 0. If 0 is passed to this function,
+    ///                  then the all-ones state is picked instead.
+    #[inline]
+    pub const fn new_state(state: u32) -> Self {
+        let state = state & Self::MASK;
+        Self {
+            state: if state == 0 { Self::MASK } else { state },
+        }
+    }
+
+    /// Get the current LFSR state.
+    #[inline]
+    pub const fn state(&self) -> u32 {
+        self.state
+    }
+
+    /// Do one LFSR step and get the output bit.
+    #[inline]
+    pub fn next_bit(&mut self) -> bool {
+        let fb = Self::feedback(self.state);
+        self.state = Self::shift(self.state, fb);
+        fb ^ INVERT
+    }
+
+    /// Calculate the feedback bit of the LFSR `state`.
+    #[inline]
+    const fn feedback(state: u32) -> bool {
+        ((state >> (N - 1)) ^ (state >> (M - 1))) & 1 != 0
+    }
+
+    /// Shift the feedback bit `fb` into the LFSR `state`.
+    #[inline]
+    const fn shift(state: u32, fb: bool) -> u32 {
+        ((state << 1) | fb as u32) & Self::MASK
+    }
+}
+
+impl<const N: u32, const M: u32, const INVERT: bool> Default for Prbs<N, M, INVERT> {
+    /// Create a new instance with the standard all-ones initial state.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: u32, const M: u32, const INVERT: bool> Shr3Ops<T> for Prbs<N, M, INVERT>
+    where T: BaseOps + PartialOrd,
+          T::U: BaseOps,
+          Wrapping<T::U>: Sub<Output=Wrapping<T::U>> + Add<Output=Wrapping<T::U>> + PartialOrd + ShlAssign<usize> + BitOrAssign,
+{
+    #[inline]
+    fn get_bits(&mut self, bitcount: u8) -> T {
+        T::from_unsigned(Shr3Ops::<T>::get_bits_unsigned(self, bitcount))
+    }
+
+    fn get_bits_unsigned(&mut self, bitcount: u8) -> Wrapping<T::U> {
+        debug_assert!(bitcount <= T::NUMBITS);
+        let mut ret = Wrapping(T::U::from_u8(0));
+        for _ in 0..bitcount {
+            let bit = self.next_bit();
+            ret <<= 1;
+            ret |= Wrapping(T::U::from_u8(bit as u8));
+        }
+        ret
+    }
+}
+
+/// Number of correctly predicted bits after loading the state, until the checker is synchronized.
+const SYNC_BITS: u32 = 32;
+
+/// Size of the sync loss detection window, in bits.
+const LOSS_WINDOW: u32 = 64;
+
+/// Sync is lost, if more than this number of bit errors occur within one `LOSS_WINDOW`.
+const LOSS_THRESHOLD: u32 = 16;
+
+/// Checker for a received `Prbs` bit stream.
+///
+/// The checker synchronizes itself onto the incoming bit stream.
+/// It does not need to know the start of the sequence.
+///
+/// * Not synchronized: The received bits are loaded into the LFSR state.
+///   After `N` bits the checker predicts each following bit.
+///   After 32 consecutive correct predictions the checker is synchronized.
+///   A wrong prediction restarts the 32 bit verification.
+/// * Synchronized: The checker runs its own copy of the generator.
+///   Each received bit that differs from the generated bit is counted as bit error.
+///   Bit errors do not disturb the checker state.
+///   If more than 16 bit errors occur within a window of 64 bits,
+///   then the sync is lost and the checker synchronizes again.
+///
+/// Bits received while not synchronized are neither checked nor counted.
+///
+/// ```
+///     use shr3::prelude::*;
+///     use shr3::{Prbs15, Prbs15Checker};
+///
+///     let mut prbs = Prbs15::new();
+///     let mut checker = Prbs15Checker::new();
+///     let mut buf = [0_u8; 64];
+///     prbs.fill_slice(&mut buf);
+///     buf[40] ^= 0x10;    // Inject one bit error.
+///     checker.check_bytes(&buf);
+///     assert!(checker.is_synced());
+///     assert_eq!(checker.error_count(), 1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PrbsChecker<const N: u32, const M: u32, const INVERT: bool> {
+    state: u32,
+    synced: bool,
+    run: u32,
+    window_bits: u32,
+    window_errors: u32,
+    bits: u64,
+    errors: u64,
+    sync_losses: u32,
+}
+
+/// Checker for the `Prbs7` sequence.
+pub type Prbs7Checker = PrbsChecker<7, 6, false>;
+
+/// Checker for the `Prbs15` sequence.
+pub type Prbs15Checker = PrbsChecker<15, 14, true>;
+
+/// Checker for the `Prbs23` sequence.
+pub type Prbs23Checker = PrbsChecker<23, 18, true>;
+
+/// Checker for the `Prbs31` sequence.
+pub type Prbs31Checker = PrbsChecker<31, 28, true>;
+
+impl<const N: u32, const M: u32, const INVERT: bool> PrbsChecker<N, M, INVERT> {
+    /// Create a new, not synchronized checker.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            state: 0,
+            synced: false,
+            run: 0,
+            window_bits: 0,
+            window_errors: 0,
+            bits: 0,
+            errors: 0,
+            sync_losses: 0,
+        }
+    }
+
+    /// Check one received bit.
+    pub fn check_bit(&mut self, bit: bool) {
+        let rx = bit ^ INVERT;
+        let fb = Prbs::<N, M, INVERT>::feedback(self.state);
+        if self.synced {
+            self.state = Prbs::<N, M, INVERT>::shift(self.state, fb);
+            self.bits += 1;
+            self.window_bits += 1;
+            if rx != fb {
+                self.errors += 1;
+                self.window_errors += 1;
+            }
+            if self.window_bits >= LOSS_WINDOW {
+                if self.window_errors > LOSS_THRESHOLD {
+                    self.synced = false;
+                    self.run = 0;
+                    self.sync_losses += 1;
+                }
+                self.window_bits = 0;
+                self.window_errors = 0;
+            }
+        } else {
+            self.state = Prbs::<N, M, INVERT>::shift(self.state, rx);
+            if self.run < N || rx == fb {
+                self.run += 1;
+            } else {
+                self.run = N;
+            }
+            if self.run >= N + SYNC_BITS && self.state != 0 {
+                self.synced = true;
+                self.window_bits = 0;
+                self.window_errors = 0;
+            }
+        }
+    }
+
+    /// Check all bits of the received bytes.
+    ///
+    /// The bits of each byte are checked from MSB to LSB.
+    /// That is the order in which `Shr3Ops::fill_slice()` of `Prbs` fills bytes.
+    pub fn check_bytes(&mut self, data: &[u8]) {
+        for byte in data {
+            for i in (0..8).rev() {
+                self.check_bit(byte & (1 << i) != 0);
+            }
+        }
+    }
+
+    /// Returns true, if the checker is synchronized to the received bit stream.
+    #[inline]
+    pub const fn is_synced(&self) -> bool {
+        self.synced
+    }
+
+    /// Get the number of bits checked while synchronized.
+    #[inline]
+    pub const fn bit_count(&self) -> u64 {
+        self.bits
+    }
+
+    /// Get the number of bit errors detected while synchronized.
+    #[inline]
+    pub const fn error_count(&self) -> u64 {
+        self.errors
+    }
+
+    /// Get the number of sync losses.
+    #[inline]
+    pub const fn sync_loss_count(&self) -> u32 {
+        self.sync_losses
+    }
+
+    /// Reset the bit, error and sync loss counters.
+    ///
+    /// The synchronization state is not changed.
+    #[inline]
+    pub fn reset_counters(&mut self) {
+        self.bits = 0;
+        self.errors = 0;
+        self.sync_losses = 0;
+    }
+}
+
+impl<const N: u32, const M: u32, const INVERT: bool> Default for PrbsChecker<N, M, INVERT> {
+    /// Create a new, not synchronized checker.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prbs() {
+        let mut a = Prbs7::new();
+        let x: u32 = a.get();
+        assert_eq!(x, 0x020C_28F2);
+        let mut a = Prbs15::new();
+        let x: u32 = a.get();
+        assert_eq!(x, 0xFFFD_FFF3);
+        let mut a = Prbs23::new();
+        let x: u32 = a.get();
+        assert_eq!(x, 0xFFFF_C1FF);
+        let mut a = Prbs31::new();
+        let x: u32 = a.get();
+        assert_eq!(x, 0xFFFF_FFF1);
+
+        let mut a = Prbs7::new();
+        let mut ones = 0;
+        for i in 1..=Prbs7::PERIOD {
+            ones += a.next_bit() as u64;
+            assert_eq!(a.state() == Prbs7::MASK, i == Prbs7::PERIOD);
+        }
+        assert_eq!(ones, 64);
+        let mut a = Prbs15::new();
+        for i in 1..=Prbs15::PERIOD {
+            a.next_bit();
+            assert_eq!(a.state() == Prbs15::MASK, i == Prbs15::PERIOD);
+        }
+
+        assert_eq!(Prbs7::new_state(0).state(), 0x7F);
+        assert_eq!(Prbs7::new_state(0x180).state(), 0x7F);
+        assert_eq!(Prbs7::new_state(0x181).state(), 0x01);
+        assert_eq!(Prbs31::default(), Prbs31::new());
+    }
+
+    #[test]
+    fn test_prbs_checker() {
+        // Start somewhere in the sequence.
+        let mut a = Prbs31::new_state(0x1234_5678);
+        let mut c = Prbs31Checker::new();
+        for i in 0..1000_u32 {
+            c.check_bit(a.next_bit());
+            assert_eq!(c.is_synced(), i + 1 >= 31 + SYNC_BITS);
+        }
+        assert_eq!(c.bit_count(), 1000 - 31 - SYNC_BITS as u64);
+        assert_eq!(c.error_count(), 0);
+
+        // Single bit errors.
+        for i in 0..1000 {
+            c.check_bit(a.next_bit() ^ (i % 100 == 0));
+        }
+        assert!(c.is_synced());
+        assert_eq!(c.error_count(), 10);
+        assert_eq!(c.sync_loss_count(), 0);
+
+        // Random data.
+        let mut r = crate::Shr3::new_state(42);
+        let mut buf = [0_u8; 16];
+        r.fill_bytes(&mut buf);
+        c.check_bytes(&buf);
+        assert!(!c.is_synced());
+        assert_eq!(c.sync_loss_count(), 1);
+
+        // Resync.
+        c.reset_counters();
+        let mut buf = [0_u8; 32];
+        a.fill_slice(&mut buf);
+        c.check_bytes(&buf);
+        assert!(c.is_synced());
+        assert_eq!(c.error_count(), 0);
+        assert_eq!(c.sync_loss_count(), 0);
+
+        // Inverted stream never synchronizes.
+        let mut a = Prbs7::new();
+        let mut c = Prbs7Checker::default();
+        for _ in 0..1000 {
+            c.check_bit(!a.next_bit());
+        }
+        assert!(!c.is_synced());
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
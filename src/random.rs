@@ -0,0 +1,165 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Random generation of whole values.
+
+use crate::Shr3Ops;
+use core::num::{
+    NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroIsize,
+    NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroUsize,
+};
+#[cfg(has_u128)]
+use core::num::{NonZeroI128, NonZeroU128};
+
+/// Types that can be generated randomly from the generator `R`.
+///
+/// This is implemented for all integer types, the non-zero integer types, `char`, `bool`
+/// and arrays of random types.
+///
+/// If the `macros` feature is enabled, then `#[derive(Random)]` implements this trait
+/// for structs and enums:
+///
+/// * The fields are generated from top to bottom with `Random::random()`.
+/// * `#[random(range = 1..=10)]` on a field generates the field with `Shr3Ops::get_range()` instead.
+/// * The variant of an enum is chosen uniformly with `Shr3Ops::<u32>::get_max()`.
+///   The fields of the chosen variant are generated afterwards.
+///
+/// See `Shr3Random::get_random()`.
+pub trait Random<R>: Sized {
+    /// Generate a random value.
+    fn random(rng: &mut R) -> Self;
+}
+
+macro_rules! impl_random {
+    ($( $t:ty ),*) => {
+        $(
+            impl<R: Shr3Ops<$t>> Random<R> for $t {
+                #[inline]
+                fn random(rng: &mut R) -> Self {
+                    Shr3Ops::<$t>::get(rng)
+                }
+            }
+        )*
+    }
+}
+
+impl_random!(u8, i8, u16, i16, u32, i32, u64, i64, usize, isize, char);
+impl_random!(NonZeroU8, NonZeroI8, NonZeroU16, NonZeroI16, NonZeroU32, NonZeroI32,
+             NonZeroU64, NonZeroI64, NonZeroUsize, NonZeroIsize);
+#[cfg(has_u128)]
+impl_random!(u128, i128, NonZeroU128, NonZeroI128);
+
+/// Generation of `Random` values with any generator.
+///
+/// This is implemented for all generators with the `Shr3Ops` interface.
+/// It is included in the `prelude`.
+pub trait Shr3Random: Sized {
+    /// Get a random value of any type that implements `Random`.
+    ///
+    /// With the `macros` feature `Random` can be derived for structs and enums.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     let mut shr3 = Shr3::new_state(42);
+    ///     let x: [u8; 2] = shr3.get_random();
+    ///     assert_eq!(x, [0x20, 0xD3]);
+    /// ```
+    #[inline]
+    fn get_random<T: Random<Self>>(&mut self) -> T {
+        T::random(self)
+    }
+}
+
+impl<R: Shr3Ops<u8>> Shr3Random for R {}
+
+/// One bit is extracted for a `bool`.
+impl<R: Shr3Ops<u8>> Random<R> for bool {
+    #[inline]
+    fn random(rng: &mut R) -> Self {
+        Shr3Ops::<u8>::get_bits(rng, 1) != 0
+    }
+}
+
+/// The elements are generated in ascending order.
+impl<R, T: Random<R>, const N: usize> Random<R> for [T; N] {
+    #[inline]
+    fn random(rng: &mut R) -> Self {
+        core::array::from_fn(|_| T::random(rng))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Kiss32, Shr3};
+
+    #[test]
+    fn test_random() {
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        let x: (u8, i64, char) = (a.get_random(), a.get_random(), a.get_random());
+        assert_eq!(x.0, Shr3Ops::<u8>::get(&mut b));
+        assert_eq!(x.1, Shr3Ops::<i64>::get(&mut b));
+        assert_eq!(x.2, Shr3Ops::<char>::get(&mut b));
+
+        let x: [NonZeroU16; 3] = a.get_random();
+        for v in x {
+            assert_eq!(v, Shr3Ops::<NonZeroU16>::get(&mut b));
+        }
+        let x: bool = a.get_random();
+        assert_eq!(x, Shr3Ops::<u8>::get_bits(&mut b, 1) == 1);
+        assert_eq!(a.state(), b.state());
+
+        let mut a = Kiss32::new();
+        let mut b = Kiss32::new();
+        let x: (u32, [i8; 2]) = (a.get_random(), a.get_random());
+        assert_eq!(x.0, Shr3Ops::<u32>::get(&mut b));
+        assert_eq!(x.1, [Shr3Ops::<i8>::get(&mut b), Shr3Ops::<i8>::get(&mut b)]);
+    }
+
+    #[cfg(feature="macros")]
+    #[test]
+    fn test_derive() {
+        #[derive(crate::Random, Debug, PartialEq)]
+        enum E {
+            A,
+            B(u8),
+            C { #[random(range = 10..=20)] c: i16 },
+        }
+
+        #[derive(crate::Random, Debug, PartialEq)]
+        struct S<T> {
+            a: u16,
+            e: [E; 4],
+            t: T,
+        }
+
+        #[derive(crate::Random, Debug, PartialEq)]
+        struct U;
+
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        let x: S<bool> = a.get_random();
+        assert_eq!(x.a, Shr3Ops::<u16>::get(&mut b));
+        for e in &x.e {
+            let y = match Shr3Ops::<u32>::get_max(&mut b, 2) {
+                0 => E::A,
+                1 => E::B(b.get()),
+                _ => E::C { c: b.get_range(10..=20) },
+            };
+            assert_eq!(*e, y);
+        }
+        assert_eq!(x.t, Shr3Ops::<u8>::get_bits(&mut b, 1) == 1);
+        let _: U = a.get_random();
+        assert_eq!(a.state(), b.state());
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,144 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Generator wrapper with automatic reseeding.
+
+use crate::{BaseOps, Shr3Ops};
+use core::num::Wrapping;
+use core::ops::{Add, Sub};
+
+/// Generator wrapper that reseeds the inner generator `R` from an entropy source periodically.
+///
+/// The entropy source is the callback `F`. It returns a newly seeded generator `R`.
+/// Any generator with `Shr3Ops` can be used, e.g. `Shr3` or `Kiss32`.
+///
+/// The generator is reseeded before an extraction,
+/// if at least `threshold` bits have been extracted since the previous reseed.
+/// An extraction is never split across a reseed.
+///
+/// *Note*: Only extractions through `Shr3Ops` are counted.
+///
+/// ```
+///     use shr3::prelude::*;
+///     use shr3::Reseeding;
+///
+///     let mut adc = 1234_u32;
+///     let mut shr3 = Reseeding::new(1024, || {
+///         adc = adc.wrapping_mul(31).wrapping_add(7); // Read a noise source here.
+///         Shr3::new_state(adc)
+///     });
+///     let x: u32 = shr3.get();
+/// ```
+pub struct Reseeding<R, F> {
+    rng: R,
+    entropy: F,
+    threshold: u64,
+    count: u64,
+}
+
+impl<R, F: FnMut() -> R> Reseeding<R, F> {
+    /// Create a new generator that is seeded from `entropy`
+    /// and reseeded after each `threshold` extracted bits.
+    pub fn new(threshold: u64, mut entropy: F) -> Self {
+        Self {
+            rng: entropy(),
+            entropy,
+            threshold,
+            count: 0,
+        }
+    }
+
+    /// Reseed the generator from the entropy source now.
+    pub fn reseed(&mut self) {
+        self.rng = (self.entropy)();
+        self.count = 0;
+    }
+
+    /// Get the number of bits extracted since the previous reseed.
+    #[inline]
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Get a reference to the inner generator.
+    #[inline]
+    pub fn inner(&self) -> &R {
+        &self.rng
+    }
+}
+
+impl<T, R, F> Shr3Ops<T> for Reseeding<R, F>
+    where T: BaseOps + PartialOrd,
+          T::U: BaseOps,
+          Wrapping<T::U>: Sub<Output=Wrapping<T::U>> + Add<Output=Wrapping<T::U>> + PartialOrd,
+          R: Shr3Ops<T>,
+          F: FnMut() -> R,
+{
+    #[inline]
+    fn get_bits(&mut self, bitcount: u8) -> T {
+        T::from_unsigned(Shr3Ops::<T>::get_bits_unsigned(self, bitcount))
+    }
+
+    fn get_bits_unsigned(&mut self, bitcount: u8) -> Wrapping<T::U> {
+        if self.count >= self.threshold {
+            self.reseed();
+        }
+        self.count += bitcount as u64;
+        Shr3Ops::<T>::get_bits_unsigned(&mut self.rng, bitcount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Kiss32, Shr3};
+
+    #[test]
+    fn test_reseeding() {
+        let mut seeds = [42, 43, 0, 44].into_iter();
+        let mut a = Reseeding::new(64, || Shr3::new_state(seeds.next().unwrap()));
+        let mut b = Shr3::new_state(42);
+        let x: [u32; 2] = a.get_array();
+        assert_eq!(x, Shr3Ops::<u32>::get_array(&mut b));
+        assert_eq!(a.count(), 64);
+
+        let mut b = Shr3::new_state(43);
+        let x: [u16; 4] = a.get_array();
+        assert_eq!(x, Shr3Ops::<u16>::get_array(&mut b));
+
+        // Extractions are not split.
+        let mut b = Shr3::new_state(0);
+        let x: u64 = a.get_bits(60);
+        assert_eq!(x, Shr3Ops::<u64>::get_bits(&mut b, 60));
+        let x: u16 = a.get();
+        assert_eq!(x, Shr3Ops::<u16>::get(&mut b));
+        assert_eq!(a.count(), 76);
+
+        a.reseed();
+        assert_eq!(a.count(), 0);
+        let mut b = Shr3::new_state(44);
+        let x: u32 = a.get();
+        assert_eq!(x, Shr3Ops::<u32>::get(&mut b));
+        assert_eq!(a.inner().state(), b.state());
+
+        let mut seed = 0;
+        let mut a = Reseeding::new(32, || {
+            seed += 1;
+            Kiss32::new_state([seed, 2, 3, 4])
+        });
+        let mut b = Kiss32::new_state([1, 2, 3, 4]);
+        let x: u32 = a.get();
+        assert_eq!(x, Shr3Ops::<u32>::get(&mut b));
+        let mut b = Kiss32::new_state([2, 2, 3, 4]);
+        let x: u8 = a.get();
+        assert_eq!(x, Shr3Ops::<u8>::get(&mut b));
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
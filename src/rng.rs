@@ -0,0 +1,78 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! `rand_core` trait implementations for `Shr3`.
+
+use crate::{Shr3, Shr3Ops};
+use rand_core::{RngCore, SeedableRng};
+
+impl RngCore for Shr3 {
+    /// Extract 32 bits from SHR3.
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        self.get()
+    }
+
+    /// Extract 64 bits from SHR3.
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.get()
+    }
+
+    /// Fill `dst` with random bytes.
+    /// See `Shr3::fill_bytes()`.
+    #[inline]
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        Shr3::fill_bytes(self, dst);
+    }
+}
+
+impl SeedableRng for Shr3 {
+    /// The seed is the little endian representation of the initial SHR3 state.
+    type Seed = [u8; 4];
+
+    /// Create a new SHR3 instance from a little endian seed.
+    ///
+    /// The same special state 0 handling as in `Shr3::new_state()` applies.
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Shr3::new_state(u32::from_le_bytes(seed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rngcore() {
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        assert_eq!(a.next_u32(), Shr3Ops::<u32>::get(&mut b));
+        assert_eq!(a.next_u64(), Shr3Ops::<u64>::get(&mut b));
+
+        let mut a = Shr3::new_state(42);
+        let mut buf = [0_u8; 6];
+        a.fill_bytes(&mut buf);
+        assert_eq!(buf, [0x20, 0xD3, 0x2C, 0x5C, 0x2A, 0x17]);
+    }
+
+    #[test]
+    fn test_seedable() {
+        let mut a = Shr3::from_seed([42, 0, 0, 0]);
+        let mut b = Shr3::new_state(42);
+        assert_eq!(a.next_u32(), Shr3Ops::<u32>::get(&mut b));
+
+        let mut a = Shr3::from_seed([0, 0, 0, 0]);
+        let mut b = Shr3::new_state(0);
+        assert_eq!(a.next_u32(), Shr3Ops::<u32>::get(&mut b));
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
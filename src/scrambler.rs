@@ -0,0 +1,266 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Self-synchronizing (multiplicative) scrambler and descrambler.
+
+/// Calculate the feedback bit of the scrambler `state` with the taps `TAPS`.
+#[inline]
+const fn feedback<const TAPS: u64>(state: u64) -> bool {
+    (state & TAPS).count_ones() & 1 != 0
+}
+
+/// Shift the line bit `bit` into the scrambler `state`.
+#[inline]
+const fn shift(state: u64, bit: bool) -> u64 {
+    (state << 1) | bit as u64
+}
+
+/// Self-synchronizing (multiplicative) scrambler with the polynomial `TAPS`.
+///
+/// Bit `k - 1` of `TAPS` is the coefficient of `x**k` of the polynomial.
+/// The constant term 1 is implicit.
+/// For example the polynomial `1 + x**39 + x**58` is `TAPS = (1 << 57) | (1 << 38)`.
+/// Polynomials of degree up to 64 are supported.
+///
+/// Each scrambled bit is the data bit XORed with the feedback of the previously scrambled bits:
+///
+/// ```text
+///     y[n] = x[n] ^ y[n - k1] ^ y[n - k2] ^ ...
+/// ```
+///
+/// The scrambled stream is DC balanced for typical data (e.g. long runs of zeros or ones).
+/// Use the `Descrambler` with the same polynomial on the receiving side.
+///
+/// ```
+///     use shr3::{Scrambler64b66b, Descrambler64b66b};
+///
+///     let orig = *b"Hello world, 0000000000000000";
+///     let mut data = orig;
+///     let mut scrambler = Scrambler64b66b::new_state(0x1234_5678);
+///     let mut descrambler = Descrambler64b66b::new();
+///     scrambler.scramble(&mut data);
+///     assert_ne!(data, orig);
+///     // The descrambler synchronizes itself within the first 58 bits.
+///     descrambler.descramble(&mut data);
+///     assert_eq!(data[8..], orig[8..]);
+/// ```
+///
+/// *Note*: Scrambling is not encryption.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Scrambler<const TAPS: u64> {
+    state: u64,
+}
+
+/// Self-synchronizing descrambler with the polynomial `TAPS`.
+///
+/// This reverts `Scrambler` with the same polynomial:
+///
+/// ```text
+///     x[n] = y[n] ^ y[n - k1] ^ y[n - k2] ^ ...
+/// ```
+///
+/// The descrambler state only consists of the last received bits.
+/// Therefore, the descrambler synchronizes itself after receiving `degree` bits,
+/// independent of its initial state.
+/// Each bit error on the line results in one bit error in the descrambled data
+/// plus one bit error per term of the polynomial.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Descrambler<const TAPS: u64> {
+    state: u64,
+}
+
+/// IEEE 802.3 64b/66b scrambler with the polynomial `1 + x**39 + x**58`.
+///
+/// Use `Scrambler::scramble_lsb_first()`, because 802.3 transmits the LSB first.
+pub type Scrambler64b66b = Scrambler<{ (1 << 57) | (1 << 38) }>;
+
+/// IEEE 802.3 64b/66b descrambler with the polynomial `1 + x**39 + x**58`.
+///
+/// Use `Descrambler::descramble_lsb_first()`, because 802.3 transmits the LSB first.
+pub type Descrambler64b66b = Descrambler<{ (1 << 57) | (1 << 38) }>;
+
+/// ATM/SDH payload scrambler with the polynomial `1 + x**43`.
+pub type ScramblerX43 = Scrambler<{ 1 << 42 }>;
+
+/// ATM/SDH payload descrambler with the polynomial `1 + x**43`.
+pub type DescramblerX43 = Descrambler<{ 1 << 42 }>;
+
+impl<const TAPS: u64> Scrambler<TAPS> {
+    /// Create a new scrambler with all-zeros initial state.
+    #[inline]
+    pub const fn new() -> Self {
+        Self::new_state(0)
+    }
+
+    /// Create a new scrambler with user specified initial state.
+    ///
+    /// Bit 0 of `state` is the most recently scrambled bit.
+    #[inline]
+    pub const fn new_state(state: u64) -> Self {
+        Self { state }
+    }
+
+    /// Get the current state.
+    #[inline]
+    pub const fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Scramble one bit.
+    #[inline]
+    pub fn scramble_bit(&mut self, bit: bool) -> bool {
+        let out = bit ^ feedback::<TAPS>(self.state);
+        self.state = shift(self.state, out);
+        out
+    }
+
+    /// Scramble the bytes of `data` in place.
+    ///
+    /// The bits of each byte are processed from MSB to LSB.
+    pub fn scramble(&mut self, data: &mut [u8]) {
+        for byte in data {
+            for i in (0..8).rev() {
+                let bit = self.scramble_bit(*byte & (1 << i) != 0);
+                *byte = (*byte & !(1 << i)) | ((bit as u8) << i);
+            }
+        }
+    }
+
+    /// Scramble the bytes of `data` in place.
+    ///
+    /// The bits of each byte are processed from LSB to MSB.
+    pub fn scramble_lsb_first(&mut self, data: &mut [u8]) {
+        for byte in data {
+            for i in 0..8 {
+                let bit = self.scramble_bit(*byte & (1 << i) != 0);
+                *byte = (*byte & !(1 << i)) | ((bit as u8) << i);
+            }
+        }
+    }
+}
+
+impl<const TAPS: u64> Default for Scrambler<TAPS> {
+    /// Create a new scrambler with all-zeros initial state.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const TAPS: u64> Descrambler<TAPS> {
+    /// Create a new descrambler with all-zeros initial state.
+    #[inline]
+    pub const fn new() -> Self {
+        Self::new_state(0)
+    }
+
+    /// Create a new descrambler with user specified initial state.
+    ///
+    /// Bit 0 of `state` is the most recently received bit.
+    #[inline]
+    pub const fn new_state(state: u64) -> Self {
+        Self { state }
+    }
+
+    /// Get the current state.
+    #[inline]
+    pub const fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Descramble one bit.
+    #[inline]
+    pub fn descramble_bit(&mut self, bit: bool) -> bool {
+        let out = bit ^ feedback::<TAPS>(self.state);
+        self.state = shift(self.state, bit);
+        out
+    }
+
+    /// Descramble the bytes of `data` in place.
+    ///
+    /// The bits of each byte are processed from MSB to LSB.
+    pub fn descramble(&mut self, data: &mut [u8]) {
+        for byte in data {
+            for i in (0..8).rev() {
+                let bit = self.descramble_bit(*byte & (1 << i) != 0);
+                *byte = (*byte & !(1 << i)) | ((bit as u8) << i);
+            }
+        }
+    }
+
+    /// Descramble the bytes of `data` in place.
+    ///
+    /// The bits of each byte are processed from LSB to MSB.
+    pub fn descramble_lsb_first(&mut self, data: &mut [u8]) {
+        for byte in data {
+            for i in 0..8 {
+                let bit = self.descramble_bit(*byte & (1 << i) != 0);
+                *byte = (*byte & !(1 << i)) | ((bit as u8) << i);
+            }
+        }
+    }
+}
+
+impl<const TAPS: u64> Default for Descrambler<TAPS> {
+    /// Create a new descrambler with all-zeros initial state.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn test_scrambler() {
+        // Scrambling a constant stream gives the LFSR sequence.
+        let mut s = Scrambler::<{ (1 << 6) | (1 << 5) }>::new_state(0x7F);
+        let mut data = [0_u8; 4];
+        s.scramble(&mut data);
+        assert_eq!(u32::from_be_bytes(data), 0x020C_28F2);
+
+        // Round trip.
+        let mut rng = Shr3::new_state(42);
+        let mut orig = [0_u8; 100];
+        rng.fill_bytes(&mut orig);
+        orig[50..].fill(0);
+        let mut data = orig;
+        let mut s = Scrambler64b66b::new_state(0xDEAD_BEEF);
+        s.scramble_lsb_first(&mut data);
+        assert!(data[50..].iter().filter(|x| **x == 0).count() < 5);
+        let mut d = Descrambler64b66b::new_state(0xDEAD_BEEF);
+        let mut rx = data;
+        d.descramble_lsb_first(&mut rx);
+        assert_eq!(rx, orig);
+
+        // Self synchronization with unknown state.
+        let mut d = Descrambler64b66b::new();
+        let mut rx = data;
+        d.descramble_lsb_first(&mut rx);
+        assert_ne!(rx[..8], orig[..8]);
+        assert_eq!(rx[8..], orig[8..]);
+
+        // Error multiplication.
+        let mut d = DescramblerX43::default();
+        let mut s = ScramblerX43::default();
+        let mut data = orig;
+        s.scramble(&mut data);
+        data[10] ^= 0x80;
+        d.descramble(&mut data);
+        let errors: u32 = data.iter().zip(orig).map(|(x, y)| (x ^ y).count_ones()).sum();
+        assert_eq!(errors, 2);
+        assert_eq!(data[10] ^ orig[10], 0x80);
+        assert_eq!(data[15] ^ orig[15], 0x10);
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,311 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Seed derivation.
+
+use crate::Shr3;
+
+/// Bijective 32 bit integer mixing function (MurmurHash3 finalizer).
+///
+/// Use this to condition weak seeds (e.g. timestamps or counters) before passing them to `Shr3::new_state()`.
+///
+/// Avalanche properties:
+/// Flipping any single input bit flips each output bit with a probability close to 50%.
+/// Therefore, adjacent inputs give unrelated outputs.
+/// The function is bijective. Different inputs always give different outputs.
+///
+/// *Note*: `mix32(0)` is 0.
+///
+/// ```
+///     use shr3::prelude::*;
+///     use shr3::mix32;
+///
+///     let boot_counter = 7;
+///     let mut shr3 = Shr3::new_state(mix32(boot_counter));
+/// ```
+pub const fn mix32(mut x: u32) -> u32 {
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x85EB_CA6B);
+    x ^= x >> 13;
+    x = x.wrapping_mul(0xC2B2_AE35);
+    x ^= x >> 16;
+    x
+}
+
+/// Fold the byte slice `data` into a well mixed 32 bit value.
+///
+/// Each little endian 32 bit word of `data` (the last one zero padded)
+/// is combined with the running value and mixed with `mix32()`.
+/// The length of `data` is mixed in last.
+/// Therefore, slices that only differ in trailing zero bytes give different results.
+const fn fold_bytes(init: u32, data: &[u8]) -> u32 {
+    let mut h = init;
+    let mut i = 0;
+    while i < data.len() {
+        let mut word = 0_u32;
+        let mut j = 0;
+        while j < 4 && i + j < data.len() {
+            word |= (data[i + j] as u32) << (j * 8);
+            j += 1;
+        }
+        h = mix32(h ^ word);
+        i += 4;
+    }
+    mix32(h ^ data.len() as u32)
+}
+
+/// Compute a 32 bit seed from the string `label` with the FNV-1a hash function.
+///
+/// This can be evaluated at compile time.
+/// It allows writing seeds as human readable labels.
+///
+/// ```
+///     use shr3::prelude::*;
+///     use shr3::seed_from_str;
+///
+///     const MOTOR_SEED: u32 = seed_from_str("motor-task");
+///     let mut shr3 = Shr3::new_state(MOTOR_SEED);
+/// ```
+pub const fn seed_from_str(label: &str) -> u32 {
+    let bytes = label.as_bytes();
+    let mut h = 0x811C_9DC5_u32;
+    let mut i = 0;
+    while i < bytes.len() {
+        h ^= bytes[i] as u32;
+        h = h.wrapping_mul(0x0100_0193);
+        i += 1;
+    }
+    h
+}
+
+impl Shr3 {
+    /// Create a new SHR3 instance seeded from the contents of the byte slice `data`.
+    ///
+    /// `data` can be of any length, e.g. a serial number, a MAC address or a calibration blob.
+    /// All bytes are folded into the 32 bit seed with an integer mixing function.
+    /// Each byte affects all bits of the seed.
+    ///
+    /// The same special state 0 handling as in `Shr3::new_state()` applies to the folded seed.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     let mac = [0x02, 0x00, 0x5E, 0x10, 0x00, 0x01];
+    ///     let mut shr3 = Shr3::seed_from_bytes(&mac);
+    ///     let x: u8 = shr3.get();
+    /// ```
+    pub const fn seed_from_bytes(data: &[u8]) -> Shr3 {
+        Shr3::new_state(fold_bytes(0, data))
+    }
+
+    /// Create a new SHR3 instance from a 64 bit seed.
+    ///
+    /// The 64 bits are mixed down to the 32 bit state with one PCG32 step,
+    /// exactly like the default implementation of `rand_core::SeedableRng::seed_from_u64()`.
+    /// Therefore, both give the identical generator.
+    /// The same special state 0 handling as in `Shr3::new_state()` applies.
+    pub const fn seed_from_u64(seed: u64) -> Shr3 {
+        const MUL: u64 = 6364136223846793005;
+        const INC: u64 = 11634580027462260723;
+        let state = seed.wrapping_mul(MUL).wrapping_add(INC);
+        let xorshifted = (((state >> 18) ^ state) >> 27) as u32;
+        let rot = (state >> 59) as u32;
+        Shr3::new_state(xorshifted.rotate_right(rot))
+    }
+
+    /// Create a new SHR3 instance seeded from a microcontroller unique device ID.
+    ///
+    /// `id` is the unique ID as exposed by the device (typically 96 or 128 bits).
+    /// `salt` separates the seeds of different applications or subsystems
+    /// on the same device.
+    ///
+    /// The ID is folded into the seed like in `Shr3::seed_from_bytes()`,
+    /// starting from the mixed salt.
+    /// Therefore, two units with adjacent IDs get unrelated seeds
+    /// and unrelated positions in the SHR3 cycle.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///     use shr3::seed_from_str;
+    ///
+    ///     let uid: [u8; 12] = [0x30, 0x00, 0x2B, 0x00, 0x11, 0x51, 0x33, 0x38, 0x37, 0x31, 0x39, 0x34];
+    ///     let mut shr3 = Shr3::seed_from_device_id(&uid, seed_from_str("radio-backoff"));
+    ///     let x: u8 = shr3.get();
+    /// ```
+    pub const fn seed_from_device_id(id: &[u8], salt: u32) -> Shr3 {
+        // The constant separates the seeds from those of `seed_from_bytes()`.
+        Shr3::new_state(fold_bytes(mix32(salt ^ 0x5EED_1D00), id))
+    }
+
+    /// Create a new SHR3 instance seeded from the operating system or hardware random number generator.
+    ///
+    /// 4 bytes are read with `getrandom::fill()`.
+    /// The same special state 0 handling as in `Shr3::new_state()` applies.
+    ///
+    /// The random stream is not reproducible.
+    /// This is only available with the `getrandom` feature.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     let mut shr3 = Shr3::from_entropy().unwrap();
+    ///     let x: u32 = shr3.get();
+    /// ```
+    #[cfg(feature="getrandom")]
+    pub fn from_entropy() -> Result<Shr3, getrandom::Error> {
+        let mut seed = [0; 4];
+        getrandom::fill(&mut seed)?;
+        Ok(Shr3::new_state(u32::from_le_bytes(seed)))
+    }
+
+    /// Create a new SHR3 instance seeded from the current system time.
+    ///
+    /// The nanoseconds since the Unix epoch are folded into the seed with `mix32()`.
+    /// The same special state 0 handling as in `Shr3::new_state()` applies.
+    ///
+    /// The random stream is not reproducible.
+    /// The seed is predictable. Use it for quick scripts and examples only.
+    /// This is only available with the `std` feature.
+    #[cfg(feature="std")]
+    pub fn from_time() -> Shr3 {
+        use std::time::{SystemTime, UNIX_EPOCH};
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        Shr3::new_state(fold_bytes(0, &nanos.to_le_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mix32() {
+        assert_eq!(mix32(0), 0);
+        assert_ne!(mix32(1), 1);
+        assert_ne!(mix32(1), mix32(2));
+
+        // Avalanche: Count the flips of each output bit per flipped input bit.
+        let mut x = 1_u32;
+        for in_bit in 0..32 {
+            let mut flips = [0_u32; 32];
+            for _ in 0..1000 {
+                x = crate::shr3(x);
+                let diff = mix32(x) ^ mix32(x ^ (1 << in_bit));
+                for (out_bit, f) in flips.iter_mut().enumerate() {
+                    *f += (diff >> out_bit) & 1;
+                }
+            }
+            assert!(flips.iter().all(|f| (400..600).contains(f)));
+        }
+    }
+
+    #[test]
+    fn test_seed_from_str() {
+        const SEED: u32 = seed_from_str("foobar");
+        assert_eq!(SEED, 0xBF9C_F968);
+        assert_eq!(seed_from_str(""), 0x811C_9DC5);
+        assert_eq!(seed_from_str("a"), 0xE40C_292C);
+        assert_ne!(seed_from_str("motor-task"), seed_from_str("audio-task"));
+    }
+
+    #[test]
+    fn test_seed_from_bytes() {
+        let a = Shr3::seed_from_bytes(b"");
+        let b = Shr3::seed_from_bytes(&[0]);
+        let c = Shr3::seed_from_bytes(&[0, 0, 0, 0]);
+        let d = Shr3::seed_from_bytes(&[0, 0, 0, 0, 0]);
+        let e = Shr3::seed_from_bytes(&[1]);
+        let f = Shr3::seed_from_bytes(&[0, 0, 0, 0, 1]);
+        let states = [a.state(), b.state(), c.state(), d.state(), e.state(), f.state()];
+        for (i, x) in states.iter().enumerate() {
+            assert_ne!(*x, 0);
+            assert!(!states[..i].contains(x));
+        }
+        assert_eq!(Shr3::seed_from_bytes(b"serial").state(),
+                   Shr3::seed_from_bytes(b"serial").state());
+
+        // Adjacent inputs give seeds that differ in about half of the bits.
+        let mut sum = 0;
+        for i in 0..1000_u32 {
+            let x = Shr3::seed_from_bytes(&i.to_le_bytes()).state();
+            let y = Shr3::seed_from_bytes(&(i + 1).to_le_bytes()).state();
+            sum += (x ^ y).count_ones();
+        }
+        assert!((15_000..17_000).contains(&sum));
+    }
+
+    #[test]
+    fn test_seed_from_u64() {
+        let a = Shr3::seed_from_u64(0);
+        let b = Shr3::seed_from_u64(1);
+        let c = Shr3::seed_from_u64(1 << 32);
+        assert_ne!(a.state(), b.state());
+        assert_ne!(b.state(), c.state());
+        assert_ne!(a.state(), c.state());
+        assert_eq!(Shr3::seed_from_u64(42).state(), Shr3::seed_from_u64(42).state());
+    }
+
+    #[cfg(feature="rand_core")]
+    #[test]
+    fn test_seed_from_u64_rand_core() {
+        for seed in [0, 1, 42, 1 << 32, u64::MAX] {
+            let a = Shr3::seed_from_u64(seed);
+            let b = <Shr3 as rand_core::SeedableRng>::seed_from_u64(seed);
+            assert_eq!(a.state(), b.state());
+        }
+    }
+
+    #[test]
+    fn test_seed_from_device_id() {
+        let mut id = [0x30, 0x00, 0x2B, 0x00, 0x11, 0x51, 0x33, 0x38, 0x37, 0x31, 0x39, 0x34];
+        let a = Shr3::seed_from_device_id(&id, 0).state();
+        assert_eq!(Shr3::seed_from_device_id(&id, 0).state(), a);
+        assert_ne!(Shr3::seed_from_device_id(&id, 1).state(), a);
+        assert_ne!(Shr3::seed_from_bytes(&id).state(), a);
+
+        // Adjacent IDs give seeds that differ in about half of the bits.
+        let mut sum = 0;
+        let mut prev = a;
+        for _ in 0..1000 {
+            id[11] = id[11].wrapping_add(1);
+            if id[11] == 0 {
+                id[10] += 1;
+            }
+            let x = Shr3::seed_from_device_id(&id, 0).state();
+            sum += (x ^ prev).count_ones();
+            prev = x;
+        }
+        assert!((15_000..17_000).contains(&sum));
+    }
+
+    #[cfg(feature="std")]
+    #[test]
+    fn test_from_time() {
+        let a = Shr3::from_time();
+        std::thread::sleep(std::time::Duration::from_millis(1));
+        let b = Shr3::from_time();
+        assert_ne!(a.state(), b.state());
+    }
+
+    #[cfg(feature="getrandom")]
+    #[test]
+    fn test_from_entropy() {
+        let a = Shr3::from_entropy().unwrap();
+        let b = Shr3::from_entropy().unwrap();
+        let c = Shr3::from_entropy().unwrap();
+        assert_ne!(a.state(), 0);
+        // Three equal seeds are practically impossible.
+        assert!(a.state() != b.state() || b.state() != c.state());
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,65 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! `serde` trait implementations for `Shr3`.
+//!
+//! The generator is serialized as its `u32` state.
+
+use crate::Shr3;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+impl Serialize for Shr3 {
+    /// Serialize the SHR3 state as `u32`.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.state().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Shr3 {
+    /// Deserialize the SHR3 state from `u32`.
+    ///
+    /// The same special state 0 handling as in `Shr3::new_state()` applies.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u32::deserialize(deserializer).map(Shr3::new_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shr3Ops;
+    use serde::de::IntoDeserializer;
+    use serde::de::value::{Error, U32Deserializer};
+    use serde_test::{Token, assert_de_tokens_error, assert_ser_tokens};
+
+    #[test]
+    fn test_serialize() {
+        let mut a = Shr3::new_state(42);
+        assert_ser_tokens(&a, &[Token::U32(42)]);
+        let _: u8 = a.get_bits(1);
+        assert_ser_tokens(&a, &[Token::U32(a.state())]);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let de: U32Deserializer<Error> = 42_u32.into_deserializer();
+        let mut a = Shr3::deserialize(de).unwrap();
+        let mut b = Shr3::new_state(42);
+        assert_eq!(Shr3Ops::<u32>::get(&mut a), Shr3Ops::<u32>::get(&mut b));
+
+        let de: U32Deserializer<Error> = 0_u32.into_deserializer();
+        let a = Shr3::deserialize(de).unwrap();
+        assert_eq!(a.state(), 0x7FFFFFFF);
+
+        assert_de_tokens_error::<Shr3>(&[Token::Str("x")],
+                                       "invalid type: string \"x\", expected u32");
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
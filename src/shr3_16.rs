@@ -0,0 +1,143 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! 16 bit xorshift generator.
+
+use crate::{BaseOps, Shr3Ops};
+use crate::arch;
+use core::num::Wrapping;
+use core::ops::{Add, BitOrAssign, ShlAssign, Sub};
+
+/// One round of the 16 bit xorshift shuffle function.
+///
+/// Xorshift16 with the full period triple (7, 9, 8).
+///
+/// On AVR this uses an optimized implementation without multiplications.
+pub fn shr3_16(state: u16) -> u16 {
+    #[cfg(all(target_arch="avr", not(feature="force-generic")))]
+    let state = arch::avr::shr3_16(state);
+
+    #[cfg(any(not(target_arch="avr"), feature="force-generic"))]
+    let state = arch::generic::shr3_16(state);
+
+    state
+}
+
+/// 16 bit xorshift generator register state.
+///
+/// This works exactly like `Shr3`, but with a 16 bit state and `shr3_16()` as shuffle function.
+/// The shuffle function is evaluated once per extracted random bit
+/// and the LSB of the state is extracted as output.
+///
+/// The generator is meant for very small microcontrollers with only a few dozen bytes of RAM.
+///
+/// *Note*: The period is very short.
+///         The generator will loop back to the beginning after `2**16 - 1 = 65535` iterations.
+///         That is only about 8 KiB of random bits.
+///
+/// This generator is *not* cryptographically secure!
+#[allow(non_camel_case_types)]
+pub struct Shr3_16 {
+    state: u16,
+}
+
+impl Shr3_16 {
+    /// Create a new instance with default initial `state = 1`.
+    #[inline]
+    pub const fn new() -> Shr3_16 {
+        Self::new_state(1)
+    }
+
+    /// Create a new instance with user specified initial state.
+    ///
+    /// Special state 0: The state must not be 0. If 0 is passed to this function,
+    ///                  then the state 0x7FFF is picked instead.
+    #[inline]
+    pub const fn new_state(state: u16) -> Shr3_16 {
+        Shr3_16 {
+            state: if state == 0 { 0x7FFF } else { state },
+        }
+    }
+
+    /// Get the current state.
+    #[inline]
+    pub const fn state(&self) -> u16 {
+        self.state
+    }
+}
+
+impl Default for Shr3_16 {
+    /// Create a new instance with default initial `state = 1`.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Shr3Ops<T> for Shr3_16
+    where T: BaseOps + PartialOrd,
+          T::U: BaseOps,
+          Wrapping<T::U>: Sub<Output=Wrapping<T::U>> + Add<Output=Wrapping<T::U>> + PartialOrd + ShlAssign<usize> + BitOrAssign,
+{
+    #[inline]
+    fn get_bits(&mut self, bitcount: u8) -> T {
+        T::from_unsigned(Shr3Ops::<T>::get_bits_unsigned(self, bitcount))
+    }
+
+    fn get_bits_unsigned(&mut self, bitcount: u8) -> Wrapping<T::U> {
+        debug_assert!(bitcount <= T::NUMBITS);
+        let mut ret = Wrapping(T::U::from_u8(0));
+        for _ in 0..bitcount {
+            self.state = shr3_16(self.state);
+            ret <<= 1;
+            ret |= Wrapping(T::U::from_u8(self.state as u8 & 1));
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shr3_16() {
+        assert_eq!(shr3_16(1), 33153);
+        assert_eq!(shr3_16(0x1234), 14384);
+        assert_eq!(shr3_16(0), 0);
+
+        let mut state = 1;
+        let mut period = 0;
+        loop {
+            state = shr3_16(state);
+            period += 1;
+            if state == 1 {
+                break;
+            }
+        }
+        assert_eq!(period, 65535);
+
+        let mut a = Shr3_16::new_state(42);
+        let x: u16 = a.get();
+        assert_eq!(x, 0x3884);
+
+        let a = Shr3_16::new_state(0);
+        assert_eq!(a.state(), 0x7FFF);
+        let a: Shr3_16 = Default::default();
+        assert_eq!(a.state(), 1);
+
+        let mut a = Shr3_16::new_state(42);
+        for _ in 0..1000 {
+            let x: u8 = a.get_range(3..9);
+            assert!((3..9).contains(&x));
+        }
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
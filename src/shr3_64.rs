@@ -0,0 +1,123 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! 64 bit xorshift generator.
+
+use crate::{BaseOps, Shr3Ops};
+use core::num::Wrapping;
+use core::ops::{Add, BitOrAssign, ShlAssign, Sub};
+
+/// One round of the 64 bit xorshift shuffle function.
+///
+/// Xorshift64 with the full period triple (13, 7, 17)
+/// from "Xorshift RNGs" by George Marsaglia (2003).
+#[inline]
+pub const fn shr3_64(mut state: u64) -> u64 {
+    state ^= state << 13;
+    state ^= state >> 7;
+    state ^= state << 17;
+    state
+}
+
+/// 64 bit xorshift generator register state.
+///
+/// This works exactly like `Shr3`, but with a 64 bit state and `shr3_64()` as shuffle function.
+/// The shuffle function is evaluated once per extracted random bit
+/// and the LSB of the state is extracted as output.
+///
+/// The generator will loop back to the beginning after `2**64 - 1` iterations.
+///
+/// This generator is *not* cryptographically secure!
+#[allow(non_camel_case_types)]
+pub struct Shr3_64 {
+    state: u64,
+}
+
+impl Shr3_64 {
+    /// Create a new instance with default initial `state = 1`.
+    #[inline]
+    pub const fn new() -> Shr3_64 {
+        Self::new_state(1)
+    }
+
+    /// Create a new instance with user specified initial state.
+    ///
+    /// Special state 0: The state must not be 0. If 0 is passed to this function,
+    ///                  then the state 0x7FFFFFFFFFFFFFFF is picked instead.
+    #[inline]
+    pub const fn new_state(state: u64) -> Shr3_64 {
+        Shr3_64 {
+            state: if state == 0 { 0x7FFFFFFFFFFFFFFF } else { state },
+        }
+    }
+
+    /// Get the current state.
+    #[inline]
+    pub const fn state(&self) -> u64 {
+        self.state
+    }
+}
+
+impl Default for Shr3_64 {
+    /// Create a new instance with default initial `state = 1`.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Shr3Ops<T> for Shr3_64
+    where T: BaseOps + PartialOrd,
+          T::U: BaseOps,
+          Wrapping<T::U>: Sub<Output=Wrapping<T::U>> + Add<Output=Wrapping<T::U>> + PartialOrd + ShlAssign<usize> + BitOrAssign,
+{
+    #[inline]
+    fn get_bits(&mut self, bitcount: u8) -> T {
+        T::from_unsigned(Shr3Ops::<T>::get_bits_unsigned(self, bitcount))
+    }
+
+    fn get_bits_unsigned(&mut self, bitcount: u8) -> Wrapping<T::U> {
+        debug_assert!(bitcount <= T::NUMBITS);
+        let mut ret = Wrapping(T::U::from_u8(0));
+        for _ in 0..bitcount {
+            self.state = shr3_64(self.state);
+            ret <<= 1;
+            ret |= Wrapping(T::U::from_u8(self.state as u8 & 1));
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shr3_64() {
+        assert_eq!(shr3_64(88172645463325252), 8748534153485358512);
+        assert_eq!(shr3_64(0), 0);
+
+        let mut a = Shr3_64::new_state(42);
+        let x: u16 = a.get();
+        assert_eq!(x, 0x466F);
+
+        let a = Shr3_64::new_state(0);
+        assert_eq!(a.state(), 0x7FFFFFFFFFFFFFFF);
+        let a: Shr3_64 = Default::default();
+        assert_eq!(a.state(), 1);
+
+        let mut a = Shr3_64::new_state(42);
+        for _ in 0..1000 {
+            let x: i32 = a.get_range(-60..170);
+            assert!((-60..170).contains(&x));
+        }
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,297 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Raw and persistent SHR3 state storage.
+
+use crate::Shr3;
+
+/// Raw SHR3 state for placing into persistent memory.
+///
+/// This is a plain `u32` with the same memory layout as `u32`.
+/// Every bit pattern is valid, including all zeros.
+/// A zero state is fixed up on conversion to `Shr3` like in `Shr3::new_state()`.
+///
+/// If the `bytemuck` feature is enabled, then this type implements
+/// `bytemuck::Pod` and `bytemuck::Zeroable`.
+/// That allows placing it into memory-mapped retention RAM structures without unsafe casts.
+///
+/// ```
+///     use shr3::prelude::*;
+///     use shr3::Shr3State;
+///
+///     let mut shr3 = Shr3::new_state(42);
+///     let retained = Shr3State::from(&shr3);
+///     let x: u16 = shr3.get();
+///     let mut shr3 = Shr3::from(retained);
+///     let y: u16 = shr3.get();
+///     assert_eq!(x, y);
+/// ```
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Shr3State(pub u32);
+
+impl From<&Shr3> for Shr3State {
+    #[inline]
+    fn from(shr3: &Shr3) -> Self {
+        Shr3State(shr3.state())
+    }
+}
+
+impl From<Shr3State> for Shr3 {
+    /// The same special state 0 handling as in `Shr3::new_state()` applies.
+    #[inline]
+    fn from(state: Shr3State) -> Self {
+        Shr3::new_state(state.0)
+    }
+}
+
+// SAFETY: Shr3State is a repr(transparent) wrapper around u32.
+#[cfg(feature="bytemuck")]
+unsafe impl bytemuck::Zeroable for Shr3State {
+}
+
+// SAFETY: Shr3State is a repr(transparent) wrapper around u32.
+//         All bit patterns are valid.
+#[cfg(feature="bytemuck")]
+unsafe impl bytemuck::Pod for Shr3State {
+}
+
+/// CRC-32 (IEEE 802.3, reflected polynomial `0xEDB88320`) of `data`.
+const fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0_u32;
+    let mut i = 0;
+    while i < data.len() {
+        crc ^= data[i] as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        i += 1;
+    }
+    !crc
+}
+
+/// CRC protected SHR3 state record for persistent memory such as EEPROM or flash.
+///
+/// The record consists of the SHR3 state, a generation counter and a CRC-32
+/// over both, in little endian byte order (`PersistentState::SIZE` bytes).
+/// The generation counter is incremented on each `update()`.
+///
+/// # Recovery
+///
+/// A record can be corrupted, if power fails during the write to the persistent memory.
+/// Corrupted and erased records are detected by `validate()` and `load()`.
+///
+/// Use two storage slots to always have a valid record:
+/// Write each stored record to the slot `generation() % 2`
+/// and read both slots back with `load_newest()` on startup.
+/// If neither slot contains a valid record, then start with a new generator
+/// (e.g. seeded from a device ID) and `PersistentState::new()`.
+///
+/// ```
+///     use shr3::prelude::*;
+///     use shr3::PersistentState;
+///
+///     let mut eeprom = [[0xFF; PersistentState::SIZE]; 2];
+///
+///     let (mut shr3, mut record) = match PersistentState::load_newest(&eeprom[0], &eeprom[1]) {
+///         Some(record) => (record.shr3(), record),
+///         None => {
+///             let shr3 = Shr3::new_state(42);
+///             let record = PersistentState::new(&shr3);
+///             (shr3, record)
+///         }
+///     };
+///
+///     let _: u32 = shr3.get();
+///     record.update(&shr3);
+///     eeprom[record.generation() as usize % 2] = record.store();
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PersistentState {
+    state: u32,
+    generation: u32,
+}
+
+impl PersistentState {
+    /// Size of the stored record in bytes.
+    pub const SIZE: usize = 12;
+
+    /// Create a new record of generation 0 with the state of `shr3`.
+    #[inline]
+    pub const fn new(shr3: &Shr3) -> Self {
+        Self {
+            state: shr3.state(),
+            generation: 0,
+        }
+    }
+
+    /// Replace the state with the state of `shr3` and increment the generation counter.
+    #[inline]
+    pub fn update(&mut self, shr3: &Shr3) {
+        self.state = shr3.state();
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Get the generation counter.
+    #[inline]
+    pub const fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Create a new generator from the stored state.
+    #[inline]
+    pub const fn shr3(&self) -> Shr3 {
+        Shr3::new_state(self.state)
+    }
+
+    /// Serialize the record with CRC for writing it to persistent memory.
+    pub fn store(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        bytes[0..4].copy_from_slice(&self.state.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.generation.to_le_bytes());
+        let crc = crc32(&bytes[0..8]);
+        bytes[8..12].copy_from_slice(&crc.to_le_bytes());
+        bytes
+    }
+
+    /// Check whether `bytes` contains a record with a correct CRC.
+    pub fn validate(bytes: &[u8; Self::SIZE]) -> bool {
+        let crc = u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]);
+        crc32(&bytes[0..8]) == crc
+    }
+
+    /// Deserialize a record that was read from persistent memory.
+    ///
+    /// Returns `None`, if the record is corrupted.
+    pub fn load(bytes: &[u8; Self::SIZE]) -> Option<Self> {
+        if Self::validate(bytes) {
+            Some(Self {
+                state: u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+                generation: u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Deserialize the newest valid record from two storage slots.
+    ///
+    /// Returns `None`, if both records are corrupted.
+    /// The generation counters are compared with wrap-around.
+    pub fn load_newest(slot_a: &[u8; Self::SIZE], slot_b: &[u8; Self::SIZE]) -> Option<Self> {
+        match (Self::load(slot_a), Self::load(slot_b)) {
+            (Some(a), Some(b)) => {
+                if (b.generation.wrapping_sub(a.generation) as i32) > 0 {
+                    Some(b)
+                } else {
+                    Some(a)
+                }
+            }
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shr3Ops;
+
+    #[test]
+    fn test_state() {
+        let mut a = Shr3::new_state(42);
+        let _: u8 = a.get();
+        let s = Shr3State::from(&a);
+        assert_eq!(s.0, a.state());
+        let mut b = Shr3::from(s);
+        assert_eq!(Shr3Ops::<u32>::get(&mut a), Shr3Ops::<u32>::get(&mut b));
+
+        let b = Shr3::from(Shr3State::default());
+        assert_eq!(b.state(), 0x7FFFFFFF);
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_persistent() {
+        let mut a = Shr3::new_state(42);
+        let mut rec = PersistentState::new(&a);
+        assert_eq!(rec.generation(), 0);
+        let bytes = rec.store();
+        assert_eq!(bytes[0..8], [42, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(PersistentState::validate(&bytes));
+        assert_eq!(PersistentState::load(&bytes), Some(rec));
+
+        let _: u32 = a.get();
+        rec.update(&a);
+        assert_eq!(rec.generation(), 1);
+        let mut b = PersistentState::load(&rec.store()).unwrap().shr3();
+        assert_eq!(Shr3Ops::<u32>::get(&mut a), Shr3Ops::<u32>::get(&mut b));
+
+        // Corruption detection.
+        let good = rec.store();
+        for i in 0..PersistentState::SIZE {
+            for bit in 0..8 {
+                let mut bad = good;
+                bad[i] ^= 1 << bit;
+                assert!(!PersistentState::validate(&bad));
+                assert_eq!(PersistentState::load(&bad), None);
+            }
+        }
+        assert_eq!(PersistentState::load(&[0xFF; PersistentState::SIZE]), None);
+        assert_eq!(PersistentState::load(&[0x00; PersistentState::SIZE]), None);
+    }
+
+    #[test]
+    fn test_persistent_newest() {
+        let a = Shr3::new_state(42);
+        let mut rec = PersistentState::new(&a);
+        let old = rec.store();
+        rec.update(&Shr3::new_state(43));
+        let new = rec.store();
+        let bad = [0xFF; PersistentState::SIZE];
+        assert_eq!(PersistentState::load_newest(&old, &new).unwrap().generation(), 1);
+        assert_eq!(PersistentState::load_newest(&new, &old).unwrap().generation(), 1);
+        assert_eq!(PersistentState::load_newest(&bad, &old).unwrap().generation(), 0);
+        assert_eq!(PersistentState::load_newest(&new, &bad).unwrap().generation(), 1);
+        assert_eq!(PersistentState::load_newest(&bad, &bad), None);
+
+        // Generation counter wrap-around.
+        let mut rec = PersistentState { state: 1, generation: u32::MAX };
+        let old = rec.store();
+        rec.update(&a);
+        let new = rec.store();
+        let newest = PersistentState::load_newest(&old, &new).unwrap();
+        assert_eq!(newest.generation(), 0);
+        assert_eq!(newest.shr3().state(), 42);
+    }
+
+    #[cfg(feature="bytemuck")]
+    #[test]
+    fn test_bytemuck() {
+        let mut ram = [Shr3State::default(); 2];
+        ram[1] = Shr3State::from(&Shr3::new_state(0x12345678));
+        let bytes: &[u8] = bytemuck::cast_slice(&ram);
+        assert_eq!(bytes.len(), 8);
+        assert_eq!(bytes[4..], 0x12345678_u32.to_ne_bytes());
+
+        let s: Shr3State = bytemuck::Zeroable::zeroed();
+        assert_eq!(s, Shr3State(0));
+        let s: &Shr3State = bytemuck::from_bytes(&bytes[4..]);
+        assert_eq!(Shr3::from(*s).state(), 0x12345678);
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
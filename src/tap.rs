@@ -0,0 +1,88 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Configurable output tap.
+
+use crate::Xorshift;
+
+/// Output tap of `Xorshift` and `Shr3Tap`: Emit the parity of all 32 state bits instead of a single bit.
+pub const TAP_PARITY: u32 = 32;
+
+/// Extract the output bit selected by `TAP` from the SHR3 state.
+#[inline]
+pub(crate) const fn tap_bit<const TAP: u32>(state: u32) -> u8 {
+    if TAP == TAP_PARITY {
+        (state.count_ones() & 1) as u8
+    } else {
+        (state >> TAP) as u8 & 1
+    }
+}
+
+/// SHR3 generator with a configurable output tap.
+///
+/// This is the SHR3 instance of `Xorshift`, but the output bit of each round is selected by `TAP`:
+///
+/// * `0` to `31`: The state bit number `TAP` is extracted. `Shr3Tap<0>` is identical to `Shr3`.
+/// * `TAP_PARITY`: The parity of all state bits is extracted.
+///
+/// The LSB of the SHR3 state has known statistical weaknesses.
+/// The high bits and the parity are better mixed. The cost per round is the same (or one popcount).
+///
+/// The additional methods of `Shr3` (e.g. `Shr3::discard()`) are only available for `Shr3Tap<0>`.
+///
+/// ```
+///     use shr3::prelude::*;
+///     use shr3::{Shr3Tap, TAP_PARITY};
+///
+///     let mut msb = Shr3Tap::<31>::new_state(42);
+///     let mut parity = Shr3Tap::<TAP_PARITY>::new_state(42);
+///     let x: u16 = msb.get();
+///     let y: u16 = parity.get();
+///     assert_eq!(x, 0x5BB8);
+///     assert_eq!(y, 0x41B7);
+/// ```
+///
+/// This generator is *not* cryptographically secure!
+pub type Shr3Tap<const TAP: u32> = Xorshift<13, 17, 5, TAP>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Shr3, Shr3Ops};
+
+    #[test]
+    fn test_tap() {
+        assert_eq!(tap_bit::<0>(0x8000_0001), 1);
+        assert_eq!(tap_bit::<1>(0x8000_0001), 0);
+        assert_eq!(tap_bit::<31>(0x8000_0001), 1);
+        assert_eq!(tap_bit::<TAP_PARITY>(0x8000_0001), 0);
+        assert_eq!(tap_bit::<TAP_PARITY>(0x8000_0101), 1);
+
+        let mut a = Shr3Tap::<0>::new_state(42);
+        let mut b = Shr3::new_state(42);
+        for _ in 0..100 {
+            assert_eq!(Shr3Ops::<u32>::get(&mut a), Shr3Ops::<u32>::get(&mut b));
+        }
+        assert_eq!(a.state(), b.state());
+
+        let mut a = Shr3Tap::<31>::new_state(42);
+        let x: u16 = a.get();
+        assert_eq!(x, 0x5BB8);
+        let mut a = Shr3Tap::<TAP_PARITY>::new_state(42);
+        let x: u16 = a.get();
+        assert_eq!(x, 0x41B7);
+
+        let a = Shr3Tap::<7>::new_state(0);
+        assert_eq!(a.state(), 0x7FFFFFFF);
+        let a: Shr3Tap<7> = Default::default();
+        assert_eq!(a.state(), 1);
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,132 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Taus88 combined Tausworthe generator.
+
+use crate::wordbits::{WordBits, impl_shr3_ops_words};
+
+/// Default initial state.
+const DEFAULT_STATE: [u32; 3] = [12345, 12345, 12345];
+
+/// The lowest valid value of each state component.
+const MIN_STATE: [u32; 3] = [2, 8, 16];
+
+/// One step of a Tausworthe component generator.
+#[inline]
+const fn taus_step(s: u32, q: u32, shift: u32, mask: u32, k: u32) -> u32 {
+    let b = ((s << q) ^ s) >> shift;
+    ((s & mask) << k) ^ b
+}
+
+/// Taus88 combined Tausworthe generator.
+///
+/// Algorithm from "Maximally equidistributed combined Tausworthe generators"
+/// by Pierre L'Ecuyer (1996).
+/// It combines three LFSR components with the periods `2**31 - 1`, `2**29 - 1` and `2**28 - 1`.
+/// The combined generator has a period of approximately `2**88`.
+///
+/// The shuffle function produces one 32 bit output word per step.
+/// `Shr3Ops` extracts the bits of each output word from MSB to LSB.
+/// Therefore, extracting a `u32` at a word boundary returns the unchanged output word.
+///
+/// This generator is *not* cryptographically secure!
+pub struct Taus88 {
+    state: [u32; 3],
+    bits: WordBits,
+}
+
+impl Taus88 {
+    /// Create a new instance with the default initial state `[12345, 12345, 12345]`.
+    #[inline]
+    pub const fn new() -> Taus88 {
+        Self::new_state(DEFAULT_STATE)
+    }
+
+    /// Create a new instance with user specified initial state `[s1, s2, s3]`.
+    ///
+    /// Special states: The state components must be `s1 >= 2`, `s2 >= 8` and `s3 >= 16`.
+    ///                 If a smaller value is passed for one of them,
+    ///                 then its default initial state is picked instead.
+    #[inline]
+    pub const fn new_state(state: [u32; 3]) -> Taus88 {
+        let [s1, s2, s3] = state;
+        Taus88 {
+            state: [
+                if s1 < MIN_STATE[0] { DEFAULT_STATE[0] } else { s1 },
+                if s2 < MIN_STATE[1] { DEFAULT_STATE[1] } else { s2 },
+                if s3 < MIN_STATE[2] { DEFAULT_STATE[2] } else { s3 },
+            ],
+            bits: WordBits::new(),
+        }
+    }
+
+    /// Get the current state `[s1, s2, s3]`.
+    ///
+    /// Bits of the current output word that have not been extracted yet are not part of the state.
+    #[inline]
+    pub const fn state(&self) -> [u32; 3] {
+        self.state
+    }
+
+    /// Advance the generator by one step and get the 32 bit output word.
+    ///
+    /// This bypasses the bit extraction of `Shr3Ops`.
+    #[inline]
+    pub fn next_u32(&mut self) -> u32 {
+        let [s1, s2, s3] = self.state;
+        let s1 = taus_step(s1, 13, 19, 0xFFFF_FFFE, 12);
+        let s2 = taus_step(s2, 2, 25, 0xFFFF_FFF8, 4);
+        let s3 = taus_step(s3, 3, 11, 0xFFFF_FFF0, 17);
+        self.state = [s1, s2, s3];
+        s1 ^ s2 ^ s3
+    }
+}
+
+impl Default for Taus88 {
+    /// Create a new instance with the default initial state.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl_shr3_ops_words!(Taus88);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shr3Ops;
+
+    #[test]
+    fn test_taus88() {
+        let mut a = Taus88::new();
+        assert_eq!(a.next_u32(), 0x6360_8376);
+        assert_eq!(a.next_u32(), 0x3850_5A63);
+        assert_eq!(a.next_u32(), 0x1BE5_D6D9);
+
+        let mut a = Taus88::new();
+        let x: u32 = a.get();
+        assert_eq!(x, 0x6360_8376);
+        let x: u16 = a.get();
+        assert_eq!(x, 0x3850);
+
+        let a = Taus88::new_state([1, 7, 15]);
+        assert_eq!(a.state(), DEFAULT_STATE);
+        let a = Taus88::new_state([2, 8, 16]);
+        assert_eq!(a.state(), [2, 8, 16]);
+
+        let mut a = Taus88::default();
+        for _ in 0..1000 {
+            let x: i16 = a.get_minmax(-5, 5);
+            assert!((-5..=5).contains(&x));
+        }
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,90 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Extraction of tuples.
+
+use crate::{BaseOps, Shr3Ops};
+use core::num::Wrapping;
+use core::ops::{Add, Sub};
+
+/// Tuple types that can be extracted from the generator `R`.
+///
+/// This is implemented for tuples of up to 8 elements.
+/// Each element can be of any type that is supported by `Shr3Ops`.
+///
+/// See `Shr3::get_tuple()`.
+pub trait Shr3Tuple<R>: Sized {
+    /// Extract all elements of the tuple.
+    ///
+    /// The elements are extracted like `Shr3Ops::get()` from left to right.
+    fn extract(rng: &mut R) -> Self;
+}
+
+macro_rules! impl_shr3_tuple {
+    ($( ($($t:ident),+) ),*) => {
+        $(
+            impl<R, $($t),+> Shr3Tuple<R> for ($($t,)+)
+                where $(
+                    R: Shr3Ops<$t>,
+                    $t: BaseOps + PartialOrd,
+                    $t::U: BaseOps,
+                    Wrapping<$t::U>: Sub<Output=Wrapping<$t::U>> + Add<Output=Wrapping<$t::U>> + PartialOrd,
+                )+
+            {
+                #[inline]
+                fn extract(rng: &mut R) -> Self {
+                    // Tuple expressions are evaluated from left to right.
+                    ($(Shr3Ops::<$t>::get(rng),)+)
+                }
+            }
+        )*
+    }
+}
+
+impl_shr3_tuple!(
+    (A),
+    (A, B),
+    (A, B, C),
+    (A, B, C, D),
+    (A, B, C, D, E),
+    (A, B, C, D, E, F),
+    (A, B, C, D, E, F, G),
+    (A, B, C, D, E, F, G, H)
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::{Shr3, Shr3Ops};
+
+    #[test]
+    fn test_tuple() {
+        let mut a = Shr3::new_state(42);
+        let (b, c): (u8, u16) = a.get_tuple();
+        assert_eq!(b, 0x20);
+        assert_eq!(c, 0xD32C);
+
+        let mut a = Shr3::new_state(42);
+        let (b,): (u16,) = a.get_tuple();
+        assert_eq!(b, 0x20D3);
+
+        let mut a = Shr3::new_state(42);
+        let mut b = Shr3::new_state(42);
+        let x: (i8, u32, char, u64, i16, u8, usize, i64) = a.get_tuple();
+        assert_eq!(x.0, Shr3Ops::<i8>::get(&mut b));
+        assert_eq!(x.1, Shr3Ops::<u32>::get(&mut b));
+        assert_eq!(x.2, Shr3Ops::<char>::get(&mut b));
+        assert_eq!(x.3, Shr3Ops::<u64>::get(&mut b));
+        assert_eq!(x.4, Shr3Ops::<i16>::get(&mut b));
+        assert_eq!(x.5, Shr3Ops::<u8>::get(&mut b));
+        assert_eq!(x.6, Shr3Ops::<usize>::get(&mut b));
+        assert_eq!(x.7, Shr3Ops::<i64>::get(&mut b));
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,90 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Builders for `alloc::vec::Vec`.
+
+use crate::{BaseOps, Shr3, Shr3Ops};
+use alloc::vec::Vec;
+use core::num::Wrapping;
+use core::ops::{Add, RangeBounds, Sub};
+
+impl Shr3 {
+    /// Create a `Vec` of `len` random values.
+    ///
+    /// Each value is extracted like `Shr3Ops::get()`.
+    /// This is identical to `self.iter().take(len).collect()`.
+    ///
+    /// ```
+    ///     use shr3::prelude::*;
+    ///
+    ///     let mut shr3 = Shr3::new_state(42);
+    ///     let v: Vec<u16> = shr3.random_vec(2);
+    ///     assert_eq!(v, [0x20D3, 0x2C5C]);
+    /// ```
+    pub fn random_vec<T>(&mut self, len: usize) -> Vec<T>
+        where Shr3: Shr3Ops<T>,
+              T: BaseOps + PartialOrd,
+              T::U: BaseOps,
+              Wrapping<T::U>: Sub<Output=Wrapping<T::U>> + Add<Output=Wrapping<T::U>> + PartialOrd,
+    {
+        self.iter().take(len).collect()
+    }
+
+    /// Create a `Vec` of `len` random values in the given `range`.
+    ///
+    /// Each value is extracted like `Shr3Ops::get_range()`.
+    pub fn random_vec_range<T>(&mut self, len: usize, range: impl RangeBounds<T>) -> Vec<T>
+        where Shr3: Shr3Ops<T>,
+              T: BaseOps + PartialOrd,
+              T::U: BaseOps,
+              Wrapping<T::U>: Sub<Output=Wrapping<T::U>> + Add<Output=Wrapping<T::U>> + PartialOrd,
+    {
+        let mut vec = Vec::with_capacity(len);
+        for _ in 0..len {
+            vec.push(self.get_range((range.start_bound(), range.end_bound())));
+        }
+        vec
+    }
+
+    /// Create a `Vec` of `len` random bytes.
+    ///
+    /// The extracted random stream is identical to `fill_bytes()`.
+    pub fn random_bytes(&mut self, len: usize) -> Vec<u8> {
+        let mut vec = alloc::vec![0; len];
+        self.fill_bytes(&mut vec);
+        vec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_vec() {
+        let mut a = Shr3::new_state(42);
+        let v: Vec<u16> = a.random_vec(6);
+        assert_eq!(v, [0x20D3, 0x2C5C, 0x2A17, 0xD3C5, 0xAF08, 0x9E5B]);
+        let v: Vec<i8> = a.random_vec(0);
+        assert!(v.is_empty());
+
+        let mut a = Shr3::new_state(42);
+        let v: Vec<u8> = a.iter().take(3).collect();
+        assert_eq!(v, [0x20, 0xD3, 0x2C]);
+
+        let v: Vec<i32> = a.random_vec_range(1000, -60..170);
+        assert_eq!(v.len(), 1000);
+        assert!(v.iter().all(|x| (-60..170).contains(x)));
+
+        let mut a = Shr3::new_state(42);
+        assert_eq!(a.random_bytes(5), [0x20, 0xD3, 0x2C, 0x5C, 0x2A]);
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
@@ -0,0 +1,126 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! SHR3 combined with a Weyl sequence.
+
+use crate::shr3;
+use crate::wordbits::{WordBits, impl_shr3_ops_words};
+
+/// Increment of the Weyl sequence (the 32 bit golden ratio).
+///
+/// The increment is odd. Therefore, the Weyl sequence has the full period `2**32`.
+const WEYL_INCREMENT: u32 = 0x9E37_79B9;
+
+/// SHR3 generator combined with a Weyl sequence.
+///
+/// Each step does one `shr3()` round on the SHR3 state
+/// and adds the constant increment `0x9E3779B9` to the Weyl counter.
+/// The output word is the sum of the SHR3 state and the Weyl counter.
+///
+/// The addition breaks up the purely linear structure of the SHR3 output.
+/// The SHR3 period `2**32 - 1` and the Weyl period `2**32` are coprime.
+/// Therefore, the combined generator has a period of `(2**32 - 1) * 2**32`, approximately `2**64`.
+///
+/// The shuffle function produces one 32 bit output word per step.
+/// `Shr3Ops` extracts the bits of each output word from MSB to LSB.
+/// Therefore, extracting a `u32` at a word boundary returns the unchanged output word.
+///
+/// This generator is *not* cryptographically secure!
+pub struct Shr3Weyl {
+    state: u32,
+    weyl: u32,
+    bits: WordBits,
+}
+
+impl Shr3Weyl {
+    /// Create a new instance with default initial SHR3 `state = 1` and Weyl counter 0.
+    #[inline]
+    pub const fn new() -> Shr3Weyl {
+        Self::new_state(1, 0)
+    }
+
+    /// Create a new instance with user specified initial SHR3 state and Weyl counter.
+    ///
+    /// Special state 0: The SHR3 state must not be 0. If 0 is passed to this function,
+    ///                  then the state 0x7FFFFFFF is picked instead.
+    ///                  The Weyl counter can have any value.
+    #[inline]
+    pub const fn new_state(state: u32, weyl: u32) -> Shr3Weyl {
+        Shr3Weyl {
+            state: if state == 0 { 0x7FFFFFFF } else { state },
+            weyl,
+            bits: WordBits::new(),
+        }
+    }
+
+    /// Get the current SHR3 state and Weyl counter.
+    ///
+    /// Bits of the current output word that have not been extracted yet are not part of the state.
+    #[inline]
+    pub const fn state(&self) -> (u32, u32) {
+        (self.state, self.weyl)
+    }
+
+    /// Advance the generator by one step and get the 32 bit output word.
+    ///
+    /// This bypasses the bit extraction of `Shr3Ops`.
+    #[inline]
+    pub fn next_u32(&mut self) -> u32 {
+        self.state = shr3(self.state);
+        self.weyl = self.weyl.wrapping_add(WEYL_INCREMENT);
+        self.state.wrapping_add(self.weyl)
+    }
+}
+
+impl Default for Shr3Weyl {
+    /// Create a new instance with the default initial state.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl_shr3_ops_words!(Shr3Weyl);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shr3Ops;
+
+    #[test]
+    fn test_shr3_weyl() {
+        let mut a = Shr3Weyl::new();
+        assert_eq!(a.next_u32(), 0x9E3B_99DA);
+        assert_eq!(a.next_u32(), 0x4076_F973);
+        assert_eq!(a.next_u32(), 0x7873_15F0);
+        assert_eq!(a.state().1, WEYL_INCREMENT.wrapping_mul(3));
+
+        let mut a = Shr3Weyl::new();
+        let x: u32 = a.get();
+        assert_eq!(x, 0x9E3B_99DA);
+        let x: u8 = a.get();
+        assert_eq!(x, 0x40);
+
+        // The SHR3 state follows the plain SHR3 generator.
+        let mut a = Shr3Weyl::new_state(42, 0);
+        a.next_u32();
+        assert_eq!(a.state().0, shr3(42));
+
+        let a = Shr3Weyl::new_state(0, 5);
+        assert_eq!(a.state(), (0x7FFFFFFF, 5));
+
+        let mut a = Shr3Weyl::default();
+        for _ in 0..1000 {
+            let x: u64 = a.get_max(1_000_000_000_000);
+            assert!(x <= 1_000_000_000_000);
+        }
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
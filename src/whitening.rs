@@ -0,0 +1,239 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Data whitening for radio transceivers.
+
+/// PN9 data whitening with the polynomial `x**9 + x**5 + 1`.
+///
+/// This is the whitening of the TI CC1101 family and of the Semtech SX127x family
+/// in FSK mode (CCITT whitening). The LFSR is seeded with `0x1FF`.
+/// Each data byte is XORed with the lower 8 bits of the LFSR.
+/// Then the LFSR is advanced by 8 steps.
+/// The whitening sequence starts with `0xFF, 0xE1, 0x1D, 0x9A, 0xED, ...`
+///
+/// Whitening and de-whitening are the same operation.
+/// Start a new instance for each packet.
+///
+/// ```
+///     use shr3::Pn9Whitening;
+///
+///     let mut packet = [0x00, 0x01, 0x02, 0x03];
+///     Pn9Whitening::new().whiten(&mut packet);
+///     assert_eq!(packet, [0xFF, 0xE0, 0x1F, 0x99]);
+///     Pn9Whitening::new().dewhiten(&mut packet);
+///     assert_eq!(packet, [0x00, 0x01, 0x02, 0x03]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pn9Whitening {
+    state: u16,
+}
+
+impl Pn9Whitening {
+    /// Create a new instance with the standard seed `0x1FF`.
+    #[inline]
+    pub const fn new() -> Self {
+        Self::new_state(0x1FF)
+    }
+
+    /// Create a new instance with user specified 9 bit seed.
+    ///
+    /// Special state 0: The state must not be 0. If 0 is passed to this function,
+    ///                  then the standard seed `0x1FF` is picked instead.
+    #[inline]
+    pub const fn new_state(state: u16) -> Self {
+        let state = state & 0x1FF;
+        Self {
+            state: if state == 0 { 0x1FF } else { state },
+        }
+    }
+
+    /// Get the current LFSR state.
+    #[inline]
+    pub const fn state(&self) -> u16 {
+        self.state
+    }
+
+    /// Get the next whitening byte and advance the LFSR by 8 steps.
+    #[inline]
+    pub fn next_byte(&mut self) -> u8 {
+        let ret = self.state as u8;
+        for _ in 0..8 {
+            let fb = (self.state ^ (self.state >> 5)) & 1;
+            self.state = (self.state >> 1) | (fb << 8);
+        }
+        ret
+    }
+
+    /// Whiten the bytes of `data` in place.
+    pub fn whiten(&mut self, data: &mut [u8]) {
+        for byte in data {
+            *byte ^= self.next_byte();
+        }
+    }
+
+    /// De-whiten the bytes of `data` in place.
+    ///
+    /// This is identical to `Pn9Whitening::whiten()`.
+    #[inline]
+    pub fn dewhiten(&mut self, data: &mut [u8]) {
+        self.whiten(data);
+    }
+}
+
+impl Default for Pn9Whitening {
+    /// Create a new instance with the standard seed `0x1FF`.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// IEEE 802.15.4g (SUN FSK) data whitening.
+///
+/// The 802.15.4g PN9 whitening uses the polynomial `x**9 + x**5 + 1` seeded with all ones.
+/// The sequence is applied to the data bits LSB first.
+/// That is bit exact identical to `Pn9Whitening`.
+pub type Ieee802154Whitening = Pn9Whitening;
+
+/// Bluetooth Low Energy data whitening with the polynomial `x**7 + x**4 + 1`.
+///
+/// The 7 bit LFSR is seeded from the channel index (0 to 39):
+/// Position 0 of the shift register is set to 1
+/// and positions 1 to 6 are set to the channel index bits 5 to 0.
+/// The whitening sequence is applied to the data bits LSB first,
+/// in the order in which they are transmitted on air.
+///
+/// Whitening and de-whitening are the same operation.
+/// Start a new instance for each packet.
+///
+/// ```
+///     use shr3::BleWhitening;
+///
+///     let mut pdu = [0x00, 0x00, 0x00, 0x00];
+///     BleWhitening::new(37).whiten(&mut pdu);
+///     assert_eq!(pdu, [0x8D, 0xD2, 0x57, 0xA1]);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BleWhitening {
+    state: u8,
+}
+
+impl BleWhitening {
+    /// Create a new instance for the channel index `channel`.
+    ///
+    /// Only the lower 6 bits of `channel` are used.
+    #[inline]
+    pub const fn new(channel: u8) -> Self {
+        Self {
+            // Register bit 6 is position 0. Register bit 0 is position 6 (the output).
+            state: (channel & 0x3F) | 0x40,
+        }
+    }
+
+    /// Get the current LFSR state.
+    ///
+    /// Bit 6 is the position 0 and bit 0 is the position 6 of the shift register.
+    #[inline]
+    pub const fn state(&self) -> u8 {
+        self.state
+    }
+
+    /// Get the next whitening byte and advance the LFSR by 8 steps.
+    ///
+    /// The first whitening bit is in the LSB.
+    #[inline]
+    pub fn next_byte(&mut self) -> u8 {
+        let mut ret = 0;
+        for i in 0..8 {
+            if self.state & 1 != 0 {
+                // Feedback into position 0 and position 4.
+                self.state ^= 0x88;
+                ret |= 1 << i;
+            }
+            self.state >>= 1;
+        }
+        ret
+    }
+
+    /// Whiten the bytes of `data` in place.
+    pub fn whiten(&mut self, data: &mut [u8]) {
+        for byte in data {
+            *byte ^= self.next_byte();
+        }
+    }
+
+    /// De-whiten the bytes of `data` in place.
+    ///
+    /// This is identical to `BleWhitening::whiten()`.
+    #[inline]
+    pub fn dewhiten(&mut self, data: &mut [u8]) {
+        self.whiten(data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pn9() {
+        let mut a = Pn9Whitening::new();
+        let exp = [
+            0xFF, 0xE1, 0x1D, 0x9A, 0xED, 0x85, 0x33, 0x24,
+            0xEA, 0x7A, 0xD2, 0x39, 0x70, 0x97, 0x57, 0x0A,
+        ];
+        for x in exp {
+            assert_eq!(a.next_byte(), x);
+        }
+
+        let mut data = [0_u8; 16];
+        Pn9Whitening::default().whiten(&mut data);
+        assert_eq!(data, exp);
+        Pn9Whitening::default().dewhiten(&mut data);
+        assert_eq!(data, [0; 16]);
+
+        // The sequence repeats after 511 bits.
+        let mut a = Pn9Whitening::new();
+        for _ in 0..511 {
+            a.next_byte();
+        }
+        assert_eq!(a.state(), 0x1FF);
+
+        assert_eq!(Pn9Whitening::new_state(0).state(), 0x1FF);
+        assert_eq!(Pn9Whitening::new_state(0x2AA).state(), 0xAA);
+    }
+
+    #[test]
+    fn test_ble() {
+        let mut a = BleWhitening::new(37);
+        for x in [0x8D, 0xD2, 0x57, 0xA1, 0x3D, 0xA7, 0x66, 0xB0] {
+            assert_eq!(a.next_byte(), x);
+        }
+        let mut a = BleWhitening::new(0);
+        assert_eq!(a.next_byte(), 0x40);
+
+        let mut data = [0x55_u8; 40];
+        BleWhitening::new(12).whiten(&mut data);
+        assert!(data.iter().any(|x| *x != 0x55));
+        BleWhitening::new(12).dewhiten(&mut data);
+        assert_eq!(data, [0x55; 40]);
+
+        // The sequence repeats after 127 bits.
+        let mut a = BleWhitening::new(37);
+        for _ in 0..127 {
+            a.next_byte();
+        }
+        assert_eq!(a.state(), BleWhitening::new(37).state());
+
+        assert_eq!(BleWhitening::new(0xFF).state(), 0x7F);
+        assert_eq!(Ieee802154Whitening::new(), Pn9Whitening::new());
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
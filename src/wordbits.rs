@@ -0,0 +1,91 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Bit extraction for generators with 32 bit output words.
+
+/// Buffer of the not yet extracted bits of a 32 bit output word.
+///
+/// The bits are extracted from MSB to LSB.
+/// Therefore, a 32 bit extraction at a word boundary returns the output word unchanged.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct WordBits {
+    word: u32,
+    avail: u8,
+}
+
+impl WordBits {
+    pub(crate) const fn new() -> Self {
+        Self {
+            word: 0,
+            avail: 0,
+        }
+    }
+
+    #[inline]
+    pub(crate) const fn is_empty(&self) -> bool {
+        self.avail == 0
+    }
+
+    #[inline]
+    pub(crate) const fn avail(&self) -> u8 {
+        self.avail
+    }
+
+    #[inline]
+    pub(crate) fn refill(&mut self, word: u32) {
+        self.word = word;
+        self.avail = 32;
+    }
+
+    #[inline]
+    pub(crate) fn pop(&mut self) -> u8 {
+        debug_assert!(self.avail > 0);
+        self.avail -= 1;
+        (self.word >> self.avail) as u8 & 1
+    }
+}
+
+/// Implement `Shr3Ops` for a generator type with a `bits: WordBits` field
+/// and a `fn next_u32(&mut self) -> u32` method.
+macro_rules! impl_shr3_ops_words {
+    ($t:ty) => {
+        impl<T> $crate::Shr3Ops<T> for $t
+            where T: $crate::BaseOps + PartialOrd,
+                  T::U: $crate::BaseOps,
+                  core::num::Wrapping<T::U>: core::ops::Sub<Output=core::num::Wrapping<T::U>>
+                                           + core::ops::Add<Output=core::num::Wrapping<T::U>>
+                                           + PartialOrd
+                                           + core::ops::ShlAssign<usize>
+                                           + core::ops::BitOrAssign,
+        {
+            #[inline]
+            fn get_bits(&mut self, bitcount: u8) -> T {
+                T::from_unsigned($crate::Shr3Ops::<T>::get_bits_unsigned(self, bitcount))
+            }
+
+            fn get_bits_unsigned(&mut self, bitcount: u8) -> core::num::Wrapping<T::U> {
+                debug_assert!(bitcount <= T::NUMBITS);
+                let mut ret = core::num::Wrapping(<T::U as $crate::BaseOps>::from_u8(0));
+                for _ in 0..bitcount {
+                    if self.bits.is_empty() {
+                        let word = self.next_u32();
+                        self.bits.refill(word);
+                    }
+                    ret <<= 1;
+                    ret |= core::num::Wrapping(<T::U as $crate::BaseOps>::from_u8(self.bits.pop()));
+                }
+                ret
+            }
+        }
+    }
+}
+
+pub(crate) use impl_shr3_ops_words;
+
+// vim: ts=4 sw=4 expandtab
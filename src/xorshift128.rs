@@ -0,0 +1,118 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Xorshift128 generator.
+
+use crate::wordbits::{WordBits, impl_shr3_ops_words};
+
+/// Default initial state from "Xorshift RNGs" by George Marsaglia (2003).
+const DEFAULT_STATE: [u32; 4] = [123456789, 362436069, 521288629, 88675123];
+
+/// Xorshift128 generator with four 32 bit words of state.
+///
+/// Algorithm from "Xorshift RNGs" by George Marsaglia (2003).
+/// The generator will loop back to the beginning after `2**128 - 1` output words.
+///
+/// The shuffle function produces one 32 bit output word per step.
+/// `Shr3Ops` extracts the bits of each output word from MSB to LSB.
+/// Therefore, extracting a `u32` at a word boundary returns the unchanged output word.
+///
+/// This generator is *not* cryptographically secure!
+pub struct Xorshift128 {
+    state: [u32; 4],
+    bits: WordBits,
+}
+
+impl Xorshift128 {
+    /// Create a new instance with the default initial state from Marsaglia's paper.
+    #[inline]
+    pub const fn new() -> Xorshift128 {
+        Self::new_state(DEFAULT_STATE)
+    }
+
+    /// Create a new instance with user specified initial state.
+    ///
+    /// Special state 0: The state must not be all zeros. If all zeros are passed to this function,
+    ///                  then the default initial state is picked instead.
+    #[inline]
+    pub const fn new_state(state: [u32; 4]) -> Xorshift128 {
+        let zero = state[0] == 0 && state[1] == 0 && state[2] == 0 && state[3] == 0;
+        Xorshift128 {
+            state: if zero { DEFAULT_STATE } else { state },
+            bits: WordBits::new(),
+        }
+    }
+
+    /// Get the current state.
+    ///
+    /// Bits of the current output word that have not been extracted yet are not part of the state.
+    #[inline]
+    pub const fn state(&self) -> [u32; 4] {
+        self.state
+    }
+
+    /// Advance the generator by one step and get the 32 bit output word.
+    ///
+    /// This bypasses the bit extraction of `Shr3Ops`.
+    #[inline]
+    pub fn next_u32(&mut self) -> u32 {
+        let [x, y, z, w] = self.state;
+        let t = x ^ (x << 11);
+        let out = w ^ (w >> 19) ^ (t ^ (t >> 8));
+        self.state = [y, z, w, out];
+        out
+    }
+}
+
+impl Default for Xorshift128 {
+    /// Create a new instance with the default initial state.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl_shr3_ops_words!(Xorshift128);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shr3Ops;
+
+    #[test]
+    fn test_xorshift128() {
+        let mut a = Xorshift128::new();
+        assert_eq!(a.next_u32(), 3701687786);
+        assert_eq!(a.next_u32(), 458299110);
+        assert_eq!(a.next_u32(), 2500872618);
+
+        let mut a = Xorshift128::new();
+        let x: u32 = a.get();
+        assert_eq!(x, 0xDCA345EA);
+        let x: u8 = a.get();
+        assert_eq!(x, 0x1B);
+        let x: u32 = a.get();
+        assert_eq!(x, 0x5116E695);
+        let x: u8 = a.get_bits(4);
+        assert_eq!(x, 0x1);
+
+        let a = Xorshift128::new_state([0; 4]);
+        assert_eq!(a.state(), DEFAULT_STATE);
+        let a = Xorshift128::new_state([0, 0, 0, 1]);
+        assert_eq!(a.state(), [0, 0, 0, 1]);
+
+        let mut a = Xorshift128::default();
+        for _ in 0..1000 {
+            let x: i32 = a.get_range(-60..170);
+            assert!((-60..170).contains(&x));
+        }
+    }
+}
+
+// vim: ts=4 sw=4 expandtab
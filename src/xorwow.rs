@@ -0,0 +1,125 @@
+// -*- coding: utf-8 -*-
+//
+// Copyright 2022 Michael Büsch <m@bues.ch>
+//
+// Licensed under the Apache License version 2.0
+// or the MIT license, at your option.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+
+//! Xorwow generator.
+
+use crate::wordbits::{WordBits, impl_shr3_ops_words};
+
+/// Default initial xorshift state from "Xorshift RNGs" by George Marsaglia (2003).
+const DEFAULT_STATE: [u32; 5] = [123456789, 362436069, 521288629, 88675123, 5783321];
+
+/// Default initial Weyl counter from "Xorshift RNGs" by George Marsaglia (2003).
+const DEFAULT_COUNTER: u32 = 6615241;
+
+/// Increment of the Weyl counter.
+const WEYL_INCREMENT: u32 = 362437;
+
+/// Xorwow generator: Xorshift with five 32 bit words of state plus a Weyl counter.
+///
+/// Algorithm from "Xorshift RNGs" by George Marsaglia (2003).
+/// The Weyl counter is added to the xorshift output.
+/// That gives a better equidistribution than the plain xorshift output.
+/// The generator will loop back to the beginning after `2**192 - 2**32` output words.
+///
+/// The shuffle function produces one 32 bit output word per step.
+/// `Shr3Ops` extracts the bits of each output word from MSB to LSB.
+/// Therefore, extracting a `u32` at a word boundary returns the unchanged output word.
+///
+/// This generator is *not* cryptographically secure!
+pub struct Xorwow {
+    state: [u32; 5],
+    counter: u32,
+    bits: WordBits,
+}
+
+impl Xorwow {
+    /// Create a new instance with the default initial state from Marsaglia's paper.
+    #[inline]
+    pub const fn new() -> Xorwow {
+        Self::new_state(DEFAULT_STATE, DEFAULT_COUNTER)
+    }
+
+    /// Create a new instance with user specified initial xorshift state and Weyl counter.
+    ///
+    /// Special state 0: The xorshift state must not be all zeros. If all zeros are passed to this function,
+    ///                  then the default initial xorshift state is picked instead.
+    ///                  The counter can have any value.
+    #[inline]
+    pub const fn new_state(state: [u32; 5], counter: u32) -> Xorwow {
+        let zero = state[0] == 0 && state[1] == 0 && state[2] == 0 && state[3] == 0 && state[4] == 0;
+        Xorwow {
+            state: if zero { DEFAULT_STATE } else { state },
+            counter,
+            bits: WordBits::new(),
+        }
+    }
+
+    /// Get the current xorshift state and Weyl counter.
+    ///
+    /// Bits of the current output word that have not been extracted yet are not part of the state.
+    #[inline]
+    pub const fn state(&self) -> ([u32; 5], u32) {
+        (self.state, self.counter)
+    }
+
+    /// Advance the generator by one step and get the 32 bit output word.
+    ///
+    /// This bypasses the bit extraction of `Shr3Ops`.
+    #[inline]
+    pub fn next_u32(&mut self) -> u32 {
+        let [x, y, z, w, v] = self.state;
+        let t = x ^ (x >> 2);
+        let v_next = (v ^ (v << 4)) ^ (t ^ (t << 1));
+        self.state = [y, z, w, v, v_next];
+        self.counter = self.counter.wrapping_add(WEYL_INCREMENT);
+        self.counter.wrapping_add(v_next)
+    }
+}
+
+impl Default for Xorwow {
+    /// Create a new instance with the default initial state.
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl_shr3_ops_words!(Xorwow);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Shr3Ops;
+
+    #[test]
+    fn test_xorwow() {
+        let mut a = Xorwow::new();
+        assert_eq!(a.next_u32(), 0x0EB7_0507);
+        assert_eq!(a.next_u32(), 0xDBF1_0AA0);
+        assert_eq!(a.next_u32(), 0x4B5F_F98D);
+        assert_eq!(a.state().1, DEFAULT_COUNTER.wrapping_add(3 * WEYL_INCREMENT));
+
+        let mut a = Xorwow::new();
+        let x: u32 = a.get();
+        assert_eq!(x, 0x0EB7_0507);
+        let x: u16 = a.get();
+        assert_eq!(x, 0xDBF1);
+
+        let a = Xorwow::new_state([0; 5], 7);
+        assert_eq!(a.state(), (DEFAULT_STATE, 7));
+
+        let mut a = Xorwow::default();
+        for _ in 0..1000 {
+            let x: u8 = a.get_minmax(10, 20);
+            assert!((10..=20).contains(&x));
+        }
+    }
+}
+
+// vim: ts=4 sw=4 expandtab